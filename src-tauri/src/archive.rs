@@ -0,0 +1,118 @@
+//! Builds and reads the single-file compressed tar archive used by
+//! `export_portfolio_archive`/`import_portfolio_archive`: a `manifest.json`
+//! entry (format-versioned metadata plus the portfolio's DB rows) alongside
+//! every file under the portfolio's directory, so the whole thing can be
+//! moved to another machine and unpacked back into the same layout.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use crate::database::{FileVersion, FunderPivotTable, FunderUpload};
+
+/// Bumped whenever the archive layout changes incompatibly.
+/// [`read_archive`] rejects a manifest whose `format_version` doesn't match.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The JSON snapshot stored as `manifest.json` at the archive root.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    pub portfolio_name: String,
+    pub exported_at: DateTime<Utc>,
+    pub file_versions: Vec<FileVersion>,
+    pub funder_uploads: Vec<FunderUpload>,
+    pub funder_pivot_tables: Vec<FunderPivotTable>,
+    /// Each chunked [`FileVersion`]'s ordered chunk-hash manifest, keyed by
+    /// version id, since a `FileVersion` row doesn't carry its own chunk
+    /// list — that lives in `version_chunks` (see `database.rs`).
+    pub version_chunk_manifests: HashMap<String, Vec<String>>,
+}
+
+/// One file to be written into (or read back out of) the archive, alongside
+/// the manifest. `relative_path` is relative to `get_excelerate_dir()`, so
+/// on import it can be joined straight back onto that directory.
+pub struct ArchiveEntry {
+    pub relative_path: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Build a gzip-compressed tar archive containing `manifest.json` at the
+/// root plus every entry under its `relative_path`, returning the archive's
+/// raw bytes for the caller to write to disk.
+pub fn build_archive(manifest: &ArchiveManifest, entries: &[ArchiveEntry]) -> Result<Vec<u8>, String> {
+    let manifest_json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize archive manifest: {}", e))?;
+
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    append_bytes(&mut builder, "manifest.json", &manifest_json)?;
+    for entry in entries {
+        append_bytes(&mut builder, &entry.relative_path, &entry.bytes)?;
+    }
+
+    let encoder = builder
+        .into_inner()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress archive: {}", e))
+}
+
+fn append_bytes<W: Write>(builder: &mut tar::Builder<W>, path: &str, bytes: &[u8]) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, bytes)
+        .map_err(|e| format!("Failed to add {} to archive: {}", path, e))
+}
+
+/// Parse a gzip-compressed tar archive's bytes back into its manifest and
+/// file entries, rejecting a `format_version` this build doesn't recognize.
+pub fn read_archive(archive_bytes: &[u8]) -> Result<(ArchiveManifest, Vec<ArchiveEntry>), String> {
+    let decoder = flate2::read::GzDecoder::new(archive_bytes);
+    let mut tar_reader = tar::Archive::new(decoder);
+
+    let mut manifest: Option<ArchiveManifest> = None;
+    let mut entries = Vec::new();
+
+    for entry_result in tar_reader
+        .entries()
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+    {
+        let mut entry = entry_result.map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid archive entry path: {}", e))?
+            .to_string_lossy()
+            .to_string();
+
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+        if path == "manifest.json" {
+            manifest = Some(
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| format!("Failed to parse archive manifest: {}", e))?,
+            );
+        } else {
+            entries.push(ArchiveEntry { relative_path: path, bytes });
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| "Archive is missing manifest.json".to_string())?;
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported archive format version {} (this build supports {})",
+            manifest.format_version, FORMAT_VERSION
+        ));
+    }
+
+    Ok((manifest, entries))
+}
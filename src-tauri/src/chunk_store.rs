@@ -0,0 +1,85 @@
+//! Content-defined chunking for the workbook version store: splits a byte
+//! buffer into variable-length chunks at data-dependent boundaries (so
+//! near-identical workbooks share most of their chunks instead of differing
+//! byte-for-byte), and hashes each chunk for content-addressed storage.
+//!
+//! `database.rs` persists the ordered manifest of chunk hashes per version
+//! and their refcounts; `file_handler.rs` owns the actual chunk files on
+//! disk under each portfolio's `Workbook/.chunks/` directory.
+
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+
+/// Bytes of rolling-hash history considered when deciding a chunk boundary,
+/// per the Rabin/FastCDC-style sliding window.
+const WINDOW_SIZE: usize = 64;
+/// A chunk never ends before this size (except for the final chunk of the
+/// input), so small high-frequency boundaries don't fragment storage.
+const MIN_CHUNK_SIZE: usize = 4 * 1024;
+/// A chunk is always cut at this size even without a hash-boundary match, to
+/// bound worst-case chunk size for pathological (very repetitive) input.
+const MAX_CHUNK_SIZE: usize = 32 * 1024;
+/// Low bits checked against zero for a boundary match. 13 bits gives a
+/// boundary roughly every 2^13 = 8192 bytes on average, landing inside the
+/// ~8-16 KB target range once combined with `MIN_CHUNK_SIZE`.
+const BOUNDARY_MASK_BITS: u32 = 13;
+/// Rolling polynomial hash base; arbitrary odd constant, chosen only so the
+/// hash mixes bits well — not a cryptographic property.
+const ROLLING_BASE: u64 = 1_099_511_628_211;
+
+/// Split `data` into content-defined chunks. Returns borrowed slices in
+/// order; callers hash and store each one independently.
+pub fn split_into_chunks(data: &[u8]) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    // Precompute base^(WINDOW_SIZE - 1) so the oldest byte's contribution
+    // can be subtracted back out as it slides out of the window, giving a
+    // true rolling hash over the last WINDOW_SIZE bytes rather than the
+    // whole prefix.
+    let mut base_pow: u64 = 1;
+    for _ in 0..WINDOW_SIZE.saturating_sub(1) {
+        base_pow = base_pow.wrapping_mul(ROLLING_BASE);
+    }
+
+    let mask: u64 = (1u64 << BOUNDARY_MASK_BITS) - 1;
+
+    let mut chunks = Vec::new();
+    let mut chunk_start = 0usize;
+    let mut hash: u64 = 0;
+    let mut window: VecDeque<u8> = VecDeque::with_capacity(WINDOW_SIZE);
+
+    for (i, &byte) in data.iter().enumerate() {
+        if window.len() == WINDOW_SIZE {
+            let oldest = window.pop_front().unwrap();
+            hash = hash.wrapping_sub((oldest as u64).wrapping_mul(base_pow));
+        }
+        hash = hash.wrapping_mul(ROLLING_BASE).wrapping_add(byte as u64);
+        window.push_back(byte);
+
+        let chunk_len = i + 1 - chunk_start;
+        let hash_boundary = chunk_len >= MIN_CHUNK_SIZE && hash & mask == 0;
+        let forced_boundary = chunk_len >= MAX_CHUNK_SIZE;
+
+        if hash_boundary || forced_boundary {
+            chunks.push(&data[chunk_start..=i]);
+            chunk_start = i + 1;
+            hash = 0;
+            window.clear();
+        }
+    }
+
+    if chunk_start < data.len() {
+        chunks.push(&data[chunk_start..]);
+    }
+
+    chunks
+}
+
+/// Hex-encoded SHA-256 of a single chunk, used as its content-addressed key.
+pub fn hash_chunk(chunk: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(chunk);
+    format!("{:x}", hasher.finalize())
+}
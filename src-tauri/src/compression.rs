@@ -0,0 +1,32 @@
+//! Transparent zstd compression for stored funder-upload CSV blobs and
+//! Clear View pivot CSVs. Every write site that opts in calls [`compress`];
+//! every read site calls [`decompress_if_needed`] unconditionally, since it
+//! detects zstd's own frame magic number rather than trusting a flag, so
+//! blobs written before compression was enabled for a portfolio keep
+//! reading back exactly as before.
+
+/// zstd's frame magic number (little-endian `0xFD2FB528`). Checking for this
+/// directly means we don't need our own wrapper format or a DB round-trip
+/// just to tell a compressed blob from a plain one.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Whether `data` starts with a zstd frame header.
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.len() >= ZSTD_MAGIC.len() && data[..ZSTD_MAGIC.len()] == ZSTD_MAGIC
+}
+
+/// zstd-compress `data` at `level`.
+pub fn compress(data: &[u8], level: i32) -> std::io::Result<Vec<u8>> {
+    zstd::stream::encode_all(data, level)
+}
+
+/// Inflate `data` if it's a zstd frame, otherwise return it unchanged —
+/// the single entry point every read site should call instead of branching
+/// on a stored codec column itself.
+pub fn decompress_if_needed(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    if is_compressed(data) {
+        zstd::stream::decode_all(data)
+    } else {
+        Ok(data.to_vec())
+    }
+}
@@ -1,7 +1,11 @@
 use rusqlite::{Connection, Result, params, OptionalExtension};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Serialize, Deserialize};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FileVersion {
@@ -14,6 +18,247 @@ pub struct FileVersion {
     pub file_size: i64,
     pub upload_timestamp: DateTime<Utc>,
     pub is_active: bool,
+    pub content_sha256: Option<String>,
+    pub content_md5: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Metadata for one generated pivot table CSV (daily/weekly/combined,
+/// depending on the funder's parser), keyed back to the [`FunderUpload`] it
+/// was built from via `upload_id`. The CSV itself lives on disk at
+/// `pivot_file_path`; this row is what export/import archives and list views
+/// work from without re-parsing it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FunderPivotTable {
+    pub id: String,
+    pub upload_id: String,
+    pub portfolio_name: String,
+    pub funder_name: String,
+    pub report_date: String,
+    pub upload_type: String,
+    pub pivot_file_path: String,
+    pub total_gross: f64,
+    pub total_fee: f64,
+    pub total_net: f64,
+    pub row_count: i32,
+    pub created_timestamp: DateTime<Utc>,
+}
+
+/// Write-ahead intent record for an atomic Clear View pivot delete-and-regenerate.
+/// `temp_path`/`final_path` are `None` for a pure deletion (no replacement
+/// pivot); otherwise a new CSV has already been written to `temp_path` and is
+/// waiting to be renamed into `final_path`. `stale_pivot_ids` are the
+/// `funder_pivot_tables` rows (by id) the commit will remove; `new_pivot_metadata`
+/// is the JSON-encoded [`FunderPivotTable`] row the commit will insert, if any.
+/// See [`Database::insert_pending_pivot_swap`] and [`Database::commit_pivot_swap`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingPivotSwap {
+    pub id: String,
+    pub portfolio_name: String,
+    pub report_date: String,
+    pub temp_path: Option<String>,
+    pub final_path: Option<String>,
+    pub stale_pivot_ids: Vec<String>,
+    pub new_pivot_metadata: Option<FunderPivotTable>,
+    pub created_timestamp: DateTime<Utc>,
+}
+
+/// Write-ahead intent record for `delete_clearview_file`'s upload deletion
+/// itself, recorded *before* `delete_funder_upload` runs. A [`PendingPivotSwap`]
+/// only covers the pivot-regeneration step that follows; without this record,
+/// a crash right after the upload is deleted but before the regenerate-or-remove
+/// step finishes would leave no durable trace for recovery to act on. See
+/// [`Database::insert_pending_clearview_deletion`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingClearviewDeletion {
+    pub id: String,
+    pub upload_id: String,
+    pub portfolio_name: String,
+    pub report_date: String,
+    pub is_daily: bool,
+    pub created_timestamp: DateTime<Utc>,
+}
+
+/// One pivot CSV's outcome from [`Database::plan_clearview_pivot_prune`]:
+/// either slated for removal, or kept and annotated with the reason —
+/// `"last"`/`"daily"`/`"weekly"`/`"monthly"` for a [`RetentionPolicy`] bucket,
+/// `"combined-dependency"` for a daily pivot force-kept because a combined
+/// pivot for its report date still depends on it, or `"unparseable-date"` for a pivot retention can't safely bucket.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PivotPruneDecision {
+    pub pivot: FunderPivotTable,
+    pub removed: bool,
+    pub kept_by: Option<String>,
+}
+
+/// Where a [`Job`] is in its lifecycle. Only `Pending`/`InProgress` are
+/// non-terminal — [`Database::get_incomplete_jobs`] looks for those after a
+/// crash or restart.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::InProgress => "in_progress",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    fn from_str(s: &str) -> rusqlite::Result<Self> {
+        match s {
+            "pending" => Ok(JobStatus::Pending),
+            "in_progress" => Ok(JobStatus::InProgress),
+            "completed" => Ok(JobStatus::Completed),
+            "failed" => Ok(JobStatus::Failed),
+            "cancelled" => Ok(JobStatus::Cancelled),
+            other => Err(rusqlite::Error::InvalidColumnType(
+                0,
+                format!("unknown job status: {}", other),
+                rusqlite::types::Type::Text,
+            )),
+        }
+    }
+}
+
+/// A persisted record of one long-running, multi-stage operation (today only
+/// `process_clearview_daily_pivot`'s daily/combined pivot regeneration),
+/// so a crash mid-operation leaves a row the app can find and surface on
+/// next launch instead of silently losing track of the half-finished work.
+/// `stage` is a free-form, job-type-specific label (e.g.
+/// `"storing_daily_metadata"`) set by whichever command is driving the job,
+/// purely for display and diagnostics — it isn't interpreted by the
+/// database layer. See [`crate::jobs`] for the orchestration helpers that
+/// create and advance one of these around an operation's stages.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Job {
+    pub id: String,
+    pub job_type: String,
+    pub portfolio_name: String,
+    pub report_date: String,
+    pub stage: String,
+    pub status: JobStatus,
+    pub error: Option<String>,
+    pub created_timestamp: DateTime<Utc>,
+    pub updated_timestamp: DateTime<Utc>,
+}
+
+/// A confirmed `(funder, normalized header) -> canonical field` mapping,
+/// learned once (by a scoring match or user confirmation) and then applied
+/// deterministically on every later import instead of being re-scored.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnMapping {
+    pub funder_name: String,
+    pub normalized_header: String,
+    pub field: String,
+    pub confidence: f64,
+    pub updated_timestamp: DateTime<Utc>,
+}
+
+/// Whether an `advance_ledger` event opens a new FIFO lot (`Funding`) or
+/// consumes existing lots oldest-first (`Collection`).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerEventType {
+    Funding,
+    Collection,
+}
+
+impl LedgerEventType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LedgerEventType::Funding => "funding",
+            LedgerEventType::Collection => "collection",
+        }
+    }
+
+    fn from_str(s: &str) -> rusqlite::Result<Self> {
+        match s {
+            "funding" => Ok(LedgerEventType::Funding),
+            "collection" => Ok(LedgerEventType::Collection),
+            other => Err(rusqlite::Error::InvalidColumnType(
+                0,
+                format!("unknown ledger event type: {}", other),
+                rusqlite::types::Type::Text,
+            )),
+        }
+    }
+}
+
+/// A single funding or collection event against one `advance_id`, used to
+/// drive FIFO lot matching in [`Database::advance_balance`] and
+/// [`Database::realized_gains`]. A `Funding` event opens a lot of `amount`
+/// at cost basis `buy_rate` (a fraction of face value); a `Collection`
+/// event consumes the oldest open lots first, and any cash collected above
+/// their cost basis is a realized gain.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AdvanceLedgerEvent {
+    pub id: String,
+    pub advance_id: String,
+    pub portfolio_name: String,
+    pub event_date: NaiveDate,
+    pub event_type: LedgerEventType,
+    pub amount: Decimal,
+    /// Cost basis as a fraction of face value; only meaningful for `Funding` events.
+    pub buy_rate: Option<Decimal>,
+}
+
+/// A FIFO lot still carrying outstanding face amount, oldest-first.
+struct Lot {
+    remaining: Decimal,
+    buy_rate: Decimal,
+}
+
+/// One parsed portfolio-workbook row: a single merchant's funding record for
+/// a given funder, as extracted by `PortfolioParser`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Merchant {
+    pub id: String,
+    pub portfolio_name: String,
+    pub funder_name: String,
+    pub date_funded: Option<String>,
+    pub merchant_name: String,
+    pub website: Option<String>,
+    pub advance_id: Option<String>,
+    pub funder_advance_id: Option<String>,
+    pub industry_naics_or_sic: Option<String>,
+    pub state: Option<String>,
+    pub fico: Option<String>,
+    pub buy_rate: Option<f64>,
+    pub commission: Option<f64>,
+    pub total_amount_funded: Option<f64>,
+    pub created_timestamp: DateTime<Utc>,
+    pub updated_timestamp: DateTime<Utc>,
+}
+
+/// One funder's share of a portfolio's total funding over a reporting window.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FunderFundingShare {
+    pub funder_name: String,
+    pub total_funded: Decimal,
+    pub share: f64,
+}
+
+/// Result of [`Database::portfolio_funding_summary`]: daily-average funding
+/// (normalized over the days actually spanned by the data, not the raw row
+/// count) plus a simple extrapolation of that average across the requested
+/// window, and each funder's share of the total.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FundingSummary {
+    pub portfolio_name: String,
+    pub elapsed_days: i64,
+    pub total_funded: Decimal,
+    pub daily_average: Decimal,
+    pub projected_total: Decimal,
+    pub funder_shares: Vec<FunderFundingShare>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,372 +273,2693 @@ pub struct FunderUpload {
     pub file_path: String,
     pub file_size: i64,
     pub upload_timestamp: DateTime<Utc>,
+    pub content_sha256: Option<String>,
+    pub content_md5: Option<String>,
+    /// Compression codec the stored blob was written with (currently only
+    /// `"zstd"`), or `None` if it's stored raw. Set by `write_funder_blob`
+    /// based on the portfolio's [`CompressionConfig`].
+    pub codec: Option<String>,
+    /// On-disk size of the stored blob when `codec` is set, vs. `file_size`
+    /// which always holds the logical (uncompressed) size.
+    pub compressed_size: Option<i64>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Per-portfolio toggle and level for compressing newly-written funder
+/// upload blobs (see `compression.rs`). A portfolio with no row here gets
+/// [`CompressionConfig::default`] (disabled).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    pub level: i32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        CompressionConfig {
+            enabled: false,
+            level: 3,
+        }
+    }
+}
+
+/// Proxmox-style bucketed retention counts for [`Database::prune_versions`].
+/// Each `keep_*` field caps how many versions survive as the newest
+/// representative of that time bucket (day/ISO week/month/year); `keep_last`
+/// additionally force-keeps the N most recent versions regardless of bucket.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RetentionPolicy {
+    pub keep_last: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+    pub keep_yearly: usize,
+}
+
+impl RetentionPolicy {
+    /// A policy where every count is zero would delete the entire history;
+    /// [`Database::prune_versions`] refuses to run such a policy.
+    pub fn keeps_something(&self) -> bool {
+        self.keep_last > 0
+            || self.keep_daily > 0
+            || self.keep_weekly > 0
+            || self.keep_monthly > 0
+            || self.keep_yearly > 0
+    }
 }
 
 pub struct Database {
     conn: Connection,
 }
 
+/// Connection-level tuning applied by [`Database::new`]. The single global
+/// `Mutex<Option<Database>>` in `file_handler.rs` already serializes access
+/// within this process, but SQLite's own file locking still needs to
+/// tolerate a concurrent writer (e.g. a background pivot job) overlapping
+/// with an external reader, so every connection gets WAL mode and a busy
+/// timeout rather than failing immediately with `SQLITE_BUSY`.
+pub struct ConnectionOptions {
+    /// How long SQLite itself will wait for a lock to clear before
+    /// returning `SQLITE_BUSY`, via `PRAGMA busy_timeout`.
+    pub busy_timeout_ms: u32,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        ConnectionOptions {
+            busy_timeout_ms: 5_000,
+        }
+    }
+}
+
+/// How many times [`retry_on_busy`] re-runs a write before giving up.
+const BUSY_RETRY_ATTEMPTS: u32 = 5;
+/// Base delay between retries, multiplied by the attempt number so each
+/// retry backs off a little further than the last.
+const BUSY_RETRY_DELAY_MS: u64 = 50;
+
+/// Re-run `f` if SQLite reports the database is locked or busy, sleeping a
+/// short, increasing delay between attempts. `PRAGMA busy_timeout` already
+/// makes SQLite block internally before surfacing this error; this is a
+/// second, coarser layer for the rare case that a write still loses the
+/// race under heavy contention (e.g. a version write and a pivot-metadata
+/// write landing at the same moment).
+fn retry_on_busy<T>(mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if attempt < BUSY_RETRY_ATTEMPTS
+                    && (err.code == rusqlite::ErrorCode::DatabaseBusy
+                        || err.code == rusqlite::ErrorCode::DatabaseLocked) =>
+            {
+                attempt += 1;
+                std::thread::sleep(std::time::Duration::from_millis(
+                    BUSY_RETRY_DELAY_MS * attempt as u64,
+                ));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// One versioned schema upgrade step, applied by [`Database::migrate`] when
+/// its `version` exceeds the database's current `PRAGMA user_version`. Steps
+/// run oldest-first inside a single transaction, so write every step as
+/// idempotent SQL (`IF NOT EXISTS` / `ADD COLUMN` guarded appropriately) —
+/// a step must be safe to re-run if a later step in the same upgrade fails.
+struct Migration {
+    version: i32,
+    run: fn(&Connection) -> Result<()>,
+}
+
+/// The full, ordered migration history. Append new steps with the next
+/// integer version; never edit or reorder an existing one, since databases
+/// in the field may already be stamped past it.
+fn migrations() -> Vec<Migration> {
+    vec![
+        Migration { version: 1, run: migrate_v1_initial_schema },
+        Migration { version: 2, run: migrate_v2_content_hashes },
+        Migration { version: 3, run: migrate_v3_soft_delete },
+        Migration { version: 4, run: migrate_v4_chunk_store },
+        Migration { version: 5, run: migrate_v5_retention_policies },
+        Migration { version: 6, run: migrate_v6_funder_pivot_tables },
+        Migration { version: 7, run: migrate_v7_compression },
+        Migration { version: 8, run: migrate_v8_pending_pivot_swaps },
+        Migration { version: 9, run: migrate_v9_jobs },
+        Migration { version: 10, run: migrate_v10_pending_clearview_deletions },
+        Migration { version: 11, run: migrate_v11_ledger_event_dedup },
+    ]
+}
+
+fn migrate_v1_initial_schema(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS file_versions (
+            id TEXT PRIMARY KEY,
+            portfolio_name TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            original_filename TEXT NOT NULL,
+            version_filename TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            upload_timestamp TEXT NOT NULL,
+            is_active BOOLEAN DEFAULT 0
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_portfolio_date
+         ON file_versions(portfolio_name, report_date)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_report_date
+         ON file_versions(report_date)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_active
+         ON file_versions(is_active)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS funder_uploads (
+            id TEXT PRIMARY KEY,
+            portfolio_name TEXT NOT NULL,
+            funder_name TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            upload_type TEXT NOT NULL,
+            original_filename TEXT NOT NULL,
+            stored_filename TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            upload_timestamp TEXT NOT NULL,
+            UNIQUE(portfolio_name, funder_name, report_date, upload_type)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_funder_portfolio_date
+         ON funder_uploads(portfolio_name, funder_name, report_date)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS column_mappings (
+            funder_name TEXT NOT NULL,
+            normalized_header TEXT NOT NULL,
+            field TEXT NOT NULL,
+            confidence REAL NOT NULL,
+            updated_timestamp TEXT NOT NULL,
+            PRIMARY KEY (funder_name, normalized_header)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS advance_ledger (
+            id TEXT PRIMARY KEY,
+            advance_id TEXT NOT NULL,
+            portfolio_name TEXT NOT NULL,
+            event_date TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            buy_rate TEXT
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_ledger_advance
+         ON advance_ledger(advance_id, event_date)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_ledger_portfolio
+         ON advance_ledger(portfolio_name, event_date)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS merchants (
+            id TEXT PRIMARY KEY,
+            portfolio_name TEXT NOT NULL,
+            funder_name TEXT NOT NULL,
+            date_funded TEXT,
+            merchant_name TEXT NOT NULL,
+            website TEXT,
+            advance_id TEXT,
+            funder_advance_id TEXT,
+            industry_naics_or_sic TEXT,
+            state TEXT,
+            fico TEXT,
+            buy_rate REAL,
+            commission REAL,
+            total_amount_funded REAL,
+            created_timestamp TEXT NOT NULL,
+            updated_timestamp TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_merchants_portfolio
+         ON merchants(portfolio_name, date_funded)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Whether `table` already has a column named `column`, so an `ALTER TABLE
+/// ADD COLUMN` step (SQLite has no `ADD COLUMN IF NOT EXISTS`) can be
+/// skipped if it's somehow already been applied.
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+fn migrate_v2_content_hashes(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "file_versions", "content_sha256")? {
+        conn.execute("ALTER TABLE file_versions ADD COLUMN content_sha256 TEXT", [])?;
+    }
+    if !column_exists(conn, "file_versions", "content_md5")? {
+        conn.execute("ALTER TABLE file_versions ADD COLUMN content_md5 TEXT", [])?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_file_versions_sha256 ON file_versions(content_sha256)",
+        [],
+    )?;
+
+    if !column_exists(conn, "funder_uploads", "content_sha256")? {
+        conn.execute("ALTER TABLE funder_uploads ADD COLUMN content_sha256 TEXT", [])?;
+    }
+    if !column_exists(conn, "funder_uploads", "content_md5")? {
+        conn.execute("ALTER TABLE funder_uploads ADD COLUMN content_md5 TEXT", [])?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_funder_uploads_sha256 ON funder_uploads(content_sha256)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migrate_v3_soft_delete(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "file_versions", "deleted_at")? {
+        conn.execute("ALTER TABLE file_versions ADD COLUMN deleted_at TEXT", [])?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_file_versions_deleted_at ON file_versions(deleted_at)",
+        [],
+    )?;
+
+    if !column_exists(conn, "funder_uploads", "deleted_at")? {
+        conn.execute("ALTER TABLE funder_uploads ADD COLUMN deleted_at TEXT", [])?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_funder_uploads_deleted_at ON funder_uploads(deleted_at)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Schema for the chunk store (see `chunk_store.rs`): `version_chunks` holds
+/// each version's ordered manifest of chunk hashes, and `chunk_refs` counts
+/// how many manifests reference each `(portfolio_name, chunk_hash)` pair so
+/// the last reference going away can trigger garbage collection of the
+/// on-disk chunk file. Refcounts are scoped per portfolio, mirroring
+/// [`Database::find_funder_upload_by_hash`]'s reasoning that the same bytes
+/// in two different portfolios aren't meaningfully "the same" stored object.
+fn migrate_v4_chunk_store(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS version_chunks (
+            version_id TEXT NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            chunk_hash TEXT NOT NULL,
+            PRIMARY KEY (version_id, chunk_index)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_version_chunks_hash ON version_chunks(chunk_hash)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS chunk_refs (
+            portfolio_name TEXT NOT NULL,
+            chunk_hash TEXT NOT NULL,
+            refcount INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (portfolio_name, chunk_hash)
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// One configured [`RetentionPolicy`] per portfolio, consulted by
+/// [`Database::run_retention`]. A portfolio with no row here has no
+/// automatic pruning — the upload-triggered sweep is a no-op for it.
+fn migrate_v5_retention_policies(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS retention_policies (
+            portfolio_name TEXT PRIMARY KEY,
+            keep_last INTEGER NOT NULL,
+            keep_daily INTEGER NOT NULL,
+            keep_weekly INTEGER NOT NULL,
+            keep_monthly INTEGER NOT NULL,
+            keep_yearly INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Backs [`FunderPivotTable`]; this table already had two call sites
+/// (`file_handler.rs`'s `save_funder_upload` and
+/// `clearview_pivot_processor.rs`'s `store_pivot_metadata`) inserting into it
+/// before the schema existed to receive them.
+fn migrate_v6_funder_pivot_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS funder_pivot_tables (
+            id TEXT PRIMARY KEY,
+            upload_id TEXT NOT NULL,
+            portfolio_name TEXT NOT NULL,
+            funder_name TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            upload_type TEXT NOT NULL,
+            pivot_file_path TEXT NOT NULL,
+            total_gross REAL NOT NULL,
+            total_fee REAL NOT NULL,
+            total_net REAL NOT NULL,
+            row_count INTEGER NOT NULL,
+            created_timestamp TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_funder_pivot_tables_portfolio
+         ON funder_pivot_tables(portfolio_name, report_date)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Adds the `codec`/`compressed_size` columns `write_funder_blob` stamps on
+/// every insert, and a `compression_configs` table mirroring
+/// `retention_policies`' one-row-per-portfolio shape.
+fn migrate_v7_compression(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "funder_uploads", "codec")? {
+        conn.execute("ALTER TABLE funder_uploads ADD COLUMN codec TEXT", [])?;
+    }
+    if !column_exists(conn, "funder_uploads", "compressed_size")? {
+        conn.execute("ALTER TABLE funder_uploads ADD COLUMN compressed_size INTEGER", [])?;
+    }
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS compression_configs (
+            portfolio_name TEXT PRIMARY KEY,
+            enabled BOOLEAN NOT NULL,
+            level INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Backs [`PendingPivotSwap`]: the write-ahead intent record a Clear View
+/// delete-and-regenerate leaves behind while its new pivot CSV is staged at a
+/// temp path, so a crash between the temp write and the final DB commit can
+/// be rolled forward or back on the next startup instead of leaving the
+/// filesystem and `funder_pivot_tables` permanently disagreeing.
+fn migrate_v8_pending_pivot_swaps(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pending_pivot_swaps (
+            id TEXT PRIMARY KEY,
+            portfolio_name TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            temp_path TEXT,
+            final_path TEXT,
+            stale_pivot_ids TEXT NOT NULL,
+            new_pivot_metadata TEXT,
+            created_timestamp TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migrate_v9_jobs(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS jobs (
+            id TEXT PRIMARY KEY,
+            job_type TEXT NOT NULL,
+            portfolio_name TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            stage TEXT NOT NULL,
+            status TEXT NOT NULL,
+            error TEXT,
+            created_timestamp TEXT NOT NULL,
+            updated_timestamp TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Backs [`PendingClearviewDeletion`]: the write-ahead intent `delete_clearview_file`
+/// records before it deletes the funder upload, so a crash between that
+/// deletion and the pivot regenerate-or-remove step that follows it still
+/// leaves something for `recover_pending_clearview_deletions` to finish.
+fn migrate_v10_pending_clearview_deletions(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pending_clearview_deletions (
+            id TEXT PRIMARY KEY,
+            upload_id TEXT NOT NULL,
+            portfolio_name TEXT NOT NULL,
+            report_date TEXT NOT NULL,
+            is_daily BOOLEAN NOT NULL,
+            created_timestamp TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Makes [`Database::insert_ledger_event`] idempotent on replay: without a
+/// uniqueness constraint, re-running `record_weekly_collections` for the
+/// same advance/date/event type (e.g. a recovered `PendingClearviewDeletion`
+/// regenerating a pivot whose ledger events were already recorded before a
+/// crash) inserts a second `Collection` row and silently doubles the advance's
+/// realized total.
+fn migrate_v11_ledger_event_dedup(conn: &Connection) -> Result<()> {
+    // A pre-existing database may already hold duplicate rows from before
+    // this fix (insert_ledger_event had no uniqueness guard); collapse each
+    // duplicate group down to its most recently inserted row before the
+    // unique index below can enforce it, or CREATE UNIQUE INDEX fails outright.
+    conn.execute(
+        "DELETE FROM advance_ledger
+         WHERE rowid NOT IN (
+             SELECT MAX(rowid) FROM advance_ledger
+             GROUP BY advance_id, portfolio_name, event_date, event_type
+         )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_ledger_dedup
+         ON advance_ledger(advance_id, portfolio_name, event_date, event_type)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Parse a pivot's `report_date` for bucketing in
+/// [`Database::plan_clearview_pivot_prune`], accepting the same `YYYY-MM-DD`
+/// / `MM/DD/YYYY` shapes `ClearViewPivotProcessor` does elsewhere. `None` for
+/// anything else, so retention can refuse to touch a pivot it can't
+/// confidently place in a time bucket rather than guess.
+fn parse_pivot_report_date(report_date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(report_date, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(report_date, "%m/%d/%Y"))
+        .or_else(|_| NaiveDate::parse_from_str(&report_date.replace('-', "/"), "%m/%d/%Y"))
+        .ok()
+}
+
+/// Hash a file's raw bytes for content-addressed dedup: a hex-encoded
+/// SHA-256 (used for lookups) alongside an MD5 (kept only as a cheap
+/// secondary check, per the two-hash convention this table follows).
+pub fn hash_content(bytes: &[u8]) -> (String, String) {
+    use sha2::{Digest, Sha256};
+
+    let mut sha256 = Sha256::new();
+    sha256.update(bytes);
+    let sha256_hex = format!("{:x}", sha256.finalize());
+
+    let md5_hex = format!("{:x}", md5::compute(bytes));
+
+    (sha256_hex, md5_hex)
+}
+
 impl Database {
     pub fn new(db_path: &PathBuf) -> Result<Self> {
+        Self::new_with_options(db_path, ConnectionOptions::default())
+    }
+
+    /// Like [`Database::new`], but with explicit [`ConnectionOptions`]
+    /// instead of the defaults — mainly for tests that want a tighter busy
+    /// timeout than production.
+    pub fn new_with_options(db_path: &PathBuf, options: ConnectionOptions) -> Result<Self> {
         let conn = Connection::open(db_path)?;
-        
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS file_versions (
-                id TEXT PRIMARY KEY,
-                portfolio_name TEXT NOT NULL,
-                report_date TEXT NOT NULL,
-                original_filename TEXT NOT NULL,
-                version_filename TEXT NOT NULL,
-                file_path TEXT NOT NULL,
-                file_size INTEGER NOT NULL,
-                upload_timestamp TEXT NOT NULL,
-                is_active BOOLEAN DEFAULT 0
-            )",
-            [],
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "busy_timeout", options.busy_timeout_ms)?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        let db = Database { conn };
+        db.migrate()?;
+        Ok(db)
+    }
+
+    /// Apply every migration step whose version exceeds the schema's
+    /// current `PRAGMA user_version`, inside one transaction, bumping
+    /// `user_version` as each step lands. A failing step rolls back the
+    /// whole upgrade rather than leaving the schema half-applied.
+    pub fn migrate(&self) -> Result<()> {
+        let current_version: i32 =
+            self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        let pending: Vec<Migration> = migrations()
+            .into_iter()
+            .filter(|m| m.version > current_version)
+            .collect();
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut applied_version = current_version;
+
+        for migration in pending {
+            (migration.run)(&tx)?;
+            applied_version = migration.version;
+        }
+
+        tx.pragma_update(None, "user_version", applied_version)?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Look up a previously confirmed column mapping for this funder, so a
+    /// header that scored ambiguously (or was hand-confirmed) once doesn't
+    /// need to be re-scored on every later import.
+    pub fn get_column_mapping(&self, funder_name: &str, normalized_header: &str) -> Result<Option<String>> {
+        self.conn.query_row(
+            "SELECT field FROM column_mappings WHERE funder_name = ?1 AND normalized_header = ?2",
+            params![funder_name, normalized_header],
+            |row| row.get(0),
+        ).optional()
+    }
+
+    /// Snapshot every confirmed `(funder, normalized header) -> field`
+    /// mapping in one query, so a caller that needs to parse several
+    /// workbooks in parallel can look mappings up from memory instead of
+    /// round-tripping through `get_column_mapping` (and the lock guarding
+    /// this connection) per header.
+    pub fn get_all_column_mappings(&self) -> Result<HashMap<(String, String), String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT funder_name, normalized_header, field FROM column_mappings",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(((row.get::<_, String>(0)?, row.get::<_, String>(1)?), row.get::<_, String>(2)?))
+        })?;
+
+        let mut mappings = HashMap::new();
+        for row in rows {
+            let (key, field) = row?;
+            mappings.insert(key, field);
+        }
+        Ok(mappings)
+    }
+
+    /// Record (or update) a confirmed `(funder, normalized header) -> field`
+    /// mapping.
+    pub fn upsert_column_mapping(
+        &self,
+        funder_name: &str,
+        normalized_header: &str,
+        field: &str,
+        confidence: f64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO column_mappings (funder_name, normalized_header, field, confidence, updated_timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(funder_name, normalized_header)
+             DO UPDATE SET field = excluded.field, confidence = excluded.confidence, updated_timestamp = excluded.updated_timestamp",
+            params![funder_name, normalized_header, field, confidence, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Record a funding or collection event against an advance's FIFO ledger.
+    /// Replaying the same advance/portfolio/date/event type (e.g. a recovered
+    /// pivot regeneration re-deriving the same week's collection) updates the
+    /// existing row in place instead of inserting a duplicate.
+    pub fn insert_ledger_event(&self, event: &AdvanceLedgerEvent) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO advance_ledger (id, advance_id, portfolio_name, event_date, event_type, amount, buy_rate)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(advance_id, portfolio_name, event_date, event_type)
+             DO UPDATE SET amount = excluded.amount, buy_rate = excluded.buy_rate",
+            params![
+                event.id,
+                event.advance_id,
+                event.portfolio_name,
+                event.event_date.format("%Y-%m-%d").to_string(),
+                event.event_type.as_str(),
+                event.amount.to_string(),
+                event.buy_rate.map(|r| r.to_string()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// All ledger events for one advance, ordered oldest-first.
+    fn get_ledger_events(&self, advance_id: &str) -> Result<Vec<AdvanceLedgerEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, advance_id, portfolio_name, event_date, event_type, amount, buy_rate
+             FROM advance_ledger
+             WHERE advance_id = ?1
+             ORDER BY event_date ASC"
+        )?;
+
+        let events = stmt.query_map(params![advance_id], |row| {
+            let event_date: String = row.get(3)?;
+            let event_type: String = row.get(4)?;
+            let amount: String = row.get(5)?;
+            let buy_rate: Option<String> = row.get(6)?;
+
+            Ok(AdvanceLedgerEvent {
+                id: row.get(0)?,
+                advance_id: row.get(1)?,
+                portfolio_name: row.get(2)?,
+                event_date: NaiveDate::parse_from_str(&event_date, "%Y-%m-%d").unwrap(),
+                event_type: LedgerEventType::from_str(&event_type)?,
+                amount: Decimal::from_str(&amount).unwrap_or(Decimal::ZERO),
+                buy_rate: buy_rate.and_then(|r| Decimal::from_str(&r).ok()),
+            })
+        })?;
+
+        events.collect()
+    }
+
+    /// All ledger events for a whole portfolio, ordered oldest-first.
+    fn get_ledger_events_for_portfolio(&self, portfolio_name: &str) -> Result<Vec<AdvanceLedgerEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, advance_id, portfolio_name, event_date, event_type, amount, buy_rate
+             FROM advance_ledger
+             WHERE portfolio_name = ?1
+             ORDER BY event_date ASC"
+        )?;
+
+        let events = stmt.query_map(params![portfolio_name], |row| {
+            let event_date: String = row.get(3)?;
+            let event_type: String = row.get(4)?;
+            let amount: String = row.get(5)?;
+            let buy_rate: Option<String> = row.get(6)?;
+
+            Ok(AdvanceLedgerEvent {
+                id: row.get(0)?,
+                advance_id: row.get(1)?,
+                portfolio_name: row.get(2)?,
+                event_date: NaiveDate::parse_from_str(&event_date, "%Y-%m-%d").unwrap(),
+                event_type: LedgerEventType::from_str(&event_type)?,
+                amount: Decimal::from_str(&amount).unwrap_or(Decimal::ZERO),
+                buy_rate: buy_rate.and_then(|r| Decimal::from_str(&r).ok()),
+            })
+        })?;
+
+        events.collect()
+    }
+
+    /// Run FIFO lot matching over one advance's full event history, in order,
+    /// returning the outstanding lots and the realized gain recognized by
+    /// each collection event (paired with that event's date).
+    fn run_fifo(events: &[AdvanceLedgerEvent]) -> (VecDeque<Lot>, Vec<(NaiveDate, Decimal)>) {
+        let mut lots: VecDeque<Lot> = VecDeque::new();
+        let mut gains: Vec<(NaiveDate, Decimal)> = Vec::new();
+
+        for event in events {
+            match event.event_type {
+                LedgerEventType::Funding => {
+                    lots.push_back(Lot {
+                        remaining: event.amount,
+                        buy_rate: event.buy_rate.unwrap_or(Decimal::ONE),
+                    });
+                }
+                LedgerEventType::Collection => {
+                    let mut remaining_collection = event.amount;
+                    let mut gain = Decimal::ZERO;
+
+                    while remaining_collection > Decimal::ZERO {
+                        let Some(lot) = lots.front_mut() else { break };
+                        let consumed_face = lot.remaining.min(remaining_collection);
+                        let cost_basis = consumed_face * lot.buy_rate;
+                        gain += consumed_face - cost_basis;
+
+                        lot.remaining -= consumed_face;
+                        remaining_collection -= consumed_face;
+
+                        if lot.remaining <= Decimal::ZERO {
+                            lots.pop_front();
+                        }
+                    }
+
+                    gains.push((event.event_date, gain));
+                }
+            }
+        }
+
+        (lots, gains)
+    }
+
+    /// Outstanding principal for one advance as of `as_of`, after consuming
+    /// collections against funding lots oldest-first.
+    pub fn advance_balance(&self, advance_id: &str, as_of: NaiveDate) -> Result<Decimal> {
+        let events: Vec<AdvanceLedgerEvent> = self
+            .get_ledger_events(advance_id)?
+            .into_iter()
+            .filter(|e| e.event_date <= as_of)
+            .collect();
+
+        let (lots, _gains) = Self::run_fifo(&events);
+        Ok(lots.iter().fold(Decimal::ZERO, |acc, lot| acc + lot.remaining))
+    }
+
+    /// Total realized servicing gain recognized across a portfolio's
+    /// advances during `period` (inclusive), via FIFO lot matching over each
+    /// advance's full history so mid-period collections consume the correct
+    /// cost basis.
+    pub fn realized_gains(&self, portfolio_name: &str, period: (NaiveDate, NaiveDate)) -> Result<Decimal> {
+        let events = self.get_ledger_events_for_portfolio(portfolio_name)?;
+
+        let mut by_advance: std::collections::HashMap<String, Vec<AdvanceLedgerEvent>> = std::collections::HashMap::new();
+        for event in events {
+            by_advance.entry(event.advance_id.clone()).or_default().push(event);
+        }
+
+        let (period_start, period_end) = period;
+        let mut total = Decimal::ZERO;
+
+        for advance_events in by_advance.values() {
+            let (_lots, gains) = Self::run_fifo(advance_events);
+            for (date, gain) in gains {
+                if date >= period_start && date <= period_end {
+                    total += gain;
+                }
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Insert a merchant row, replacing any earlier row with the same `id`
+    /// (re-parsing a portfolio workbook assigns a fresh `id` per row, so in
+    /// practice this is always a plain insert).
+    pub fn insert_or_update_merchant(&self, merchant: &Merchant) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO merchants
+             (id, portfolio_name, funder_name, date_funded, merchant_name, website,
+              advance_id, funder_advance_id, industry_naics_or_sic, state, fico,
+              buy_rate, commission, total_amount_funded, created_timestamp, updated_timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                merchant.id,
+                merchant.portfolio_name,
+                merchant.funder_name,
+                merchant.date_funded,
+                merchant.merchant_name,
+                merchant.website,
+                merchant.advance_id,
+                merchant.funder_advance_id,
+                merchant.industry_naics_or_sic,
+                merchant.state,
+                merchant.fico,
+                merchant.buy_rate,
+                merchant.commission,
+                merchant.total_amount_funded,
+                merchant.created_timestamp.to_rfc3339(),
+                merchant.updated_timestamp.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// All merchant rows parsed so far for one portfolio, across every funder.
+    pub fn get_merchants_by_portfolio(&self, portfolio_name: &str) -> Result<Vec<Merchant>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, funder_name, date_funded, merchant_name, website,
+                    advance_id, funder_advance_id, industry_naics_or_sic, state, fico,
+                    buy_rate, commission, total_amount_funded, created_timestamp, updated_timestamp
+             FROM merchants
+             WHERE portfolio_name = ?1"
+        )?;
+
+        let merchants = stmt.query_map(params![portfolio_name], |row| {
+            Ok(Merchant {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                funder_name: row.get(2)?,
+                date_funded: row.get(3)?,
+                merchant_name: row.get(4)?,
+                website: row.get(5)?,
+                advance_id: row.get(6)?,
+                funder_advance_id: row.get(7)?,
+                industry_naics_or_sic: row.get(8)?,
+                state: row.get(9)?,
+                fico: row.get(10)?,
+                buy_rate: row.get(11)?,
+                commission: row.get(12)?,
+                total_amount_funded: row.get(13)?,
+                created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(14)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                updated_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(15)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+        })?;
+
+        merchants.collect()
+    }
+
+    /// Funding-rate summary for `portfolio_name` over `[start, end]`
+    /// (inclusive). The elapsed-day denominator is derived from the
+    /// earliest and latest `date_funded` actually present in the window
+    /// (missing intermediate days are implicit, not counted as zero-funding
+    /// rows), while the projection extrapolates that daily average across
+    /// the full requested window.
+    pub fn portfolio_funding_summary(
+        &self,
+        portfolio_name: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<FundingSummary> {
+        let merchants = self.get_merchants_by_portfolio(portfolio_name)?;
+
+        let in_window: Vec<(&Merchant, NaiveDate)> = merchants
+            .iter()
+            .filter_map(|m| {
+                let date = NaiveDate::parse_from_str(m.date_funded.as_deref()?, "%Y-%m-%d").ok()?;
+                (date >= start && date <= end).then_some((m, date))
+            })
+            .collect();
+
+        let earliest = in_window.iter().map(|(_, d)| *d).min();
+        let latest = in_window.iter().map(|(_, d)| *d).max();
+
+        let elapsed_days = match (earliest, latest) {
+            (Some(e), Some(l)) => (l - e).num_days() + 1,
+            _ => 0,
+        };
+
+        let total_funded = in_window.iter().fold(Decimal::ZERO, |acc, (m, _)| {
+            acc + m.total_amount_funded.and_then(Decimal::from_f64_retain).unwrap_or(Decimal::ZERO)
+        });
+
+        let daily_average = if elapsed_days > 0 {
+            total_funded / Decimal::from(elapsed_days)
+        } else {
+            Decimal::ZERO
+        };
+
+        let requested_days = (end - start).num_days() + 1;
+        let projected_total = daily_average * Decimal::from(requested_days.max(0));
+
+        let mut by_funder: std::collections::HashMap<String, Decimal> = std::collections::HashMap::new();
+        for (merchant, _) in &in_window {
+            let amount = merchant.total_amount_funded.and_then(Decimal::from_f64_retain).unwrap_or(Decimal::ZERO);
+            *by_funder.entry(merchant.funder_name.clone()).or_insert(Decimal::ZERO) += amount;
+        }
+
+        let mut funder_shares: Vec<FunderFundingShare> = by_funder
+            .into_iter()
+            .map(|(funder_name, funder_total)| {
+                let share = if total_funded.is_zero() {
+                    0.0
+                } else {
+                    (funder_total / total_funded).to_f64().unwrap_or(0.0)
+                };
+                FunderFundingShare { funder_name, total_funded: funder_total, share }
+            })
+            .collect();
+        funder_shares.sort_by(|a, b| a.funder_name.cmp(&b.funder_name));
+
+        Ok(FundingSummary {
+            portfolio_name: portfolio_name.to_string(),
+            elapsed_days,
+            total_funded,
+            daily_average,
+            projected_total,
+            funder_shares,
+        })
+    }
+
+    /// Register a new file version. If `version.content_sha256` matches an
+    /// already-stored version, the existing (duplicate) row is returned
+    /// alongside the successful insert so the caller can warn the user and,
+    /// if desired, point the new row at the existing stored file instead of
+    /// copying bytes again.
+    /// Deactivates the portfolio's current active version (if any) and
+    /// inserts `version` in a single `unchecked_transaction()` — the same
+    /// `&self`-friendly primitive [`Database::migrate`] uses, since
+    /// `Connection::transaction()` needs `&mut Connection` and every
+    /// `Database` method here only ever takes `&self`. Committing both
+    /// statements together means a failure partway through never leaves a
+    /// portfolio with zero or two active versions.
+    pub fn insert_file_version(&self, version: &FileVersion) -> Result<Option<FileVersion>> {
+        retry_on_busy(|| self.insert_file_version_once(version))
+    }
+
+    fn insert_file_version_once(&self, version: &FileVersion) -> Result<Option<FileVersion>> {
+        let duplicate_of = match &version.content_sha256 {
+            Some(sha256) => self.find_version_by_hash(sha256)?,
+            None => None,
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+
+        tx.execute(
+            "UPDATE file_versions SET is_active = 0
+             WHERE portfolio_name = ?1 AND is_active = 1",
+            params![version.portfolio_name],
+        )?;
+
+        tx.execute(
+            "INSERT INTO file_versions
+             (id, portfolio_name, report_date, original_filename, version_filename,
+              file_path, file_size, upload_timestamp, is_active, content_sha256, content_md5)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                version.id,
+                version.portfolio_name,
+                version.report_date,
+                version.original_filename,
+                version.version_filename,
+                version.file_path,
+                version.file_size,
+                version.upload_timestamp.to_rfc3339(),
+                version.is_active,
+                version.content_sha256,
+                version.content_md5,
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(duplicate_of)
+    }
+
+    /// Find an existing file version with this exact content hash, if any —
+    /// used both by [`Database::insert_file_version`] for dedup detection
+    /// and by callers wanting to confirm two uploads are byte-identical.
+    pub fn find_version_by_hash(&self, sha256: &str) -> Result<Option<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename,
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at
+             FROM file_versions
+             WHERE content_sha256 = ?1 AND deleted_at IS NULL
+             ORDER BY upload_timestamp ASC
+             LIMIT 1"
+        )?;
+
+        stmt.query_row(params![sha256], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        }).optional()
+    }
+    
+    pub fn get_version_by_id(&self, id: &str) -> Result<Option<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at 
+             FROM file_versions 
+             WHERE id = ?1 AND deleted_at IS NULL"
+        )?;
+        
+        let version = stmt.query_row(params![id], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        }).optional()?;
+        
+        Ok(version)
+    }
+    
+    pub fn get_active_version(&self, portfolio_name: &str) -> Result<Option<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at 
+             FROM file_versions 
+             WHERE portfolio_name = ?1 AND is_active = 1 AND deleted_at IS NULL"
+        )?;
+        
+        let version = stmt.query_row(params![portfolio_name], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        }).optional()?;
+        
+        Ok(version)
+    }
+    
+    pub fn get_versions_by_portfolio(&self, portfolio_name: &str) -> Result<Vec<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at 
+             FROM file_versions 
+             WHERE portfolio_name = ?1 AND deleted_at IS NULL
+             ORDER BY report_date DESC, upload_timestamp DESC"
+        )?;
+        
+        let versions = stmt.query_map(params![portfolio_name], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+        
+        versions.collect()
+    }
+    
+    pub fn get_versions_by_date(&self, report_date: &str) -> Result<Vec<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at 
+             FROM file_versions 
+             WHERE report_date = ?1 AND deleted_at IS NULL
+             ORDER BY portfolio_name, upload_timestamp DESC"
+        )?;
+        
+        let versions = stmt.query_map(params![report_date], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+        
+        versions.collect()
+    }
+    
+    pub fn get_version_by_portfolio_and_date(
+        &self, 
+        portfolio_name: &str, 
+        report_date: &str
+    ) -> Result<Option<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at 
+             FROM file_versions 
+             WHERE portfolio_name = ?1 AND report_date = ?2 AND deleted_at IS NULL
+             ORDER BY upload_timestamp DESC
+             LIMIT 1"
+        )?;
+        
+        let version = stmt.query_row(params![portfolio_name, report_date], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        }).optional()?;
+        
+        Ok(version)
+    }
+    
+    /// Soft-delete a version by stamping `deleted_at`; the row stays in
+    /// `file_versions` (excluded from every read query above) until
+    /// [`Database::restore_version`] clears the stamp or
+    /// [`Database::purge_version`]/[`Database::purge_trashed_older_than`]
+    /// removes it for good.
+    pub fn delete_version(&self, id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "UPDATE file_versions SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// List a portfolio's soft-deleted versions, most-recently-deleted first.
+    pub fn list_trashed(&self, portfolio_name: &str) -> Result<Vec<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename,
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at
+             FROM file_versions
+             WHERE portfolio_name = ?1 AND deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC"
+        )?;
+
+        let versions = stmt.query_map(params![portfolio_name], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+
+        versions.collect()
+    }
+
+    /// Undo a [`Database::delete_version`] by clearing `deleted_at`.
+    pub fn restore_version(&self, id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "UPDATE file_versions SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Record the ordered chunk-hash manifest produced by
+    /// [`crate::chunk_store::split_into_chunks`] for `version_id`, and bump
+    /// each chunk's refcount for `portfolio_name` so a later
+    /// [`Database::purge_version`] knows when a chunk file is safe to delete.
+    /// Wrapped in one transaction for the same reason
+    /// [`Database::insert_file_version`] is: a partial manifest is worse
+    /// than no manifest.
+    pub fn record_version_chunks(
+        &self,
+        version_id: &str,
+        portfolio_name: &str,
+        chunk_hashes: &[String],
+    ) -> Result<()> {
+        retry_on_busy(|| self.record_version_chunks_once(version_id, portfolio_name, chunk_hashes))
+    }
+
+    fn record_version_chunks_once(
+        &self,
+        version_id: &str,
+        portfolio_name: &str,
+        chunk_hashes: &[String],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        for (index, chunk_hash) in chunk_hashes.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO version_chunks (version_id, chunk_index, chunk_hash) VALUES (?1, ?2, ?3)",
+                params![version_id, index as i64, chunk_hash],
+            )?;
+            tx.execute(
+                "INSERT INTO chunk_refs (portfolio_name, chunk_hash, refcount) VALUES (?1, ?2, 1)
+                 ON CONFLICT(portfolio_name, chunk_hash) DO UPDATE SET refcount = refcount + 1",
+                params![portfolio_name, chunk_hash],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The chunk hashes making up `version_id`, in manifest order, so the
+    /// caller can reassemble the workbook by concatenating each chunk file
+    /// in turn. Empty for versions stored before the chunk store existed.
+    pub fn get_version_chunk_manifest(&self, version_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT chunk_hash FROM version_chunks WHERE version_id = ?1 ORDER BY chunk_index ASC"
+        )?;
+
+        let hashes = stmt.query_map(params![version_id], |row| row.get(0))?;
+        hashes.collect()
+    }
+
+    /// Decrement the refcount of every chunk `version_id`'s manifest
+    /// references, delete the manifest rows, and return the chunk hashes
+    /// whose refcount dropped to zero — those chunk files have no remaining
+    /// version pointing at them and are safe for the caller to delete from
+    /// disk. Takes `&Connection` rather than `&self` so [`Database::purge_version`]
+    /// can run it inside its own transaction.
+    fn release_version_chunks(conn: &Connection, version_id: &str, portfolio_name: &str) -> Result<Vec<String>> {
+        let chunk_hashes: Vec<String> = {
+            let mut stmt = conn.prepare(
+                "SELECT chunk_hash FROM version_chunks WHERE version_id = ?1 ORDER BY chunk_index ASC"
+            )?;
+            stmt.query_map(params![version_id], |row| row.get(0))?
+                .collect::<Result<Vec<String>>>()?
+        };
+
+        conn.execute("DELETE FROM version_chunks WHERE version_id = ?1", params![version_id])?;
+
+        let mut orphaned = Vec::new();
+        for chunk_hash in &chunk_hashes {
+            conn.execute(
+                "UPDATE chunk_refs SET refcount = refcount - 1 WHERE portfolio_name = ?1 AND chunk_hash = ?2",
+                params![portfolio_name, chunk_hash],
+            )?;
+
+            let refcount: i64 = conn.query_row(
+                "SELECT refcount FROM chunk_refs WHERE portfolio_name = ?1 AND chunk_hash = ?2",
+                params![portfolio_name, chunk_hash],
+                |row| row.get(0),
+            )?;
+
+            if refcount <= 0 {
+                conn.execute(
+                    "DELETE FROM chunk_refs WHERE portfolio_name = ?1 AND chunk_hash = ?2",
+                    params![portfolio_name, chunk_hash],
+                )?;
+                orphaned.push(chunk_hash.clone());
+            }
+        }
+
+        Ok(orphaned)
+    }
+
+    /// The portfolio a version belongs to, regardless of `deleted_at` —
+    /// unlike [`Database::get_version_by_id`], so a caller can still locate
+    /// a version's `Workbook/.chunks/` directory right before purging it.
+    pub fn get_version_portfolio_name(&self, id: &str) -> Result<Option<String>> {
+        self.conn.query_row(
+            "SELECT portfolio_name FROM file_versions WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        ).optional()
+    }
+
+    /// Permanently remove a single (normally already soft-deleted) version
+    /// row, release any chunks its manifest referenced, and return the
+    /// chunk hashes that lost their last reference — the caller must delete
+    /// those files from `Workbook/.chunks/` to actually reclaim the space.
+    /// Unlike [`Database::delete_version`], this cannot be undone.
+    pub fn purge_version(&self, id: &str) -> Result<Vec<String>> {
+        let Some(portfolio_name) = self.get_version_portfolio_name(id)? else {
+            return Ok(Vec::new());
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+        let orphaned_chunks = Self::release_version_chunks(&tx, id, &portfolio_name)?;
+        tx.execute("DELETE FROM file_versions WHERE id = ?1", params![id])?;
+        tx.commit()?;
+
+        Ok(orphaned_chunks)
+    }
+
+    /// Configure (or replace) the [`RetentionPolicy`] a portfolio's uploads
+    /// are automatically pruned against. Pass a policy where
+    /// [`RetentionPolicy::keeps_something`] is false to effectively disable
+    /// pruning without deleting the row (the empty policy is stored as-is;
+    /// [`Database::run_retention`] still refuses to act on it via the same
+    /// guard [`Database::prune_versions`] uses).
+    pub fn set_retention_policy(&self, portfolio_name: &str, policy: &RetentionPolicy) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO retention_policies
+             (portfolio_name, keep_last, keep_daily, keep_weekly, keep_monthly, keep_yearly)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(portfolio_name) DO UPDATE SET
+                keep_last = excluded.keep_last,
+                keep_daily = excluded.keep_daily,
+                keep_weekly = excluded.keep_weekly,
+                keep_monthly = excluded.keep_monthly,
+                keep_yearly = excluded.keep_yearly",
+            params![
+                portfolio_name,
+                policy.keep_last as i64,
+                policy.keep_daily as i64,
+                policy.keep_weekly as i64,
+                policy.keep_monthly as i64,
+                policy.keep_yearly as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The retention policy configured for a portfolio, if any.
+    pub fn get_retention_policy(&self, portfolio_name: &str) -> Result<Option<RetentionPolicy>> {
+        self.conn.query_row(
+            "SELECT keep_last, keep_daily, keep_weekly, keep_monthly, keep_yearly
+             FROM retention_policies WHERE portfolio_name = ?1",
+            params![portfolio_name],
+            |row| {
+                Ok(RetentionPolicy {
+                    keep_last: row.get::<_, i64>(0)? as usize,
+                    keep_daily: row.get::<_, i64>(1)? as usize,
+                    keep_weekly: row.get::<_, i64>(2)? as usize,
+                    keep_monthly: row.get::<_, i64>(3)? as usize,
+                    keep_yearly: row.get::<_, i64>(4)? as usize,
+                })
+            },
+        ).optional()
+    }
+
+    /// Configure (or replace) the [`CompressionConfig`] new funder uploads
+    /// for a portfolio are written with. Existing blobs are unaffected —
+    /// only `write_funder_blob` calls made after this consult it.
+    pub fn set_compression_config(&self, portfolio_name: &str, config: &CompressionConfig) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO compression_configs (portfolio_name, enabled, level)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(portfolio_name) DO UPDATE SET
+                enabled = excluded.enabled,
+                level = excluded.level",
+            params![portfolio_name, config.enabled, config.level],
+        )?;
+        Ok(())
+    }
+
+    /// The compression config for a portfolio, or [`CompressionConfig::default`]
+    /// (disabled) if none has been set.
+    pub fn get_compression_config(&self, portfolio_name: &str) -> Result<CompressionConfig> {
+        let config = self.conn.query_row(
+            "SELECT enabled, level FROM compression_configs WHERE portfolio_name = ?1",
+            params![portfolio_name],
+            |row| {
+                Ok(CompressionConfig {
+                    enabled: row.get(0)?,
+                    level: row.get(1)?,
+                })
+            },
+        ).optional()?;
+
+        Ok(config.unwrap_or_default())
+    }
+
+    /// Evaluate and apply a portfolio's configured retention policy for
+    /// real, via [`Database::prune_versions`] in dry-run mode followed by
+    /// [`Database::purge_version`] for each expired version — reusing
+    /// `purge_version`'s chunk-refcount release rather than duplicating it.
+    /// Returns the versions removed and the chunk hashes that dropped to
+    /// zero references as a result; the caller (the file-system layer) is
+    /// responsible for deleting those chunk files and any removed version's
+    /// legacy standalone file. A no-op, returning two empty vecs, if the
+    /// portfolio has no retention policy configured.
+    pub fn run_retention(&self, portfolio_name: &str) -> Result<(Vec<FileVersion>, Vec<String>)> {
+        let Some(policy) = self.get_retention_policy(portfolio_name)? else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+
+        if !policy.keeps_something() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let expired = self.prune_versions(portfolio_name, &policy, true)?;
+
+        let mut orphaned_chunks = Vec::new();
+        for version in &expired {
+            orphaned_chunks.extend(self.purge_version(&version.id)?);
+        }
+
+        Ok((expired, orphaned_chunks))
+    }
+
+    /// Compute (and, unless `dry_run`, apply) a [`RetentionPolicy`] against
+    /// a portfolio's version history.
+    ///
+    /// Versions are walked newest-first by `upload_timestamp`. The first
+    /// `keep_last` are force-kept outright; every version after that is kept
+    /// only if it is the newest version seen so far in its day/ISO-week/
+    /// month/year bucket *and* that bucket class still has remaining quota
+    /// (decremented on each keep). The active version is always force-kept
+    /// regardless of policy. Returns the versions that were (or would be)
+    /// removed, so the caller can confirm a dry run before re-calling with
+    /// `dry_run: false`.
+    pub fn prune_versions(
+        &self,
+        portfolio_name: &str,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<Vec<FileVersion>> {
+        if !policy.keeps_something() {
+            return Err(rusqlite::Error::InvalidColumnType(
+                0,
+                "retention policy keeps nothing; refusing to prune everything".to_string(),
+                rusqlite::types::Type::Null,
+            ));
+        }
+
+        let mut versions = self.get_versions_by_portfolio(portfolio_name)?;
+        versions.sort_by(|a, b| b.upload_timestamp.cmp(&a.upload_timestamp));
+
+        let mut remaining_daily = policy.keep_daily;
+        let mut remaining_weekly = policy.keep_weekly;
+        let mut remaining_monthly = policy.keep_monthly;
+        let mut remaining_yearly = policy.keep_yearly;
+
+        let mut seen_days: HashSet<NaiveDate> = HashSet::new();
+        let mut seen_weeks: HashSet<(i32, u32)> = HashSet::new();
+        let mut seen_months: HashSet<(i32, u32)> = HashSet::new();
+        let mut seen_years: HashSet<i32> = HashSet::new();
+
+        let mut to_delete = Vec::new();
+
+        for (index, version) in versions.into_iter().enumerate() {
+            if version.is_active || index < policy.keep_last {
+                continue;
+            }
+
+            let date = version.upload_timestamp.date_naive();
+            let iso_week = date.iso_week();
+            let is_newest_in_day = seen_days.insert(date);
+            let is_newest_in_week = seen_weeks.insert((iso_week.year(), iso_week.week()));
+            let is_newest_in_month = seen_months.insert((date.year(), date.month()));
+            let is_newest_in_year = seen_years.insert(date.year());
+
+            let mut kept = false;
+            if is_newest_in_day && remaining_daily > 0 {
+                remaining_daily -= 1;
+                kept = true;
+            }
+            if is_newest_in_week && remaining_weekly > 0 {
+                remaining_weekly -= 1;
+                kept = true;
+            }
+            if is_newest_in_month && remaining_monthly > 0 {
+                remaining_monthly -= 1;
+                kept = true;
+            }
+            if is_newest_in_year && remaining_yearly > 0 {
+                remaining_yearly -= 1;
+                kept = true;
+            }
+
+            if !kept {
+                to_delete.push(version);
+            }
+        }
+
+        if !dry_run && !to_delete.is_empty() {
+            let tx = self.conn.unchecked_transaction()?;
+            for version in &to_delete {
+                Self::release_version_chunks(&tx, &version.id, &version.portfolio_name)?;
+                tx.execute("DELETE FROM file_versions WHERE id = ?1", params![version.id])?;
+            }
+            tx.commit()?;
+        }
+
+        Ok(to_delete)
+    }
+
+    /// Deactivates the portfolio's current active version and activates
+    /// `id` in a single `unchecked_transaction()`, for the same reason
+    /// [`Database::insert_file_version`] does — so a crash or error between
+    /// the two `UPDATE`s can never leave the portfolio with zero or two
+    /// active versions.
+    pub fn set_active_version(&self, id: &str) -> Result<()> {
+        let version = self.get_version_by_id(id)?
+            .ok_or_else(|| rusqlite::Error::QueryReturnedNoRows)?;
+
+        let tx = self.conn.unchecked_transaction()?;
+
+        tx.execute(
+            "UPDATE file_versions SET is_active = 0
+             WHERE portfolio_name = ?1 AND is_active = 1",
+            params![version.portfolio_name],
+        )?;
+
+        tx.execute(
+            "UPDATE file_versions SET is_active = 1 WHERE id = ?1",
+            params![id],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Check that every portfolio with at least one version has exactly one
+    /// active version, and no portfolio has more than one. Returns the
+    /// portfolio names that violate this — empty means the database is
+    /// consistent. Intended for post-crash sanity checks and tests, since
+    /// [`Database::insert_file_version`]/[`Database::set_active_version`]
+    /// already guarantee this invariant transactionally in normal operation.
+    pub fn verify_invariants(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT portfolio_name FROM file_versions
+             WHERE deleted_at IS NULL
+             GROUP BY portfolio_name
+             HAVING SUM(CASE WHEN is_active = 1 THEN 1 ELSE 0 END) <> 1"
+        )?;
+
+        let offenders = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(offenders)
+    }
+    
+    pub fn get_all_versions(&self) -> Result<Vec<FileVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
+                    file_path, file_size, upload_timestamp, is_active,
+                    content_sha256, content_md5, deleted_at
+             FROM file_versions
+             WHERE deleted_at IS NULL
+             ORDER BY report_date DESC, portfolio_name, upload_timestamp DESC"
+        )?;
+        
+        let versions = stmt.query_map([], |row| {
+            Ok(FileVersion {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                report_date: row.get(2)?,
+                original_filename: row.get(3)?,
+                version_filename: row.get(4)?,
+                file_path: row.get(5)?,
+                file_size: row.get(6)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                is_active: row.get(8)?,
+                content_sha256: row.get(9)?,
+                content_md5: row.get(10)?,
+                deleted_at: row.get::<_, Option<String>>(11)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+        
+        versions.collect()
+    }
+    
+    // Funder Upload Methods
+    /// Register a funder upload. If `upload.content_sha256` matches an
+    /// already-stored upload for this portfolio, the existing (duplicate)
+    /// row is returned alongside the successful insert, mirroring
+    /// [`Database::insert_file_version`]'s dedup-detection behavior.
+    pub fn insert_funder_upload(&self, upload: &FunderUpload) -> Result<Option<FunderUpload>> {
+        retry_on_busy(|| self.insert_funder_upload_once(upload))
+    }
+
+    fn insert_funder_upload_once(&self, upload: &FunderUpload) -> Result<Option<FunderUpload>> {
+        let duplicate_of = match &upload.content_sha256 {
+            Some(sha256) => self.find_funder_upload_by_hash(&upload.portfolio_name, sha256)?,
+            None => None,
+        };
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO funder_uploads
+             (id, portfolio_name, funder_name, report_date, upload_type,
+              original_filename, stored_filename, file_path, file_size, upload_timestamp,
+              content_sha256, content_md5, codec, compressed_size)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                upload.id,
+                upload.portfolio_name,
+                upload.funder_name,
+                upload.report_date,
+                upload.upload_type,
+                upload.original_filename,
+                upload.stored_filename,
+                upload.file_path,
+                upload.file_size,
+                upload.upload_timestamp.to_rfc3339(),
+                upload.content_sha256,
+                upload.content_md5,
+                upload.codec,
+                upload.compressed_size,
+            ],
+        )?;
+        Ok(duplicate_of)
+    }
+
+    pub fn get_funder_upload(
+        &self,
+        portfolio_name: &str,
+        funder_name: &str,
+        report_date: &str,
+        upload_type: &str,
+    ) -> Result<Option<FunderUpload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, funder_name, report_date, upload_type,
+                    original_filename, stored_filename, file_path, file_size, upload_timestamp,
+                    content_sha256, content_md5, codec, compressed_size, deleted_at
+             FROM funder_uploads
+             WHERE portfolio_name = ?1 AND funder_name = ?2 AND report_date = ?3 AND upload_type = ?4 AND deleted_at IS NULL"
+        )?;
+
+        let upload = stmt.query_row(
+            params![portfolio_name, funder_name, report_date, upload_type],
+            |row| {
+                Ok(FunderUpload {
+                    id: row.get(0)?,
+                    portfolio_name: row.get(1)?,
+                    funder_name: row.get(2)?,
+                    report_date: row.get(3)?,
+                    upload_type: row.get(4)?,
+                    original_filename: row.get(5)?,
+                    stored_filename: row.get(6)?,
+                    file_path: row.get(7)?,
+                    file_size: row.get(8)?,
+                    upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                    content_sha256: row.get(10)?,
+                    content_md5: row.get(11)?,
+                    codec: row.get(12)?,
+                    compressed_size: row.get(13)?,
+                    deleted_at: row.get::<_, Option<String>>(14)?
+                        .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                        .map(|dt| dt.with_timezone(&Utc)),
+                })
+            }
+        ).optional()?;
+
+        Ok(upload)
+    }
+
+    pub fn get_funder_uploads_by_portfolio_and_date(
+        &self,
+        portfolio_name: &str,
+        report_date: &str,
+    ) -> Result<Vec<FunderUpload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, funder_name, report_date, upload_type,
+                    original_filename, stored_filename, file_path, file_size, upload_timestamp,
+                    content_sha256, content_md5, codec, compressed_size, deleted_at
+             FROM funder_uploads
+             WHERE portfolio_name = ?1 AND report_date = ?2 AND deleted_at IS NULL
+             ORDER BY upload_type, funder_name"
+        )?;
+
+        let uploads = stmt.query_map(params![portfolio_name, report_date], |row| {
+            Ok(FunderUpload {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                funder_name: row.get(2)?,
+                report_date: row.get(3)?,
+                upload_type: row.get(4)?,
+                original_filename: row.get(5)?,
+                stored_filename: row.get(6)?,
+                file_path: row.get(7)?,
+                file_size: row.get(8)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                content_sha256: row.get(10)?,
+                content_md5: row.get(11)?,
+                codec: row.get(12)?,
+                compressed_size: row.get(13)?,
+                deleted_at: row.get::<_, Option<String>>(14)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+
+        uploads.collect()
+    }
+
+    /// Every funder upload for a portfolio across all report dates, newest
+    /// first — used by `export_portfolio_archive` to snapshot a portfolio's
+    /// full upload history rather than one date at a time.
+    pub fn get_funder_uploads_by_portfolio(&self, portfolio_name: &str) -> Result<Vec<FunderUpload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, funder_name, report_date, upload_type,
+                    original_filename, stored_filename, file_path, file_size, upload_timestamp,
+                    content_sha256, content_md5, codec, compressed_size, deleted_at
+             FROM funder_uploads
+             WHERE portfolio_name = ?1 AND deleted_at IS NULL
+             ORDER BY report_date DESC, funder_name, upload_type"
+        )?;
+
+        let uploads = stmt.query_map(params![portfolio_name], |row| {
+            Ok(FunderUpload {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                funder_name: row.get(2)?,
+                report_date: row.get(3)?,
+                upload_type: row.get(4)?,
+                original_filename: row.get(5)?,
+                stored_filename: row.get(6)?,
+                file_path: row.get(7)?,
+                file_size: row.get(8)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                content_sha256: row.get(10)?,
+                content_md5: row.get(11)?,
+                codec: row.get(12)?,
+                compressed_size: row.get(13)?,
+                deleted_at: row.get::<_, Option<String>>(14)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+
+        uploads.collect()
+    }
+
+    /// Compute the funder uploads a [`RetentionPolicy`] would remove for
+    /// `portfolio_name`, evaluating the policy independently per (funder,
+    /// upload type) group the same way [`Database::prune_versions`]
+    /// evaluates it per portfolio: newest-first, `keep_last` force-kept
+    /// outright, then each remaining upload kept only if it is the newest
+    /// one seen so far in its day/ISO-week/month/year bucket and that
+    /// bucket still has quota. Pure computation — never deletes anything
+    /// itself; the file-system layer deletes each returned upload via its
+    /// usual reference-counted cleanup.
+    pub fn prune_funder_uploads_candidates(
+        &self,
+        portfolio_name: &str,
+        policy: &RetentionPolicy,
+    ) -> Result<Vec<FunderUpload>> {
+        if !policy.keeps_something() {
+            return Err(rusqlite::Error::InvalidColumnType(
+                0,
+                "retention policy keeps nothing; refusing to prune everything".to_string(),
+                rusqlite::types::Type::Null,
+            ));
+        }
+
+        let uploads = self.get_funder_uploads_by_portfolio(portfolio_name)?;
+
+        let mut groups: HashMap<(String, String), Vec<FunderUpload>> = HashMap::new();
+        for upload in uploads {
+            groups
+                .entry((upload.funder_name.clone(), upload.upload_type.clone()))
+                .or_default()
+                .push(upload);
+        }
+
+        let mut to_delete = Vec::new();
+
+        for (_, mut group) in groups {
+            group.sort_by(|a, b| b.upload_timestamp.cmp(&a.upload_timestamp));
+
+            let mut remaining_daily = policy.keep_daily;
+            let mut remaining_weekly = policy.keep_weekly;
+            let mut remaining_monthly = policy.keep_monthly;
+            let mut remaining_yearly = policy.keep_yearly;
+
+            let mut seen_days: HashSet<NaiveDate> = HashSet::new();
+            let mut seen_weeks: HashSet<(i32, u32)> = HashSet::new();
+            let mut seen_months: HashSet<(i32, u32)> = HashSet::new();
+            let mut seen_years: HashSet<i32> = HashSet::new();
+
+            for (index, upload) in group.into_iter().enumerate() {
+                if index < policy.keep_last {
+                    continue;
+                }
+
+                let date = upload.upload_timestamp.date_naive();
+                let iso_week = date.iso_week();
+                let is_newest_in_day = seen_days.insert(date);
+                let is_newest_in_week = seen_weeks.insert((iso_week.year(), iso_week.week()));
+                let is_newest_in_month = seen_months.insert((date.year(), date.month()));
+                let is_newest_in_year = seen_years.insert(date.year());
+
+                let mut kept = false;
+                if is_newest_in_day && remaining_daily > 0 {
+                    remaining_daily -= 1;
+                    kept = true;
+                }
+                if is_newest_in_week && remaining_weekly > 0 {
+                    remaining_weekly -= 1;
+                    kept = true;
+                }
+                if is_newest_in_month && remaining_monthly > 0 {
+                    remaining_monthly -= 1;
+                    kept = true;
+                }
+                if is_newest_in_year && remaining_yearly > 0 {
+                    remaining_yearly -= 1;
+                    kept = true;
+                }
+
+                if !kept {
+                    to_delete.push(upload);
+                }
+            }
+        }
+
+        Ok(to_delete)
+    }
+
+    /// Whether a funder upload row with this id exists, ignoring
+    /// `deleted_at` — used by archive import's id-collision check, mirroring
+    /// [`Database::get_version_portfolio_name`]'s reasoning for versions.
+    pub fn funder_upload_id_exists(&self, id: &str) -> Result<bool> {
+        let exists: Option<i64> = self.conn.query_row(
+            "SELECT 1 FROM funder_uploads WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(exists.is_some())
+    }
+
+    /// How many funder uploads in `portfolio_name` other than `exclude_id`
+    /// still reference `content_sha256` — used by `delete_funder_upload` to
+    /// decide whether the shared blob is safe to remove from disk, since
+    /// several upload rows can point at the same content-addressed file.
+    pub fn count_funder_uploads_referencing_hash(
+        &self,
+        portfolio_name: &str,
+        content_sha256: &str,
+        exclude_id: &str,
+    ) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM funder_uploads
+             WHERE portfolio_name = ?1 AND content_sha256 = ?2 AND id != ?3",
+            params![portfolio_name, content_sha256, exclude_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Find an existing funder upload for this portfolio with this exact
+    /// content hash, if any. Scoped to `portfolio_name` (unlike
+    /// [`Database::find_version_by_hash`]) since the same bytes uploaded for
+    /// two different portfolios are not meaningfully "the same upload".
+    pub fn find_funder_upload_by_hash(
+        &self,
+        portfolio_name: &str,
+        sha256: &str,
+    ) -> Result<Option<FunderUpload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, funder_name, report_date, upload_type,
+                    original_filename, stored_filename, file_path, file_size, upload_timestamp,
+                    content_sha256, content_md5, codec, compressed_size, deleted_at
+             FROM funder_uploads
+             WHERE portfolio_name = ?1 AND content_sha256 = ?2 AND deleted_at IS NULL
+             ORDER BY upload_timestamp ASC
+             LIMIT 1"
+        )?;
+
+        stmt.query_row(params![portfolio_name, sha256], |row| {
+            Ok(FunderUpload {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                funder_name: row.get(2)?,
+                report_date: row.get(3)?,
+                upload_type: row.get(4)?,
+                original_filename: row.get(5)?,
+                stored_filename: row.get(6)?,
+                file_path: row.get(7)?,
+                file_size: row.get(8)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                content_sha256: row.get(10)?,
+                content_md5: row.get(11)?,
+                codec: row.get(12)?,
+                compressed_size: row.get(13)?,
+                deleted_at: row.get::<_, Option<String>>(14)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        }).optional()
+    }
+
+    /// Soft-delete a funder upload by stamping `deleted_at`, mirroring
+    /// [`Database::delete_version`].
+    pub fn delete_funder_upload(&self, id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "UPDATE funder_uploads SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![Utc::now().to_rfc3339(), id],
         )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_portfolio_date 
-             ON file_versions(portfolio_name, report_date)",
-            [],
+        Ok(rows_affected > 0)
+    }
+
+    /// List a portfolio's soft-deleted funder uploads, most-recently-deleted first.
+    pub fn list_trashed_funder_uploads(&self, portfolio_name: &str) -> Result<Vec<FunderUpload>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, portfolio_name, funder_name, report_date, upload_type,
+                    original_filename, stored_filename, file_path, file_size, upload_timestamp,
+                    content_sha256, content_md5, codec, compressed_size, deleted_at
+             FROM funder_uploads
+             WHERE portfolio_name = ?1 AND deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC"
         )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_report_date 
-             ON file_versions(report_date)",
-            [],
+
+        let uploads = stmt.query_map(params![portfolio_name], |row| {
+            Ok(FunderUpload {
+                id: row.get(0)?,
+                portfolio_name: row.get(1)?,
+                funder_name: row.get(2)?,
+                report_date: row.get(3)?,
+                upload_type: row.get(4)?,
+                original_filename: row.get(5)?,
+                stored_filename: row.get(6)?,
+                file_path: row.get(7)?,
+                file_size: row.get(8)?,
+                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                content_sha256: row.get(10)?,
+                content_md5: row.get(11)?,
+                codec: row.get(12)?,
+                compressed_size: row.get(13)?,
+                deleted_at: row.get::<_, Option<String>>(14)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+        })?;
+
+        uploads.collect()
+    }
+
+    /// Undo a [`Database::delete_funder_upload`] by clearing `deleted_at`.
+    pub fn restore_funder_upload(&self, id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "UPDATE funder_uploads SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
         )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_active 
-             ON file_versions(is_active)",
-            [],
+        Ok(rows_affected > 0)
+    }
+
+    /// Permanently remove a single (normally already soft-deleted) funder
+    /// upload row. Unlike [`Database::delete_funder_upload`], this cannot be undone.
+    pub fn purge_funder_upload(&self, id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM funder_uploads WHERE id = ?1",
+            params![id],
         )?;
+        Ok(rows_affected > 0)
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS funder_uploads (
-                id TEXT PRIMARY KEY,
-                portfolio_name TEXT NOT NULL,
-                funder_name TEXT NOT NULL,
-                report_date TEXT NOT NULL,
-                upload_type TEXT NOT NULL,
-                original_filename TEXT NOT NULL,
-                stored_filename TEXT NOT NULL,
-                file_path TEXT NOT NULL,
-                file_size INTEGER NOT NULL,
-                upload_timestamp TEXT NOT NULL,
-                UNIQUE(portfolio_name, funder_name, report_date, upload_type)
-            )",
-            [],
+    /// Permanently remove every trashed version and funder upload whose
+    /// `deleted_at` is older than `older_than`, so soft-deleted files
+    /// eventually clear out. Returns the total number of rows purged.
+    pub fn purge_trashed_older_than(&self, older_than: chrono::Duration) -> Result<usize> {
+        let cutoff = (Utc::now() - older_than).to_rfc3339();
+
+        let versions_purged = self.conn.execute(
+            "DELETE FROM file_versions WHERE deleted_at IS NOT NULL AND deleted_at < ?1",
+            params![cutoff],
         )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_funder_portfolio_date 
-             ON funder_uploads(portfolio_name, funder_name, report_date)",
-            [],
+        let uploads_purged = self.conn.execute(
+            "DELETE FROM funder_uploads WHERE deleted_at IS NOT NULL AND deleted_at < ?1",
+            params![cutoff],
         )?;
 
-        Ok(Database { conn })
+        Ok(versions_purged + uploads_purged)
     }
-    
-    pub fn insert_file_version(&self, version: &FileVersion) -> Result<()> {
-        self.conn.execute(
-            "UPDATE file_versions SET is_active = 0 
-             WHERE portfolio_name = ?1 AND is_active = 1",
-            params![version.portfolio_name],
-        )?;
-        
+
+    /// Record a generated pivot table's metadata. `INSERT OR REPLACE` only
+    /// replaces a row with the same `id`; callers generate a fresh id per
+    /// pivot, so re-running a funder's parser for the same upload adds a new
+    /// row rather than overwriting the earlier one.
+    pub fn insert_funder_pivot_table(&self, pivot: &FunderPivotTable) -> Result<()> {
+        retry_on_busy(|| self.insert_funder_pivot_table_once(pivot))
+    }
+
+    fn insert_funder_pivot_table_once(&self, pivot: &FunderPivotTable) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO file_versions 
-             (id, portfolio_name, report_date, original_filename, version_filename, 
-              file_path, file_size, upload_timestamp, is_active) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT OR REPLACE INTO funder_pivot_tables
+             (id, upload_id, portfolio_name, funder_name, report_date, upload_type,
+              pivot_file_path, total_gross, total_fee, total_net, row_count, created_timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
-                version.id,
-                version.portfolio_name,
-                version.report_date,
-                version.original_filename,
-                version.version_filename,
-                version.file_path,
-                version.file_size,
-                version.upload_timestamp.to_rfc3339(),
-                version.is_active,
+                pivot.id,
+                pivot.upload_id,
+                pivot.portfolio_name,
+                pivot.funder_name,
+                pivot.report_date,
+                pivot.upload_type,
+                pivot.pivot_file_path,
+                pivot.total_gross,
+                pivot.total_fee,
+                pivot.total_net,
+                pivot.row_count,
+                pivot.created_timestamp.to_rfc3339(),
             ],
         )?;
         Ok(())
     }
-    
-    pub fn get_version_by_id(&self, id: &str) -> Result<Option<FileVersion>> {
+
+    /// Every generated pivot table's metadata for a portfolio, newest first.
+    pub fn get_funder_pivot_tables_by_portfolio(&self, portfolio_name: &str) -> Result<Vec<FunderPivotTable>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
-                    file_path, file_size, upload_timestamp, is_active 
-             FROM file_versions 
-             WHERE id = ?1"
+            "SELECT id, upload_id, portfolio_name, funder_name, report_date, upload_type,
+                    pivot_file_path, total_gross, total_fee, total_net, row_count, created_timestamp
+             FROM funder_pivot_tables
+             WHERE portfolio_name = ?1
+             ORDER BY report_date DESC, funder_name"
         )?;
-        
-        let version = stmt.query_row(params![id], |row| {
-            Ok(FileVersion {
+
+        let pivots = stmt.query_map(params![portfolio_name], |row| {
+            Ok(FunderPivotTable {
                 id: row.get(0)?,
-                portfolio_name: row.get(1)?,
-                report_date: row.get(2)?,
-                original_filename: row.get(3)?,
-                version_filename: row.get(4)?,
-                file_path: row.get(5)?,
-                file_size: row.get(6)?,
-                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                upload_id: row.get(1)?,
+                portfolio_name: row.get(2)?,
+                funder_name: row.get(3)?,
+                report_date: row.get(4)?,
+                upload_type: row.get(5)?,
+                pivot_file_path: row.get(6)?,
+                total_gross: row.get(7)?,
+                total_fee: row.get(8)?,
+                total_net: row.get(9)?,
+                row_count: row.get(10)?,
+                created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(11)?)
                     .unwrap()
                     .with_timezone(&Utc),
-                is_active: row.get(8)?,
             })
-        }).optional()?;
-        
-        Ok(version)
+        })?;
+
+        pivots.collect()
     }
-    
-    pub fn get_active_version(&self, portfolio_name: &str) -> Result<Option<FileVersion>> {
+
+    /// Every generated pivot table's metadata across every portfolio, used
+    /// by `get_all_database_files` and the integrity-check sweep.
+    pub fn get_all_pivot_tables(&self) -> Result<Vec<FunderPivotTable>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
-                    file_path, file_size, upload_timestamp, is_active 
-             FROM file_versions 
-             WHERE portfolio_name = ?1 AND is_active = 1"
+            "SELECT id, upload_id, portfolio_name, funder_name, report_date, upload_type,
+                    pivot_file_path, total_gross, total_fee, total_net, row_count, created_timestamp
+             FROM funder_pivot_tables
+             ORDER BY report_date DESC, portfolio_name, funder_name"
         )?;
-        
-        let version = stmt.query_row(params![portfolio_name], |row| {
-            Ok(FileVersion {
+
+        let pivots = stmt.query_map([], |row| {
+            Ok(FunderPivotTable {
                 id: row.get(0)?,
-                portfolio_name: row.get(1)?,
-                report_date: row.get(2)?,
-                original_filename: row.get(3)?,
-                version_filename: row.get(4)?,
-                file_path: row.get(5)?,
-                file_size: row.get(6)?,
-                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                upload_id: row.get(1)?,
+                portfolio_name: row.get(2)?,
+                funder_name: row.get(3)?,
+                report_date: row.get(4)?,
+                upload_type: row.get(5)?,
+                pivot_file_path: row.get(6)?,
+                total_gross: row.get(7)?,
+                total_fee: row.get(8)?,
+                total_net: row.get(9)?,
+                row_count: row.get(10)?,
+                created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(11)?)
                     .unwrap()
                     .with_timezone(&Utc),
-                is_active: row.get(8)?,
             })
-        }).optional()?;
-        
-        Ok(version)
+        })?;
+
+        pivots.collect()
     }
-    
-    pub fn get_versions_by_portfolio(&self, portfolio_name: &str) -> Result<Vec<FileVersion>> {
+
+    /// The pivot table generated for a given funder upload, if any.
+    pub fn get_pivot_table_by_upload_id(&self, upload_id: &str) -> Result<Option<FunderPivotTable>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
-                    file_path, file_size, upload_timestamp, is_active 
-             FROM file_versions 
-             WHERE portfolio_name = ?1 
-             ORDER BY report_date DESC, upload_timestamp DESC"
+            "SELECT id, upload_id, portfolio_name, funder_name, report_date, upload_type,
+                    pivot_file_path, total_gross, total_fee, total_net, row_count, created_timestamp
+             FROM funder_pivot_tables
+             WHERE upload_id = ?1"
         )?;
-        
-        let versions = stmt.query_map(params![portfolio_name], |row| {
-            Ok(FileVersion {
+
+        stmt.query_row(params![upload_id], |row| {
+            Ok(FunderPivotTable {
                 id: row.get(0)?,
-                portfolio_name: row.get(1)?,
-                report_date: row.get(2)?,
-                original_filename: row.get(3)?,
-                version_filename: row.get(4)?,
-                file_path: row.get(5)?,
-                file_size: row.get(6)?,
-                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                upload_id: row.get(1)?,
+                portfolio_name: row.get(2)?,
+                funder_name: row.get(3)?,
+                report_date: row.get(4)?,
+                upload_type: row.get(5)?,
+                pivot_file_path: row.get(6)?,
+                total_gross: row.get(7)?,
+                total_fee: row.get(8)?,
+                total_net: row.get(9)?,
+                row_count: row.get(10)?,
+                created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(11)?)
                     .unwrap()
                     .with_timezone(&Utc),
-                is_active: row.get(8)?,
             })
-        })?;
-        
-        versions.collect()
+        }).optional()
     }
-    
-    pub fn get_versions_by_date(&self, report_date: &str) -> Result<Vec<FileVersion>> {
+
+    /// Remove a pivot table's row by the upload it was generated from.
+    /// Returns whether a row was found to delete.
+    pub fn delete_pivot_table_by_upload_id(&self, upload_id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM funder_pivot_tables WHERE upload_id = ?1",
+            params![upload_id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Remove a pivot table's row by its own id, for the case (unlike
+    /// [`Database::delete_pivot_table_by_upload_id`]) where the caller
+    /// already has the pivot row itself rather than its source upload —
+    /// e.g. an integrity-check repair acting on a dangling pivot record.
+    pub fn delete_pivot_table_by_id(&self, id: &str) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "DELETE FROM funder_pivot_tables WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Plan (but do not apply) a [`RetentionPolicy`] sweep over a portfolio's
+    /// Clear View pivot CSVs (`Daily`, `Weekly`, and `Combined`), analogous to
+    /// [`Database::prune_versions`] but bucketing each pivot *type*
+    /// independently by its own report-date history, since a daily-aggregated
+    /// pivot and a weekly report pivot for the same date are unrelated files.
+    ///
+    /// Within each type, pivots are walked newest-first by report date; the
+    /// first `keep_last` are force-kept, and everything after that survives
+    /// only if it's the newest pivot seen so far in its day/ISO-week/month
+    /// bucket and that bucket class still has quota. A daily-aggregated pivot
+    /// that the bucket rules would otherwise remove is force-kept instead —
+    /// `kept_by: "combined-dependency"` — if a combined pivot for the same
+    /// report date still exists, so pruning never strands a combined pivot's
+    /// only daily source (the same "never delete everything" spirit as
+    /// [`RetentionPolicy::keeps_something`]). A pivot whose `report_date`
+    /// doesn't parse is force-kept too, rather than guessed into a bucket.
+    ///
+    /// Returns a decision for every pivot considered, kept or not, so a
+    /// dry-run caller can show which rule saved each survivor. Actually
+    /// deleting the rows/files for `removed` entries is the caller's job —
+    /// see `file_handler::prune_clearview_pivots`, which routes them through
+    /// the same write-ahead `remove_stale_pivot` path `delete_clearview_file`
+    /// uses.
+    pub fn plan_clearview_pivot_prune(
+        &self,
+        portfolio_name: &str,
+        policy: &RetentionPolicy,
+    ) -> Result<Vec<PivotPruneDecision>> {
+        if !policy.keeps_something() {
+            return Err(rusqlite::Error::InvalidColumnType(
+                0,
+                "retention policy keeps nothing; refusing to prune everything".to_string(),
+                rusqlite::types::Type::Null,
+            ));
+        }
+
+        let all_pivots: Vec<FunderPivotTable> = self
+            .get_funder_pivot_tables_by_portfolio(portfolio_name)?
+            .into_iter()
+            .filter(|p| p.funder_name == "ClearView")
+            .collect();
+
+        // Report dates still backed by a combined pivot, so the
+        // `combined-dependency` guard below can tell a daily pivot nothing
+        // depends on apart from one a combined pivot was built from.
+        let combined_report_dates: HashSet<String> = all_pivots
+            .iter()
+            .filter(|p| p.upload_type == "combined")
+            .map(|p| p.report_date.clone())
+            .collect();
+
+        let mut by_type: HashMap<String, Vec<FunderPivotTable>> = HashMap::new();
+        for pivot in all_pivots {
+            by_type.entry(pivot.upload_type.clone()).or_default().push(pivot);
+        }
+
+        let mut decisions = Vec::new();
+        let mut types: Vec<String> = by_type.keys().cloned().collect();
+        types.sort();
+
+        for upload_type in types {
+            let mut pivots = by_type.remove(&upload_type).unwrap();
+            pivots.sort_by(|a, b| {
+                let a_date = parse_pivot_report_date(&a.report_date);
+                let b_date = parse_pivot_report_date(&b.report_date);
+                b_date.cmp(&a_date)
+            });
+
+            let mut remaining_daily = policy.keep_daily;
+            let mut remaining_weekly = policy.keep_weekly;
+            let mut remaining_monthly = policy.keep_monthly;
+
+            let mut seen_days: HashSet<NaiveDate> = HashSet::new();
+            let mut seen_weeks: HashSet<(i32, u32)> = HashSet::new();
+            let mut seen_months: HashSet<(i32, u32)> = HashSet::new();
+
+            for (index, pivot) in pivots.into_iter().enumerate() {
+                let Some(date) = parse_pivot_report_date(&pivot.report_date) else {
+                    decisions.push(PivotPruneDecision {
+                        pivot,
+                        removed: false,
+                        kept_by: Some("unparseable-date".to_string()),
+                    });
+                    continue;
+                };
+
+                if index < policy.keep_last {
+                    decisions.push(PivotPruneDecision {
+                        pivot,
+                        removed: false,
+                        kept_by: Some("last".to_string()),
+                    });
+                    continue;
+                }
+
+                let iso_week = date.iso_week();
+                let is_newest_in_day = seen_days.insert(date);
+                let is_newest_in_week = seen_weeks.insert((iso_week.year(), iso_week.week()));
+                let is_newest_in_month = seen_months.insert((date.year(), date.month()));
+
+                let kept_by = if is_newest_in_day && remaining_daily > 0 {
+                    remaining_daily -= 1;
+                    Some("daily")
+                } else if is_newest_in_week && remaining_weekly > 0 {
+                    remaining_weekly -= 1;
+                    Some("weekly")
+                } else if is_newest_in_month && remaining_monthly > 0 {
+                    remaining_monthly -= 1;
+                    Some("monthly")
+                } else {
+                    None
+                };
+
+                match kept_by {
+                    Some(rule) => decisions.push(PivotPruneDecision {
+                        pivot,
+                        removed: false,
+                        kept_by: Some(rule.to_string()),
+                    }),
+                    None if upload_type == "daily_aggregated"
+                        && combined_report_dates.contains(&pivot.report_date) =>
+                    {
+                        decisions.push(PivotPruneDecision {
+                            pivot,
+                            removed: false,
+                            kept_by: Some("combined-dependency".to_string()),
+                        });
+                    }
+                    None => decisions.push(PivotPruneDecision {
+                        pivot,
+                        removed: true,
+                        kept_by: None,
+                    }),
+                }
+            }
+        }
+
+        Ok(decisions)
+    }
+
+    /// The ids of every pivot table row currently pointing at `pivot_file_path`
+    /// — in practice zero or one, since a report date's pivot CSV lives at a
+    /// deterministic path, but a crash-recovered swap could briefly leave two.
+    /// Used to find what a pending regeneration is about to make stale.
+    pub fn get_funder_pivot_table_ids_by_path(&self, pivot_file_path: &str) -> Result<Vec<String>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
-                    file_path, file_size, upload_timestamp, is_active 
-             FROM file_versions 
-             WHERE report_date = ?1 
-             ORDER BY portfolio_name, upload_timestamp DESC"
+            "SELECT id FROM funder_pivot_tables WHERE pivot_file_path = ?1"
         )?;
-        
-        let versions = stmt.query_map(params![report_date], |row| {
-            Ok(FileVersion {
-                id: row.get(0)?,
-                portfolio_name: row.get(1)?,
-                report_date: row.get(2)?,
-                original_filename: row.get(3)?,
-                version_filename: row.get(4)?,
-                file_path: row.get(5)?,
-                file_size: row.get(6)?,
-                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                is_active: row.get(8)?,
-            })
-        })?;
-        
-        versions.collect()
+        let ids = stmt.query_map(params![pivot_file_path], |row| row.get(0))?;
+        ids.collect()
     }
-    
-    pub fn get_version_by_portfolio_and_date(
-        &self, 
-        portfolio_name: &str, 
-        report_date: &str
-    ) -> Result<Option<FileVersion>> {
+
+    /// Record a write-ahead intent for an in-flight Clear View pivot swap
+    /// (see [`PendingPivotSwap`]), before the temp-file rename happens. If the
+    /// process dies before [`Database::commit_pivot_swap`] runs, this row lets
+    /// [`Database::get_pending_pivot_swaps`] find and finish or undo it on the
+    /// next startup.
+    pub fn insert_pending_pivot_swap(&self, swap: &PendingPivotSwap) -> Result<()> {
+        let new_pivot_metadata = swap
+            .new_pivot_metadata
+            .as_ref()
+            .map(|pivot| serde_json::to_string(pivot))
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let stale_pivot_ids = serde_json::to_string(&swap.stale_pivot_ids)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        self.conn.execute(
+            "INSERT INTO pending_pivot_swaps
+             (id, portfolio_name, report_date, temp_path, final_path,
+              stale_pivot_ids, new_pivot_metadata, created_timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                swap.id,
+                swap.portfolio_name,
+                swap.report_date,
+                swap.temp_path,
+                swap.final_path,
+                stale_pivot_ids,
+                new_pivot_metadata,
+                swap.created_timestamp.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every pending pivot swap left behind by an interrupted delete-and-regenerate,
+    /// for the startup recovery scan to roll forward or back.
+    pub fn get_pending_pivot_swaps(&self) -> Result<Vec<PendingPivotSwap>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
-                    file_path, file_size, upload_timestamp, is_active 
-             FROM file_versions 
-             WHERE portfolio_name = ?1 AND report_date = ?2
-             ORDER BY upload_timestamp DESC
-             LIMIT 1"
+            "SELECT id, portfolio_name, report_date, temp_path, final_path,
+                    stale_pivot_ids, new_pivot_metadata, created_timestamp
+             FROM pending_pivot_swaps"
         )?;
-        
-        let version = stmt.query_row(params![portfolio_name, report_date], |row| {
-            Ok(FileVersion {
+
+        let swaps = stmt.query_map([], |row| {
+            let stale_pivot_ids: String = row.get(5)?;
+            let new_pivot_metadata: Option<String> = row.get(6)?;
+            Ok(PendingPivotSwap {
                 id: row.get(0)?,
                 portfolio_name: row.get(1)?,
                 report_date: row.get(2)?,
-                original_filename: row.get(3)?,
-                version_filename: row.get(4)?,
-                file_path: row.get(5)?,
-                file_size: row.get(6)?,
-                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                temp_path: row.get(3)?,
+                final_path: row.get(4)?,
+                stale_pivot_ids: serde_json::from_str(&stale_pivot_ids).unwrap_or_default(),
+                new_pivot_metadata: new_pivot_metadata
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+                created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
                     .unwrap()
                     .with_timezone(&Utc),
-                is_active: row.get(8)?,
             })
-        }).optional()?;
-        
-        Ok(version)
+        })?;
+
+        swaps.collect()
     }
-    
-    pub fn delete_version(&self, id: &str) -> Result<bool> {
-        let rows_affected = self.conn.execute(
-            "DELETE FROM file_versions WHERE id = ?1",
+
+    /// Drop a pending swap's intent record without touching `funder_pivot_tables`
+    /// — used when recovery determines the swap never got far enough to need
+    /// rolling forward (e.g. its temp file is gone and its final file was never
+    /// written).
+    pub fn delete_pending_pivot_swap(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM pending_pivot_swaps WHERE id = ?1",
             params![id],
         )?;
-        Ok(rows_affected > 0)
+        Ok(())
+    }
+
+    /// Finish a pivot swap: in one transaction, remove every stale
+    /// `funder_pivot_tables` row it's replacing, insert its new row (if any),
+    /// and delete its own intent record. Called right after the temp-file
+    /// rename lands (the happy path) or by the startup recovery scan rolling
+    /// a swap forward — either way, the filesystem and the DB can only ever
+    /// be observed in their old state or their new one, never a mix.
+    pub fn commit_pivot_swap(&self, swap: &PendingPivotSwap) -> Result<()> {
+        retry_on_busy(|| self.commit_pivot_swap_once(swap))
+    }
+
+    fn commit_pivot_swap_once(&self, swap: &PendingPivotSwap) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        for stale_id in &swap.stale_pivot_ids {
+            tx.execute(
+                "DELETE FROM funder_pivot_tables WHERE id = ?1",
+                params![stale_id],
+            )?;
+        }
+
+        if let Some(pivot) = &swap.new_pivot_metadata {
+            tx.execute(
+                "INSERT OR REPLACE INTO funder_pivot_tables
+                 (id, upload_id, portfolio_name, funder_name, report_date, upload_type,
+                  pivot_file_path, total_gross, total_fee, total_net, row_count, created_timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    pivot.id,
+                    pivot.upload_id,
+                    pivot.portfolio_name,
+                    pivot.funder_name,
+                    pivot.report_date,
+                    pivot.upload_type,
+                    pivot.pivot_file_path,
+                    pivot.total_gross,
+                    pivot.total_fee,
+                    pivot.total_net,
+                    pivot.row_count,
+                    pivot.created_timestamp.to_rfc3339(),
+                ],
+            )?;
+        }
+
+        tx.execute(
+            "DELETE FROM pending_pivot_swaps WHERE id = ?1",
+            params![swap.id],
+        )?;
+
+        tx.commit()?;
+        Ok(())
     }
-    
-    pub fn set_active_version(&self, id: &str) -> Result<()> {
-        let version = self.get_version_by_id(id)?
-            .ok_or_else(|| rusqlite::Error::QueryReturnedNoRows)?;
-        
-        self.conn.execute(
-            "UPDATE file_versions SET is_active = 0 
-             WHERE portfolio_name = ?1 AND is_active = 1",
-            params![version.portfolio_name],
-        )?;
-        
+
+    /// Record a write-ahead intent for an in-flight `delete_clearview_file`
+    /// call, before its `delete_funder_upload` runs. Lets
+    /// [`Database::get_pending_clearview_deletions`] find and finish it on
+    /// the next startup if the process dies before
+    /// [`Database::delete_pending_clearview_deletion`] clears it.
+    pub fn insert_pending_clearview_deletion(&self, deletion: &PendingClearviewDeletion) -> Result<()> {
         self.conn.execute(
-            "UPDATE file_versions SET is_active = 1 WHERE id = ?1",
-            params![id],
+            "INSERT INTO pending_clearview_deletions
+             (id, upload_id, portfolio_name, report_date, is_daily, created_timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                deletion.id,
+                deletion.upload_id,
+                deletion.portfolio_name,
+                deletion.report_date,
+                deletion.is_daily,
+                deletion.created_timestamp.to_rfc3339(),
+            ],
         )?;
-        
         Ok(())
     }
-    
-    pub fn get_all_versions(&self) -> Result<Vec<FileVersion>> {
+
+    /// Every pending Clear View deletion left behind by an interrupted
+    /// `delete_clearview_file`, for the startup recovery scan to finish.
+    pub fn get_pending_clearview_deletions(&self) -> Result<Vec<PendingClearviewDeletion>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, report_date, original_filename, version_filename, 
-                    file_path, file_size, upload_timestamp, is_active 
-             FROM file_versions 
-             ORDER BY report_date DESC, portfolio_name, upload_timestamp DESC"
+            "SELECT id, upload_id, portfolio_name, report_date, is_daily, created_timestamp
+             FROM pending_clearview_deletions"
         )?;
-        
-        let versions = stmt.query_map([], |row| {
-            Ok(FileVersion {
+
+        let deletions = stmt.query_map([], |row| {
+            Ok(PendingClearviewDeletion {
                 id: row.get(0)?,
-                portfolio_name: row.get(1)?,
-                report_date: row.get(2)?,
-                original_filename: row.get(3)?,
-                version_filename: row.get(4)?,
-                file_path: row.get(5)?,
-                file_size: row.get(6)?,
-                upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                upload_id: row.get(1)?,
+                portfolio_name: row.get(2)?,
+                report_date: row.get(3)?,
+                is_daily: row.get(4)?,
+                created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
                     .unwrap()
                     .with_timezone(&Utc),
-                is_active: row.get(8)?,
             })
         })?;
-        
-        versions.collect()
+
+        deletions.collect()
     }
-    
-    // Funder Upload Methods
-    pub fn insert_funder_upload(&self, upload: &FunderUpload) -> Result<()> {
+
+    /// Drop a pending Clear View deletion's intent record once
+    /// `delete_clearview_file` (or the recovery scan finishing it) has
+    /// completed the upload deletion and the regenerate-or-remove step.
+    pub fn delete_pending_clearview_deletion(&self, id: &str) -> Result<()> {
         self.conn.execute(
-            "INSERT OR REPLACE INTO funder_uploads 
-             (id, portfolio_name, funder_name, report_date, upload_type,
-              original_filename, stored_filename, file_path, file_size, upload_timestamp) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "DELETE FROM pending_clearview_deletions WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Persist a newly-started [`Job`]. Callers go through
+    /// [`crate::jobs::begin`] rather than this directly, so the job id and
+    /// initial stage/status stay consistent.
+    pub fn create_job(&self, job: &Job) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO jobs
+             (id, job_type, portfolio_name, report_date, stage, status, error, created_timestamp, updated_timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
-                upload.id,
-                upload.portfolio_name,
-                upload.funder_name,
-                upload.report_date,
-                upload.upload_type,
-                upload.original_filename,
-                upload.stored_filename,
-                upload.file_path,
-                upload.file_size,
-                upload.upload_timestamp.to_rfc3339(),
+                job.id,
+                job.job_type,
+                job.portfolio_name,
+                job.report_date,
+                job.stage,
+                job.status.as_str(),
+                job.error,
+                job.created_timestamp.to_rfc3339(),
+                job.updated_timestamp.to_rfc3339(),
             ],
         )?;
         Ok(())
     }
-    
-    pub fn get_funder_upload(
-        &self,
-        portfolio_name: &str,
-        funder_name: &str,
-        report_date: &str,
-        upload_type: &str,
-    ) -> Result<Option<FunderUpload>> {
+
+    /// Record that `job_id` has moved on to `stage`, without changing its
+    /// status.
+    pub fn update_job_stage(&self, job_id: &str, stage: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE jobs SET stage = ?1, updated_timestamp = ?2 WHERE id = ?3",
+            params![stage, Utc::now().to_rfc3339(), job_id],
+        )?;
+        Ok(())
+    }
+
+    /// Move `job_id` to a new (typically terminal) status, recording `error`
+    /// alongside it if given.
+    pub fn update_job_status(&self, job_id: &str, status: JobStatus, error: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE jobs SET status = ?1, error = ?2, updated_timestamp = ?3 WHERE id = ?4",
+            params![status.as_str(), error, Utc::now().to_rfc3339(), job_id],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<Job> {
+        let status: String = row.get(5)?;
+
+        Ok(Job {
+            id: row.get(0)?,
+            job_type: row.get(1)?,
+            portfolio_name: row.get(2)?,
+            report_date: row.get(3)?,
+            stage: row.get(4)?,
+            status: JobStatus::from_str(&status)?,
+            error: row.get(6)?,
+            created_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
+
+    pub fn get_job(&self, job_id: &str) -> Result<Option<Job>> {
+        self.conn.query_row(
+            "SELECT id, job_type, portfolio_name, report_date, stage, status, error, created_timestamp, updated_timestamp
+             FROM jobs WHERE id = ?1",
+            params![job_id],
+            Self::row_to_job,
+        ).optional()
+    }
+
+    /// Every job ever recorded, newest first — backs the `get_jobs` command
+    /// so the UI can show in-flight and past work.
+    pub fn get_jobs(&self) -> Result<Vec<Job>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, portfolio_name, funder_name, report_date, upload_type,
-                    original_filename, stored_filename, file_path, file_size, upload_timestamp 
-             FROM funder_uploads 
-             WHERE portfolio_name = ?1 AND funder_name = ?2 AND report_date = ?3 AND upload_type = ?4"
+            "SELECT id, job_type, portfolio_name, report_date, stage, status, error, created_timestamp, updated_timestamp
+             FROM jobs ORDER BY created_timestamp DESC",
         )?;
-        
-        let upload = stmt.query_row(
-            params![portfolio_name, funder_name, report_date, upload_type], 
-            |row| {
-                Ok(FunderUpload {
-                    id: row.get(0)?,
-                    portfolio_name: row.get(1)?,
-                    funder_name: row.get(2)?,
-                    report_date: row.get(3)?,
-                    upload_type: row.get(4)?,
-                    original_filename: row.get(5)?,
-                    stored_filename: row.get(6)?,
-                    file_path: row.get(7)?,
-                    file_size: row.get(8)?,
-                    upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
-                })
-            }
-        ).optional()?;
-        
-        Ok(upload)
+        let rows = stmt.query_map([], Self::row_to_job)?;
+        rows.collect()
     }
-    
-    pub fn get_funder_uploads_by_portfolio_and_date(
-        &self,
-        portfolio_name: &str,
-        report_date: &str,
-    ) -> Result<Vec<FunderUpload>> {
+
+    /// Jobs still `Pending` or `InProgress` — the ones [`crate::jobs::recover_stuck_jobs`]
+    /// looks for on startup, since a process that's restarting can't have one
+    /// of these genuinely still running.
+    pub fn get_incomplete_jobs(&self) -> Result<Vec<Job>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, job_type, portfolio_name, report_date, stage, status, error, created_timestamp, updated_timestamp
+             FROM jobs WHERE status IN ('pending', 'in_progress') ORDER BY created_timestamp ASC",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_job)?;
+        rows.collect()
+    }
+
+    /// Overwrite a funder upload's recorded `file_size`, for reconciling it
+    /// with the file's actual on-disk size after an integrity-check repair.
+    pub fn update_funder_upload_file_size(&self, id: &str, file_size: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE funder_uploads SET file_size = ?1 WHERE id = ?2",
+            params![file_size, id],
+        )?;
+        Ok(())
+    }
+
+    /// Every funder upload across every portfolio, used by
+    /// `get_all_database_files`, `delete_funder_upload`, and the
+    /// integrity-check sweep.
+    pub fn get_all_funder_uploads(&self) -> Result<Vec<FunderUpload>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, portfolio_name, funder_name, report_date, upload_type,
-                    original_filename, stored_filename, file_path, file_size, upload_timestamp 
-             FROM funder_uploads 
-             WHERE portfolio_name = ?1 AND report_date = ?2 
-             ORDER BY upload_type, funder_name"
+                    original_filename, stored_filename, file_path, file_size, upload_timestamp,
+                    content_sha256, content_md5, codec, compressed_size, deleted_at
+             FROM funder_uploads
+             WHERE deleted_at IS NULL
+             ORDER BY report_date DESC, portfolio_name, funder_name, upload_type"
         )?;
-        
-        let uploads = stmt.query_map(params![portfolio_name, report_date], |row| {
+
+        let uploads = stmt.query_map([], |row| {
             Ok(FunderUpload {
                 id: row.get(0)?,
                 portfolio_name: row.get(1)?,
@@ -407,17 +2973,506 @@ impl Database {
                 upload_timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
                     .unwrap()
                     .with_timezone(&Utc),
+                content_sha256: row.get(10)?,
+                content_md5: row.get(11)?,
+                codec: row.get(12)?,
+                compressed_size: row.get(13)?,
+                deleted_at: row.get::<_, Option<String>>(14)?
+                    .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
             })
         })?;
-        
+
         uploads.collect()
     }
-    
-    pub fn delete_funder_upload(&self, id: &str) -> Result<bool> {
-        let rows_affected = self.conn.execute(
-            "DELETE FROM funder_uploads WHERE id = ?1",
-            params![id],
-        )?;
-        Ok(rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory connection shaped like a database that predates the
+    /// migration framework: just the original `file_versions` table, no
+    /// `user_version` stamped.
+    fn old_shaped_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE file_versions (
+                id TEXT PRIMARY KEY,
+                portfolio_name TEXT NOT NULL,
+                report_date TEXT NOT NULL,
+                original_filename TEXT NOT NULL,
+                version_filename TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                file_size INTEGER NOT NULL,
+                upload_timestamp TEXT NOT NULL,
+                is_active BOOLEAN DEFAULT 0
+            )",
+            [],
+        ).unwrap();
+        conn
+    }
+
+    #[test]
+    fn migrate_upgrades_an_old_shaped_database_cleanly() {
+        let db = Database { conn: old_shaped_connection() };
+
+        db.migrate().expect("migration should succeed");
+
+        let version: i32 = db.conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, migrations().last().unwrap().version);
+
+        for table in ["funder_uploads", "column_mappings", "advance_ledger", "merchants"] {
+            let exists: bool = db.conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1)",
+                params![table],
+                |row| row.get(0),
+            ).unwrap();
+            assert!(exists, "expected table {} to exist after migration", table);
+        }
+    }
+
+    #[test]
+    fn migrate_is_idempotent() {
+        let db = Database { conn: old_shaped_connection() };
+
+        db.migrate().expect("first migration should succeed");
+        db.migrate().expect("second migration should be a no-op, not an error");
+    }
+
+    fn fresh_database() -> Database {
+        let db = Database { conn: Connection::open_in_memory().unwrap() };
+        db.migrate().unwrap();
+        db
+    }
+
+    fn insert_version(db: &Database, id: &str, days_ago: i64, is_active: bool) {
+        let upload_timestamp = Utc::now() - chrono::Duration::days(days_ago);
+        db.insert_file_version(&FileVersion {
+            id: id.to_string(),
+            portfolio_name: "Acme".to_string(),
+            report_date: upload_timestamp.format("%Y-%m-%d").to_string(),
+            original_filename: "report.xlsx".to_string(),
+            version_filename: format!("{}.xlsx", id),
+            file_path: format!("/tmp/{}.xlsx", id),
+            file_size: 100,
+            upload_timestamp,
+            is_active,
+            content_sha256: None,
+            content_md5: None,
+            deleted_at: None,
+        }).unwrap();
+    }
+
+    #[test]
+    fn prune_versions_refuses_an_empty_policy() {
+        let db = fresh_database();
+        insert_version(&db, "v1", 0, true);
+
+        let policy = RetentionPolicy { keep_last: 0, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 0 };
+        let result = db.prune_versions("Acme", &policy, true);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn prune_versions_keeps_last_n_and_the_active_version() {
+        let db = fresh_database();
+        // Spread across distinct days so keep_daily/weekly/monthly/yearly don't interfere.
+        for (id, days_ago) in [("v1", 0), ("v2", 10), ("v3", 20), ("v4", 30), ("v5", 400)] {
+            insert_version(&db, id, days_ago, false);
+        }
+        // v5 is the oldest but force-kept as active.
+        db.set_active_version("v5").unwrap();
+
+        let policy = RetentionPolicy { keep_last: 2, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 0 };
+        let to_delete = db.prune_versions("Acme", &policy, true).unwrap();
+
+        let deleted_ids: HashSet<String> = to_delete.iter().map(|v| v.id.clone()).collect();
+        assert_eq!(deleted_ids, HashSet::from(["v3".to_string(), "v4".to_string()]));
+    }
+
+    #[test]
+    fn prune_versions_dry_run_does_not_delete() {
+        let db = fresh_database();
+        for (id, days_ago) in [("v1", 0), ("v2", 10), ("v3", 20)] {
+            insert_version(&db, id, days_ago, false);
+        }
+
+        let policy = RetentionPolicy { keep_last: 1, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 0 };
+        db.prune_versions("Acme", &policy, true).unwrap();
+
+        assert_eq!(db.get_versions_by_portfolio("Acme").unwrap().len(), 3);
+
+        let deleted = db.prune_versions("Acme", &policy, false).unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert_eq!(db.get_versions_by_portfolio("Acme").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn run_retention_is_a_no_op_without_a_configured_policy() {
+        let db = fresh_database();
+        for (id, days_ago) in [("v1", 0), ("v2", 10), ("v3", 20)] {
+            insert_version(&db, id, days_ago, false);
+        }
+
+        let (removed, orphaned_chunks) = db.run_retention("Acme").unwrap();
+        assert!(removed.is_empty());
+        assert!(orphaned_chunks.is_empty());
+        assert_eq!(db.get_versions_by_portfolio("Acme").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn run_retention_prunes_for_real_and_garbage_collects_chunks() {
+        let db = fresh_database();
+        for (id, days_ago) in [("v1", 0), ("v2", 10), ("v3", 20)] {
+            insert_version(&db, id, days_ago, false);
+        }
+        db.record_version_chunks("v3", "Acme", &["only-v3".to_string()]).unwrap();
+
+        db.set_retention_policy(
+            "Acme",
+            &RetentionPolicy { keep_last: 1, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 0 },
+        ).unwrap();
+        assert_eq!(
+            db.get_retention_policy("Acme").unwrap().unwrap().keep_last,
+            1
+        );
+
+        let (removed, orphaned_chunks) = db.run_retention("Acme").unwrap();
+        let removed_ids: HashSet<String> = removed.iter().map(|v| v.id.clone()).collect();
+        assert_eq!(removed_ids, HashSet::from(["v2".to_string(), "v3".to_string()]));
+        assert_eq!(orphaned_chunks, vec!["only-v3".to_string()]);
+        assert_eq!(db.get_versions_by_portfolio("Acme").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn delete_version_is_soft_and_reversible() {
+        let db = fresh_database();
+        insert_version(&db, "v1", 0, false);
+
+        assert!(db.delete_version("v1").unwrap());
+        assert!(db.get_version_by_id("v1").unwrap().is_none());
+        assert_eq!(db.get_versions_by_portfolio("Acme").unwrap().len(), 0);
+
+        let trashed = db.list_trashed("Acme").unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].id, "v1");
+
+        assert!(db.restore_version("v1").unwrap());
+        assert!(db.get_version_by_id("v1").unwrap().is_some());
+        assert_eq!(db.list_trashed("Acme").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn purge_version_removes_the_row_for_good() {
+        let db = fresh_database();
+        insert_version(&db, "v1", 0, false);
+
+        db.delete_version("v1").unwrap();
+        assert!(db.purge_version("v1").unwrap().is_empty());
+        assert_eq!(db.list_trashed("Acme").unwrap().len(), 0);
+        assert!(db.restore_version("v1").unwrap() == false);
+    }
+
+    #[test]
+    fn record_version_chunks_and_purge_version_garbage_collects_unreferenced_chunks() {
+        let db = fresh_database();
+        insert_version(&db, "v1", 1, false);
+        insert_version(&db, "v2", 0, false);
+
+        // v1 and v2 share chunk "shared", but each also has a chunk unique to it.
+        db.record_version_chunks("v1", "Acme", &["shared".to_string(), "only-v1".to_string()]).unwrap();
+        db.record_version_chunks("v2", "Acme", &["shared".to_string(), "only-v2".to_string()]).unwrap();
+
+        assert_eq!(
+            db.get_version_chunk_manifest("v1").unwrap(),
+            vec!["shared".to_string(), "only-v1".to_string()]
+        );
+
+        db.delete_version("v1").unwrap();
+        let mut orphaned = db.purge_version("v1").unwrap();
+        orphaned.sort();
+        // "shared" is still referenced by v2's manifest, so only "only-v1" is orphaned.
+        assert_eq!(orphaned, vec!["only-v1".to_string()]);
+        assert_eq!(db.get_version_chunk_manifest("v1").unwrap(), Vec::<String>::new());
+
+        db.delete_version("v2").unwrap();
+        let mut orphaned = db.purge_version("v2").unwrap();
+        orphaned.sort();
+        assert_eq!(orphaned, vec!["only-v2".to_string(), "shared".to_string()]);
+    }
+
+    #[test]
+    fn purge_trashed_older_than_only_sweeps_old_deletions() {
+        let db = fresh_database();
+        insert_version(&db, "old", 100, false);
+        insert_version(&db, "recent", 0, false);
+
+        // Backdate "old"'s deletion so it looks like it was trashed long ago.
+        db.delete_version("old").unwrap();
+        db.delete_version("recent").unwrap();
+        let cutoff = (Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+        db.conn.execute(
+            "UPDATE file_versions SET deleted_at = ?1 WHERE id = 'old'",
+            params![cutoff],
+        ).unwrap();
+
+        let purged = db.purge_trashed_older_than(chrono::Duration::days(1)).unwrap();
+        assert_eq!(purged, 1);
+        assert_eq!(db.list_trashed("Acme").unwrap().len(), 1);
+        assert_eq!(db.list_trashed("Acme").unwrap()[0].id, "recent");
+    }
+
+    #[test]
+    fn verify_invariants_is_clean_after_normal_inserts() {
+        let db = fresh_database();
+        insert_version(&db, "v1", 2, true);
+        insert_version(&db, "v2", 1, true);
+        insert_version(&db, "v3", 0, true);
+
+        // Every insert_file_version call deactivates the prior active row,
+        // so only the last insert ("v3") should still be active.
+        assert_eq!(db.verify_invariants().unwrap(), Vec::<String>::new());
+        assert_eq!(db.get_active_version("Acme").unwrap().unwrap().id, "v3");
+    }
+
+    #[test]
+    fn insert_file_version_rolls_back_the_deactivate_on_a_mid_operation_failure() {
+        let db = fresh_database();
+        insert_version(&db, "v1", 0, true);
+
+        // Re-inserting the same id fails the INSERT (primary key collision)
+        // after the UPDATE has already deactivated "v1" within the same
+        // transaction; the whole transaction must roll back, not just stop.
+        let result = db.insert_file_version(&FileVersion {
+            id: "v1".to_string(),
+            portfolio_name: "Acme".to_string(),
+            report_date: "2026-01-01".to_string(),
+            original_filename: "report.xlsx".to_string(),
+            version_filename: "v1.xlsx".to_string(),
+            file_path: "/tmp/v1.xlsx".to_string(),
+            file_size: 100,
+            upload_timestamp: Utc::now(),
+            is_active: true,
+            content_sha256: None,
+            content_md5: None,
+            deleted_at: None,
+        });
+        assert!(result.is_err());
+
+        assert_eq!(db.verify_invariants().unwrap(), Vec::<String>::new());
+        assert_eq!(db.get_active_version("Acme").unwrap().unwrap().id, "v1");
+    }
+
+    #[test]
+    fn set_active_version_leaves_the_active_flag_untouched_on_failure() {
+        let db = fresh_database();
+        // Insert the inactive v2 first: insert_file_version deactivates
+        // whatever is currently active, so v1 must be inserted last.
+        insert_version(&db, "v2", 0, false);
+        insert_version(&db, "v1", 1, true);
+
+        // Failing fast on an unknown id (before the transaction opens) must
+        // still leave exactly one active version — the deactivate step never
+        // gets a chance to run without a matching activate.
+        let result = db.set_active_version("does-not-exist");
+        assert!(result.is_err());
+
+        assert_eq!(db.verify_invariants().unwrap(), Vec::<String>::new());
+        assert_eq!(db.get_active_version("Acme").unwrap().unwrap().id, "v1");
+    }
+
+    fn sample_pivot(id: &str, pivot_file_path: &str) -> FunderPivotTable {
+        FunderPivotTable {
+            id: id.to_string(),
+            upload_id: "upload-1".to_string(),
+            portfolio_name: "Acme".to_string(),
+            funder_name: "ClearView".to_string(),
+            report_date: "2026-01-01".to_string(),
+            upload_type: "daily_aggregated".to_string(),
+            pivot_file_path: pivot_file_path.to_string(),
+            total_gross: 100.0,
+            total_fee: 10.0,
+            total_net: 90.0,
+            row_count: 1,
+            created_timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn commit_pivot_swap_replaces_stale_rows_and_clears_the_intent() {
+        let db = fresh_database();
+        let stale = sample_pivot("stale-1", "/tmp/2026-01-01.csv");
+        db.insert_funder_pivot_table(&stale).unwrap();
+
+        let swap = PendingPivotSwap {
+            id: "swap-1".to_string(),
+            portfolio_name: "Acme".to_string(),
+            report_date: "2026-01-01".to_string(),
+            temp_path: Some("/tmp/2026-01-01.csv.tmp-1".to_string()),
+            final_path: Some("/tmp/2026-01-01.csv".to_string()),
+            stale_pivot_ids: vec!["stale-1".to_string()],
+            new_pivot_metadata: Some(sample_pivot("new-1", "/tmp/2026-01-01.csv")),
+            created_timestamp: Utc::now(),
+        };
+        db.insert_pending_pivot_swap(&swap).unwrap();
+        assert_eq!(db.get_pending_pivot_swaps().unwrap().len(), 1);
+
+        db.commit_pivot_swap(&swap).unwrap();
+
+        assert!(db.get_pivot_table_by_upload_id("upload-1").unwrap().is_some());
+        let remaining_ids = db.get_funder_pivot_table_ids_by_path("/tmp/2026-01-01.csv").unwrap();
+        assert_eq!(remaining_ids, vec!["new-1".to_string()]);
+        assert!(db.get_pending_pivot_swaps().unwrap().is_empty());
+    }
+
+    #[test]
+    fn commit_pivot_swap_with_no_replacement_just_deletes_the_stale_rows() {
+        let db = fresh_database();
+        let stale = sample_pivot("stale-1", "/tmp/2026-01-01.csv");
+        db.insert_funder_pivot_table(&stale).unwrap();
+
+        let swap = PendingPivotSwap {
+            id: "swap-1".to_string(),
+            portfolio_name: "Acme".to_string(),
+            report_date: "2026-01-01".to_string(),
+            temp_path: None,
+            final_path: None,
+            stale_pivot_ids: vec!["stale-1".to_string()],
+            new_pivot_metadata: None,
+            created_timestamp: Utc::now(),
+        };
+        db.insert_pending_pivot_swap(&swap).unwrap();
+
+        db.commit_pivot_swap(&swap).unwrap();
+
+        assert!(db.get_funder_pivot_table_ids_by_path("/tmp/2026-01-01.csv").unwrap().is_empty());
+        assert!(db.get_pending_pivot_swaps().unwrap().is_empty());
+    }
+
+    fn insert_clearview_pivot(db: &Database, id: &str, report_date: &str, upload_type: &str) {
+        db.insert_funder_pivot_table(&FunderPivotTable {
+            id: id.to_string(),
+            upload_id: format!("upload-{}", id),
+            portfolio_name: "Acme".to_string(),
+            funder_name: "ClearView".to_string(),
+            report_date: report_date.to_string(),
+            upload_type: upload_type.to_string(),
+            pivot_file_path: format!("/tmp/{}.csv", id),
+            total_gross: 100.0,
+            total_fee: 10.0,
+            total_net: 90.0,
+            row_count: 1,
+            created_timestamp: Utc::now(),
+        }).unwrap();
+    }
+
+    #[test]
+    fn plan_clearview_pivot_prune_refuses_an_empty_policy() {
+        let db = fresh_database();
+        insert_clearview_pivot(&db, "d1", "2026-01-01", "daily_aggregated");
+
+        let policy = RetentionPolicy { keep_last: 0, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 0 };
+        assert!(db.plan_clearview_pivot_prune("Acme", &policy).is_err());
+    }
+
+    #[test]
+    fn plan_clearview_pivot_prune_keeps_last_n_per_type() {
+        let db = fresh_database();
+        for (id, date) in [("d1", "2026-01-01"), ("d2", "2025-12-25"), ("d3", "2025-12-18")] {
+            insert_clearview_pivot(&db, id, date, "daily_aggregated");
+        }
+
+        let policy = RetentionPolicy { keep_last: 1, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 0 };
+        let decisions = db.plan_clearview_pivot_prune("Acme", &policy).unwrap();
+
+        let removed: HashSet<String> = decisions.iter().filter(|d| d.removed).map(|d| d.pivot.id.clone()).collect();
+        assert_eq!(removed, HashSet::from(["d2".to_string(), "d3".to_string()]));
+
+        let kept = decisions.iter().find(|d| d.pivot.id == "d1").unwrap();
+        assert_eq!(kept.kept_by.as_deref(), Some("last"));
+    }
+
+    #[test]
+    fn plan_clearview_pivot_prune_keeps_a_daily_pivot_a_combined_pivot_depends_on() {
+        let db = fresh_database();
+        // Only one daily pivot, old enough that keep_last/keep_daily would
+        // otherwise drop it — but a combined pivot for the same date exists.
+        insert_clearview_pivot(&db, "d1", "2025-01-01", "daily_aggregated");
+        insert_clearview_pivot(&db, "c1", "2025-01-01", "combined");
+
+        // `keep_yearly` isn't one of the buckets pivot pruning honors (the
+        // request only asked for last/daily/weekly/monthly), so setting it
+        // alone satisfies `keeps_something()` without giving either pivot a
+        // bucket to be kept by — forcing the guard to be what saves "d1".
+        let policy = RetentionPolicy { keep_last: 0, keep_daily: 0, keep_weekly: 0, keep_monthly: 0, keep_yearly: 1 };
+
+        let decisions = db.plan_clearview_pivot_prune("Acme", &policy).unwrap();
+        let daily_decision = decisions.iter().find(|d| d.pivot.id == "d1").unwrap();
+        assert!(!daily_decision.removed);
+        assert_eq!(daily_decision.kept_by.as_deref(), Some("combined-dependency"));
+    }
+
+    fn sample_job(id: &str) -> Job {
+        let now = Utc::now();
+        Job {
+            id: id.to_string(),
+            job_type: "clearview_daily_pivot".to_string(),
+            portfolio_name: "Acme".to_string(),
+            report_date: "2026-01-01".to_string(),
+            stage: "started".to_string(),
+            status: JobStatus::InProgress,
+            error: None,
+            created_timestamp: now,
+            updated_timestamp: now,
+        }
+    }
+
+    #[test]
+    fn create_job_round_trips_through_get_job() {
+        let db = fresh_database();
+        db.create_job(&sample_job("job-1")).unwrap();
+
+        let job = db.get_job("job-1").unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::InProgress);
+        assert_eq!(job.stage, "started");
+    }
+
+    #[test]
+    fn update_job_stage_changes_stage_but_not_status() {
+        let db = fresh_database();
+        db.create_job(&sample_job("job-1")).unwrap();
+
+        db.update_job_stage("job-1", "storing_daily_metadata").unwrap();
+
+        let job = db.get_job("job-1").unwrap().unwrap();
+        assert_eq!(job.stage, "storing_daily_metadata");
+        assert_eq!(job.status, JobStatus::InProgress);
+    }
+
+    #[test]
+    fn update_job_status_records_the_error() {
+        let db = fresh_database();
+        db.create_job(&sample_job("job-1")).unwrap();
+
+        db.update_job_status("job-1", JobStatus::Failed, Some("disk full")).unwrap();
+
+        let job = db.get_job("job-1").unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.error.as_deref(), Some("disk full"));
+    }
+
+    #[test]
+    fn get_incomplete_jobs_excludes_terminal_statuses() {
+        let db = fresh_database();
+        db.create_job(&sample_job("job-pending")).unwrap();
+        db.create_job(&sample_job("job-done")).unwrap();
+        db.update_job_status("job-done", JobStatus::Completed, None).unwrap();
+
+        let incomplete = db.get_incomplete_jobs().unwrap();
+        let ids: HashSet<String> = incomplete.iter().map(|j| j.id.clone()).collect();
+        assert_eq!(ids, HashSet::from(["job-pending".to_string()]));
     }
 }
\ No newline at end of file
@@ -1,11 +1,18 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use rayon::prelude::*;
 use serde::{Serialize, Deserialize};
 use chrono::Utc;
+use rust_decimal::prelude::ToPrimitive;
+use tracing::{debug, info, instrument};
 use uuid::Uuid;
-use crate::database::{Database, FileVersion, FunderUpload, FunderPivotTable, Merchant};
-use crate::parsers::{BaseParser, BhbParser, BigParser, BoomParser, EfinParser, InAdvParser, KingsParser, ClearViewPivotProcessor, PortfolioParser};
+use crate::archive;
+use crate::chunk_store;
+use crate::compression;
+use crate::database::{Database, FileVersion, FunderUpload, FunderPivotTable, Merchant, PendingClearviewDeletion, PendingPivotSwap, RetentionPolicy, CompressionConfig};
+use crate::parsers::{BaseParser, BhbParser, BigParser, BoomParser, EfinParser, InAdvParser, KingsParser, ClearViewPivotProcessor, PivotTable, PortfolioParser};
+use crate::upload_session;
 
 lazy_static::lazy_static! {
     static ref DB: Mutex<Option<Database>> = Mutex::new(None);
@@ -54,13 +61,28 @@ pub fn get_excelerate_dir() -> Result<PathBuf, String> {
 pub fn init_database() -> Result<(), String> {
     let base_dir = get_excelerate_dir()?;
     let db_path = base_dir.join("excelerate.db");
-    
+
     let db = Database::new(&db_path)
         .map_err(|e| format!("Failed to initialize database: {}", e))?;
-    
+
+    // Finish or undo any Clear View pivot delete-and-regenerate that was
+    // interrupted mid-flight on a previous run, before anything else touches
+    // `funder_pivot_tables`.
+    recover_pending_pivot_swaps(&db)?;
+
+    // Finish (or restart) any `delete_clearview_file` call that was
+    // interrupted before or after its upload deletion landed. Runs after
+    // `recover_pending_pivot_swaps` so any pivot swap a previous attempt left
+    // mid-flight is already resolved before this recomputes the pivot.
+    recover_pending_clearview_deletions(&db)?;
+
+    // Any job still pending/in-progress belonged to a process that's gone
+    // now — flag it as interrupted so it shows up in `get_jobs`.
+    crate::jobs::recover_stuck_jobs(&db)?;
+
     let mut db_lock = DB.lock().unwrap();
     *db_lock = Some(db);
-    
+
     Ok(())
 }
 
@@ -141,21 +163,194 @@ pub fn ensure_directories() -> Result<(), String> {
     Ok(())
 }
 
+/// Normalize a portfolio name to the canonical form used for folder names,
+/// workbook filenames, and `BigParser::process_all`'s map keys — accepting
+/// the same `"alder"`/`"white_rabbit"`/`"whiterabbit"` aliases everywhere
+/// rather than each call site re-deriving its own variant list.
+fn canonical_portfolio_name(portfolio_name: &str) -> Option<&'static str> {
+    match portfolio_name.to_lowercase().replace(' ', "_").as_str() {
+        "alder" => Some("Alder"),
+        "white_rabbit" | "whiterabbit" => Some("White Rabbit"),
+        _ => None,
+    }
+}
+
 fn get_portfolio_dir(portfolio_name: &str) -> Result<PathBuf, String> {
     let base_dir = get_excelerate_dir()?;
-    
-    match portfolio_name.to_lowercase().replace(" ", "_").as_str() {
-        "alder" => Ok(base_dir.join("Alder")),
-        "white_rabbit" | "whiterabbit" => Ok(base_dir.join("White Rabbit")),
-        _ => Err(format!("Unknown portfolio: {}", portfolio_name)),
-    }
+
+    canonical_portfolio_name(portfolio_name)
+        .map(|name| base_dir.join(name))
+        .ok_or_else(|| format!("Unknown portfolio: {}", portfolio_name))
 }
 
 fn get_main_workbook_filename(portfolio_name: &str) -> String {
-    match portfolio_name.to_lowercase().replace(" ", "_").as_str() {
-        "alder" => "alder_portfolio_workbook.xlsx".to_string(),
-        "white_rabbit" | "whiterabbit" => "white_rabbit_portfolio_workbook.xlsx".to_string(),
-        _ => format!("{}_workbook.xlsx", portfolio_name.to_lowercase().replace(" ", "_")),
+    match canonical_portfolio_name(portfolio_name) {
+        Some("Alder") => "alder_portfolio_workbook.xlsx".to_string(),
+        Some("White Rabbit") => "white_rabbit_portfolio_workbook.xlsx".to_string(),
+        _ => format!("{}_workbook.xlsx", portfolio_name.to_lowercase().replace(' ', "_")),
+    }
+}
+
+fn chunks_dir_for(workbook_dir: &Path) -> PathBuf {
+    workbook_dir.join(".chunks")
+}
+
+/// Split `file_data` into content-defined chunks (see `chunk_store.rs`),
+/// write any chunk not already present under `chunks_dir` (keyed by its hex
+/// digest), and return the ordered manifest of chunk hashes for
+/// [`Database::record_version_chunks`].
+fn write_chunks(chunks_dir: &Path, file_data: &[u8]) -> Result<Vec<String>, String> {
+    fs::create_dir_all(chunks_dir)
+        .map_err(|e| format!("Failed to create chunk store directory: {}", e))?;
+
+    let mut manifest = Vec::new();
+    for chunk in chunk_store::split_into_chunks(file_data) {
+        let chunk_hash = chunk_store::hash_chunk(chunk);
+        let chunk_path = chunks_dir.join(&chunk_hash);
+        if !chunk_path.exists() {
+            fs::write(&chunk_path, chunk)
+                .map_err(|e| format!("Failed to write chunk {}: {}", chunk_hash, e))?;
+        }
+        manifest.push(chunk_hash);
+    }
+
+    Ok(manifest)
+}
+
+/// Concatenate a version's chunks in manifest order to reconstruct the
+/// original workbook bytes.
+fn reassemble_from_chunks(chunks_dir: &Path, manifest: &[String]) -> Result<Vec<u8>, String> {
+    let mut file_data = Vec::new();
+    for chunk_hash in manifest {
+        let chunk_path = chunks_dir.join(chunk_hash);
+        let chunk_bytes = fs::read(&chunk_path)
+            .map_err(|e| format!("Failed to read chunk {}: {}", chunk_hash, e))?;
+        file_data.extend_from_slice(&chunk_bytes);
+    }
+    Ok(file_data)
+}
+
+/// Delete chunk files that lost their last reference, ignoring any already
+/// missing (e.g. a prior partial cleanup).
+fn delete_orphaned_chunks(chunks_dir: &Path, chunk_hashes: &[String]) {
+    for chunk_hash in chunk_hashes {
+        let chunk_path = chunks_dir.join(chunk_hash);
+        if chunk_path.exists() {
+            if let Err(e) = fs::remove_file(&chunk_path) {
+                eprintln!("Failed to delete orphaned chunk {}: {}", chunk_hash, e);
+            }
+        }
+    }
+}
+
+/// The compression config a portfolio's Clear View pivot writes should use,
+/// defaulting to disabled if the database isn't initialized yet or has no
+/// row for it. Used to build each `ClearViewPivotProcessor` via
+/// `with_compression_config` rather than threading `&Database` through its
+/// constructor (which would break its existing no-DB test call sites).
+fn compression_config_for(portfolio_name: &str) -> CompressionConfig {
+    let db_lock = DB.lock().unwrap();
+    db_lock
+        .as_ref()
+        .map(|db| db.get_compression_config(portfolio_name).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+/// Portfolio-wide (not per-funder) content-addressed store for funder
+/// upload bytes, so the same file re-uploaded under a different funder name
+/// or report date still dedups against whatever already hashes the same.
+fn funder_blobs_dir_for(portfolio_dir: &Path) -> PathBuf {
+    portfolio_dir.join("Funder Uploads").join("objects")
+}
+
+/// Write `file_data` to the blob store under `objects/<first2hex>/<fullhash>`,
+/// skipping the write if a blob with this hash is already stored, and
+/// return its path alongside the codec/on-disk size it was actually stored
+/// with — `None`/`None` for a raw blob. `compress` zstd-encodes the bytes
+/// before writing (see `compression.rs`); read sites detect this from the
+/// blob's own magic-byte header rather than trusting the caller, so an
+/// already-stored blob's existing codec wins over a config change.
+fn write_funder_blob(
+    blobs_dir: &Path,
+    content_sha256: &str,
+    file_data: &[u8],
+    compress: bool,
+    level: i32,
+) -> Result<(PathBuf, Option<String>, Option<i64>), String> {
+    let shard_dir = blobs_dir.join(&content_sha256[..2]);
+    fs::create_dir_all(&shard_dir)
+        .map_err(|e| format!("Failed to create blob shard directory: {}", e))?;
+
+    let blob_path = shard_dir.join(content_sha256);
+    if blob_path.exists() {
+        let existing = fs::read(&blob_path)
+            .map_err(|e| format!("Failed to read existing funder upload blob: {}", e))?;
+        return Ok(if compression::is_compressed(&existing) {
+            (blob_path, Some("zstd".to_string()), Some(existing.len() as i64))
+        } else {
+            (blob_path, None, None)
+        });
+    }
+
+    if compress {
+        let compressed = compression::compress(file_data, level)
+            .map_err(|e| format!("Failed to compress funder upload: {}", e))?;
+        fs::write(&blob_path, &compressed)
+            .map_err(|e| format!("Failed to write funder upload blob: {}", e))?;
+        Ok((blob_path, Some("zstd".to_string()), Some(compressed.len() as i64)))
+    } else {
+        fs::write(&blob_path, file_data)
+            .map_err(|e| format!("Failed to write funder upload blob: {}", e))?;
+        Ok((blob_path, None, None))
+    }
+}
+
+/// Like [`write_funder_blob`], but the bytes already live in `temp_path` on
+/// disk (assembled by [`upload_session::push_upload_chunk`]) instead of an
+/// in-memory buffer. A raw (uncompressed) blob is moved into place with a
+/// single rename instead of being read into memory and copied, falling back
+/// to copy-then-remove if `temp_path` and the blob store aren't on the same
+/// filesystem. Compression still requires the whole buffer, since
+/// `compression::compress` isn't a streaming API.
+fn move_funder_blob_from_temp(
+    blobs_dir: &Path,
+    content_sha256: &str,
+    temp_path: &Path,
+    compress: bool,
+    level: i32,
+) -> Result<(PathBuf, Option<String>, Option<i64>), String> {
+    let shard_dir = blobs_dir.join(&content_sha256[..2]);
+    fs::create_dir_all(&shard_dir)
+        .map_err(|e| format!("Failed to create blob shard directory: {}", e))?;
+
+    let blob_path = shard_dir.join(content_sha256);
+    if blob_path.exists() {
+        let existing = fs::read(&blob_path)
+            .map_err(|e| format!("Failed to read existing funder upload blob: {}", e))?;
+        let _ = fs::remove_file(temp_path);
+        return Ok(if compression::is_compressed(&existing) {
+            (blob_path, Some("zstd".to_string()), Some(existing.len() as i64))
+        } else {
+            (blob_path, None, None)
+        });
+    }
+
+    if compress {
+        let file_data = fs::read(temp_path)
+            .map_err(|e| format!("Failed to read assembled upload: {}", e))?;
+        let compressed = compression::compress(&file_data, level)
+            .map_err(|e| format!("Failed to compress funder upload: {}", e))?;
+        fs::write(&blob_path, &compressed)
+            .map_err(|e| format!("Failed to write funder upload blob: {}", e))?;
+        let _ = fs::remove_file(temp_path);
+        Ok((blob_path, Some("zstd".to_string()), Some(compressed.len() as i64)))
+    } else {
+        if fs::rename(temp_path, &blob_path).is_err() {
+            fs::copy(temp_path, &blob_path)
+                .map_err(|e| format!("Failed to move assembled upload into blob store: {}", e))?;
+            let _ = fs::remove_file(temp_path);
+        }
+        Ok((blob_path, None, None))
     }
 }
 
@@ -187,10 +382,15 @@ pub fn save_portfolio_workbook_with_version(
         file_extension
     );
     let version_path = versions_dir.join(&version_filename);
-    
-    fs::write(&version_path, &file_data)
-        .map_err(|e| format!("Failed to save version file: {}", e))?;
-    
+
+    // Versions are stored as a manifest of content-addressed chunks under
+    // Workbook/.chunks/ rather than a standalone copy at `version_path`, so
+    // near-identical weekly workbooks only pay for the bytes that changed.
+    // `version_path` is kept as the version's identity/filename for display
+    // and for legacy (pre-chunking) versions that still have a real file there.
+    let chunks_dir = chunks_dir_for(&workbook_dir);
+    let chunk_hashes = write_chunks(&chunks_dir, &file_data)?;
+
     let main_filename = get_main_workbook_filename(portfolio_name);
     let main_path = workbook_dir.join(&main_filename);
     
@@ -198,7 +398,8 @@ pub fn save_portfolio_workbook_with_version(
         .map_err(|e| format!("Failed to save main workbook: {}", e))?;
     
     let file_size = file_data.len() as i64;
-    
+    let (content_sha256, content_md5) = crate::database::hash_content(&file_data);
+
     let version = FileVersion {
         id: version_id.clone(),
         portfolio_name: portfolio_name.to_string(),
@@ -209,13 +410,26 @@ pub fn save_portfolio_workbook_with_version(
         file_size,
         upload_timestamp: Utc::now(),
         is_active: true,
+        content_sha256: Some(content_sha256),
+        content_md5: Some(content_md5),
+        deleted_at: None,
     };
-    
+
+    let mut message = format!("Workbook saved successfully with version tracking");
+
     let db_lock = DB.lock().unwrap();
     if let Some(db) = db_lock.as_ref() {
-        db.insert_file_version(&version)
+        let duplicate_of = db.insert_file_version(&version)
             .map_err(|e| format!("Failed to save version to database: {}", e))?;
-        
+        db.record_version_chunks(&version.id, portfolio_name, &chunk_hashes)
+            .map_err(|e| format!("Failed to record chunk manifest: {}", e))?;
+        if let Some(duplicate) = duplicate_of {
+            message = format!(
+                "{} (note: identical content already uploaded as version {} on {})",
+                message, duplicate.id, duplicate.report_date
+            );
+        }
+
         // Extract merchants from the workbook
         let parser = PortfolioParser::new(portfolio_name.to_string());
         match parser.parse_portfolio_workbook(&main_path, db) {
@@ -228,10 +442,17 @@ pub fn save_portfolio_workbook_with_version(
             }
         }
     }
-    
+    drop(db_lock);
+
+    // Let old snapshots expire on their own if this portfolio has a
+    // retention policy configured; a missing policy is a no-op.
+    if let Err(e) = run_retention(portfolio_name) {
+        eprintln!("Failed to run retention sweep: {}", e);
+    }
+
     Ok(UploadResponse {
         success: true,
-        message: format!("Workbook saved successfully with version tracking"),
+        message,
         file_path: Some(main_path.to_string_lossy().to_string()),
         version_id: Some(version_id),
         backup_path: Some(version_path.to_string_lossy().to_string()),
@@ -272,42 +493,163 @@ pub fn get_versions_by_date(report_date: &str) -> Result<Vec<VersionInfo>, Strin
     }
 }
 
+/// Read a version's bytes back, whether it's stored as a chunk manifest or
+/// (for versions written before chunking existed) as a standalone file at
+/// `version.file_path`. Shared by [`restore_version`] and [`verify_version`]
+/// so both agree on exactly what bytes a version resolves to.
+fn read_version_bytes(db: &Database, version: &FileVersion, workbook_dir: &Path) -> Result<Vec<u8>, String> {
+    let manifest = db.get_version_chunk_manifest(&version.id)
+        .map_err(|e| format!("Failed to load chunk manifest: {}", e))?;
+
+    if manifest.is_empty() {
+        let version_path = Path::new(&version.file_path);
+        if !version_path.exists() {
+            return Err("Version file not found".to_string());
+        }
+        fs::read(version_path)
+            .map_err(|e| format!("Failed to read version file: {}", e))
+    } else {
+        let chunks_dir = chunks_dir_for(workbook_dir);
+        reassemble_from_chunks(&chunks_dir, &manifest)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    Ok,
+    Mismatch,
+    Missing,
+    Unverifiable,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionIntegrityReport {
+    pub version_id: String,
+    pub report_date: String,
+    pub status: IntegrityStatus,
+    pub detail: Option<String>,
+}
+
+/// Re-hash a version's stored bytes and compare against its recorded
+/// `content_sha256`, without touching the live workbook. Used both by the
+/// standalone `verify_version`/`verify_portfolio` commands and by
+/// [`restore_version`]'s pre-restore check.
+fn verify_version_integrity(db: &Database, version: &FileVersion) -> Result<VersionIntegrityReport, String> {
+    let portfolio_dir = get_portfolio_dir(&version.portfolio_name)?;
+    let workbook_dir = portfolio_dir.join("Workbook");
+
+    let report = |status: IntegrityStatus, detail: Option<String>| VersionIntegrityReport {
+        version_id: version.id.clone(),
+        report_date: version.report_date.clone(),
+        status,
+        detail,
+    };
+
+    let file_data = match read_version_bytes(db, version, &workbook_dir) {
+        Ok(data) => data,
+        Err(e) => return Ok(report(IntegrityStatus::Missing, Some(e))),
+    };
+
+    let Some(expected_sha256) = &version.content_sha256 else {
+        return Ok(report(
+            IntegrityStatus::Unverifiable,
+            Some("No content hash recorded for this version".to_string()),
+        ));
+    };
+
+    let (actual_sha256, _) = crate::database::hash_content(&file_data);
+    if &actual_sha256 == expected_sha256 {
+        Ok(report(IntegrityStatus::Ok, None))
+    } else {
+        Ok(report(
+            IntegrityStatus::Mismatch,
+            Some(format!(
+                "Expected sha256 {} but stored data hashes to {}",
+                expected_sha256, actual_sha256
+            )),
+        ))
+    }
+}
+
+/// Re-hash a single version's on-disk data and compare it to the digest
+/// recorded at upload time, without restoring it as the live workbook.
+#[tauri::command]
+pub fn verify_version(version_id: &str) -> Result<VersionIntegrityReport, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let version = db.get_version_by_id(version_id)
+        .map_err(|e| format!("Failed to get version: {}", e))?
+        .ok_or_else(|| "Version not found".to_string())?;
+
+    verify_version_integrity(db, &version)
+}
+
+/// Check every version row for a portfolio and report which are intact,
+/// corrupted, missing, or predate content hashing, so the UI can flag which
+/// ones are safe to restore before the user picks one.
+#[tauri::command]
+pub fn verify_portfolio(portfolio_name: &str) -> Result<Vec<VersionIntegrityReport>, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let versions = db.get_versions_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load versions: {}", e))?;
+
+    versions.iter().map(|version| verify_version_integrity(db, version)).collect()
+}
+
 #[tauri::command]
 pub fn restore_version(version_id: &str) -> Result<UploadResponse, String> {
     if DB.lock().unwrap().is_none() {
         init_database()?;
     }
-    
+
     let db_lock = DB.lock().unwrap();
     if let Some(db) = db_lock.as_ref() {
         let version = db.get_version_by_id(version_id)
             .map_err(|e| format!("Failed to get version: {}", e))?
             .ok_or_else(|| "Version not found".to_string())?;
-        
-        let version_path = Path::new(&version.file_path);
-        if !version_path.exists() {
-            return Err("Version file not found".to_string());
-        }
-        
-        let file_data = fs::read(version_path)
-            .map_err(|e| format!("Failed to read version file: {}", e))?;
-        
+
         let portfolio_dir = get_portfolio_dir(&version.portfolio_name)?;
+        let workbook_dir = portfolio_dir.join("Workbook");
+
+        let file_data = read_version_bytes(db, &version, &workbook_dir)?;
+
+        if let Some(expected_sha256) = &version.content_sha256 {
+            let (actual_sha256, _) = crate::database::hash_content(&file_data);
+            if &actual_sha256 != expected_sha256 {
+                return Err(format!(
+                    "Refusing to restore version {}: stored data is corrupted (expected sha256 {}, got {})",
+                    version_id, expected_sha256, actual_sha256
+                ));
+            }
+        }
+
         let main_filename = get_main_workbook_filename(&version.portfolio_name);
-        let main_path = portfolio_dir.join("Workbook").join(&main_filename);
-        
+        let main_path = workbook_dir.join(&main_filename);
+
         fs::write(&main_path, file_data)
             .map_err(|e| format!("Failed to restore workbook: {}", e))?;
-        
+
         db.set_active_version(version_id)
             .map_err(|e| format!("Failed to update active version: {}", e))?;
-        
+
         Ok(UploadResponse {
             success: true,
             message: format!("Version restored successfully"),
             file_path: Some(main_path.to_string_lossy().to_string()),
             version_id: Some(version_id.to_string()),
-            backup_path: Some(version_path.to_string_lossy().to_string()),
+            backup_path: Some(version.file_path.clone()),
         })
     } else {
         Err("Database not initialized".to_string())
@@ -373,6 +715,136 @@ pub fn delete_version(version_id: &str) -> Result<bool, String> {
     }
 }
 
+/// Permanently remove a (normally already soft-deleted) version, releasing
+/// its chunk references and deleting any chunk files that drop to zero
+/// references as a result. Returns whether a version was found to purge.
+#[tauri::command]
+pub fn purge_version(version_id: &str) -> Result<bool, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    if let Some(db) = db_lock.as_ref() {
+        let portfolio_name = db.get_version_portfolio_name(version_id)
+            .map_err(|e| format!("Failed to look up version: {}", e))?;
+
+        let orphaned_chunks = db.purge_version(version_id)
+            .map_err(|e| format!("Failed to purge version: {}", e))?;
+
+        if let Some(portfolio_name) = &portfolio_name {
+            if !orphaned_chunks.is_empty() {
+                let workbook_dir = get_portfolio_dir(portfolio_name)?.join("Workbook");
+                delete_orphaned_chunks(&chunks_dir_for(&workbook_dir), &orphaned_chunks);
+            }
+        }
+
+        Ok(portfolio_name.is_some())
+    } else {
+        Err("Database not initialized".to_string())
+    }
+}
+
+/// Configure the version retention policy a portfolio is automatically
+/// pruned against (see [`run_retention`]).
+#[tauri::command]
+pub fn set_retention_policy(portfolio_name: &str, policy: RetentionPolicy) -> Result<(), String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    if let Some(db) = db_lock.as_ref() {
+        db.set_retention_policy(portfolio_name, &policy)
+            .map_err(|e| format!("Failed to save retention policy: {}", e))
+    } else {
+        Err("Database not initialized".to_string())
+    }
+}
+
+/// Evaluate a portfolio's configured retention policy for real, deleting
+/// the DB rows, chunk files, and legacy standalone files of every expired
+/// version. A no-op if no policy is configured. Returns the number of
+/// versions removed. Invoked automatically after every successful upload
+/// via [`save_portfolio_workbook_with_version`], but also exposed directly
+/// so the UI can trigger an immediate sweep.
+#[tauri::command]
+pub fn run_retention(portfolio_name: &str) -> Result<usize, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    if let Some(db) = db_lock.as_ref() {
+        let (removed, orphaned_chunks) = db.run_retention(portfolio_name)
+            .map_err(|e| format!("Failed to run retention: {}", e))?;
+
+        if !removed.is_empty() {
+            let workbook_dir = get_portfolio_dir(portfolio_name)?.join("Workbook");
+
+            for version in &removed {
+                let version_path = Path::new(&version.file_path);
+                if version_path.exists() {
+                    if let Err(e) = fs::remove_file(version_path) {
+                        eprintln!("Failed to delete expired version file {}: {}", version.file_path, e);
+                    }
+                }
+            }
+
+            if !orphaned_chunks.is_empty() {
+                delete_orphaned_chunks(&chunks_dir_for(&workbook_dir), &orphaned_chunks);
+            }
+        }
+
+        Ok(removed.len())
+    } else {
+        Err("Database not initialized".to_string())
+    }
+}
+
+/// Configure whether new funder uploads for a portfolio are zstd-compressed
+/// in the blob store (see [`write_funder_blob`]). Existing blobs are
+/// unaffected; every read site transparently inflates based on a magic-byte
+/// check rather than this config, so toggling it never breaks old uploads.
+#[tauri::command]
+pub fn set_compression_config(portfolio_name: &str, config: CompressionConfig) -> Result<(), String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    if let Some(db) = db_lock.as_ref() {
+        db.set_compression_config(portfolio_name, &config)
+            .map_err(|e| format!("Failed to save compression config: {}", e))
+    } else {
+        Err("Database not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn get_compression_config(portfolio_name: &str) -> Result<CompressionConfig, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    if let Some(db) = db_lock.as_ref() {
+        db.get_compression_config(portfolio_name)
+            .map_err(|e| format!("Failed to load compression config: {}", e))
+    } else {
+        Err("Database not initialized".to_string())
+    }
+}
+
+/// Raise or lower the app's tracing verbosity at runtime — e.g. `"debug"` or
+/// a directive string like `"excelerate_lib::file_handler=trace"` — so
+/// support staff can get more detail out of the rolling log file (see
+/// `logging.rs`) without a rebuild.
+#[tauri::command]
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    crate::logging::set_log_level(level)
+}
+
 #[tauri::command]
 pub fn get_portfolio_workbook_path(portfolio_name: &str) -> Result<String, String> {
     let portfolio_dir = get_portfolio_dir(portfolio_name)?;
@@ -420,8 +892,8 @@ fn process_clearview_file(
     let processor = ClearViewPivotProcessor::new(
         portfolio_name.to_string(),
         report_date.to_string(),
-    );
-    
+    ).with_compression_config(compression_config_for(portfolio_name));
+
     // Determine if this is a daily or weekly file based on path structure
     let path_str = file_path.to_string_lossy();
     let is_daily = path_str.contains("/Daily/") || path_str.contains("\\Daily\\");
@@ -460,6 +932,9 @@ fn process_clearview_file(
                 &combined_pivot,
                 crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
             ).map_err(|e| format!("Failed to store combined pivot metadata: {}", e))?;
+
+            processor.record_weekly_collections(db, &combined_pivot)
+                .map_err(|e| format!("Failed to record ledger events: {}", e))?;
         }
     } else {
         // Process weekly file
@@ -488,6 +963,9 @@ fn process_clearview_file(
                 &combined_pivot,
                 crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
             ).map_err(|e| format!("Failed to store combined pivot metadata: {}", e))?;
+
+            processor.record_weekly_collections(db, &combined_pivot)
+                .map_err(|e| format!("Failed to record ledger events: {}", e))?;
         }
     }
     
@@ -520,9 +998,19 @@ fn process_funder_file(
                 .map_err(|e| format!("Failed to parse BHB file: {}", e))?
         },
         "BIG" => {
+            // A BIG workbook can carry more than one portfolio's sheet (Alder/R&H
+            // and White Rabbit together); process_all splits them out so the
+            // pivot actually matches the portfolio this upload was filed under,
+            // instead of silently taking whichever sheet appears first.
             let parser = BigParser::new();
-            parser.process(file_path)
-                .map_err(|e| format!("Failed to parse BIG file: {}", e))?
+            let mut pivots = parser.process_all(file_path)
+                .map_err(|e| format!("Failed to parse BIG file: {}", e))?;
+            let canonical_portfolio = canonical_portfolio_name(portfolio_name).unwrap_or(portfolio_name);
+            pivots.remove(canonical_portfolio).ok_or_else(|| format!(
+                "BIG file has no '{}' portfolio sheet (found: {})",
+                portfolio_name,
+                pivots.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))?
         },
         "eFin" => {
             let parser = EfinParser::new();
@@ -579,9 +1067,9 @@ fn process_funder_file(
         report_date: report_date.to_string(),
         upload_type: upload_type.to_string(),
         pivot_file_path: pivot_path.to_string_lossy().to_string(),
-        total_gross: pivot_table.total_gross,
-        total_fee: pivot_table.total_fee,
-        total_net: pivot_table.total_net,
+        total_gross: pivot_table.total_gross.to_f64().unwrap_or(0.0),
+        total_fee: pivot_table.total_fee.to_f64().unwrap_or(0.0),
+        total_net: pivot_table.total_net.to_f64().unwrap_or(0.0),
         row_count: (pivot_table.rows.len() - 1) as i32, // Subtract 1 for totals row
         created_timestamp: Utc::now(),
     };
@@ -624,6 +1112,7 @@ impl From<FunderUpload> for FunderUploadInfo {
 }
 
 #[tauri::command]
+#[instrument(skip(file_data), fields(portfolio = %portfolio_name, funder = %funder_name, report_date = %report_date, upload_type = %upload_type))]
 pub fn save_funder_upload(
     portfolio_name: &str,
     funder_name: &str,
@@ -632,97 +1121,154 @@ pub fn save_funder_upload(
     report_date: &str,
     upload_type: &str, // "weekly" or "monthly"
 ) -> Result<UploadResponse, String> {
-    ensure_directories()?;
-    
-    if DB.lock().unwrap().is_none() {
-        init_database()?;
-    }
-    
-    let portfolio_dir = get_portfolio_dir(portfolio_name)?;
-    
-    // Debug logging commented out to avoid issues with frontend
-    // println!("Processing upload - Portfolio: {}, Funder: {}, File: {}, Date: {}, Type: {}", 
-    //     portfolio_name, funder_name, file_name, report_date, upload_type);
-    
+    complete_funder_upload(portfolio_name, funder_name, file_data, file_name, report_date, upload_type)
+}
+
+/// The (funder-name, stored-filename) layout decisions shared by every path
+/// that lands a funder upload's bytes somewhere, regardless of whether they
+/// arrived as one `Vec<u8>` or were assembled from chunks.
+struct ResolvedUploadNaming {
+    stored_filename: String,
+    final_funder_name: String,
+    is_clearview: bool,
+}
+
+/// Work out where an upload's bytes are logically filed — funder name
+/// normalization and the Clear View daily/weekly split — independent of how
+/// those bytes actually reached disk. The content-addressed blob store (see
+/// `write_funder_blob`) means this naming only ever shows up in the DB row,
+/// never in a real directory path.
+fn resolve_upload_naming(
+    funder_name: &str,
+    file_name: &str,
+    report_date: &str,
+    upload_type: &str,
+) -> ResolvedUploadNaming {
     // Check if this is a Clear View file (handle various naming patterns from frontend)
-    let is_clearview = funder_name == "Clear View" 
-        || funder_name == "ClearView" 
+    let is_clearview = funder_name == "Clear View"
+        || funder_name == "ClearView"
         || funder_name.starts_with("ClearView_Daily")
         || funder_name.starts_with("Clear View Daily");
-    
-    // Normalize the funder name for Clear View (removed - using final_funder_name in tuple instead)
-    
-    // Special handling for Clear View files
-    let (funder_dir, stored_filename, final_funder_name) = if is_clearview {
+
+    let (stored_filename, final_funder_name) = if is_clearview {
         // Determine if this is a daily or weekly Clear View file
-        // Check multiple indicators
-        let is_daily = upload_type == "daily" 
+        let is_daily = upload_type == "daily"
             || funder_name.contains("Daily")
             || file_name.to_lowercase().contains("syndicate_report");
-        
-        // println!("Clear View file detected - Is Daily: {}", is_daily);
-        
+
         if is_daily {
-            // All daily files for a week go into a single folder based on the report date (Friday)
-            let folder_date = report_date.replace('/', "-");
-            
-            let daily_dir = portfolio_dir
-                .join("Funder Uploads")
-                .join("Weekly")  // Daily files still go under Weekly folder structure
-                .join("Clear View")
-                .join("Daily")
-                .join(&folder_date);
-            
             // Keep original filename for daily files
-            (daily_dir, file_name.to_string(), "Clear View".to_string())
+            (file_name.to_string(), "Clear View".to_string())
         } else {
-            // Weekly files go to Weekly/Clear View/Weekly/
-            let weekly_dir = portfolio_dir
-                .join("Funder Uploads")
-                .join("Weekly")
-                .join("Clear View")
-                .join("Weekly");
-            
             // Use report date as filename for weekly files (convert to consistent format)
             let file_date = report_date.replace('/', "-");
             let file_extension = Path::new(file_name)
                 .extension()
                 .and_then(|ext| ext.to_str())
                 .unwrap_or("csv");
-            (weekly_dir, format!("{}.{}", file_date, file_extension), "Clear View".to_string())
+            (format!("{}.{}", file_date, file_extension), "Clear View".to_string())
         }
     } else {
-        // Standard funder directory structure for non-Clear View funders
-        let funder_dir = portfolio_dir
-            .join("Funder Uploads")
-            .join(if upload_type == "weekly" { "Weekly" } else { "Monthly" })
-            .join(funder_name);
-        
         // Generate filename using report date and original extension
         let file_extension = Path::new(file_name)
             .extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("csv");
-        (funder_dir, format!("{}.{}", report_date, file_extension), funder_name.to_string())
+        (format!("{}.{}", report_date, file_extension), funder_name.to_string())
     };
-    
-    // Create funder directory if it doesn't exist
-    // println!("Creating directory: {:?}", funder_dir);
-    fs::create_dir_all(&funder_dir)
-        .map_err(|e| format!("Failed to create funder directory: {}", e))?;
-    
-    let file_path = funder_dir.join(&stored_filename);
-    // println!("Saving file to: {:?}", file_path);
-    
-    // Write the file
-    fs::write(&file_path, &file_data)
-        .map_err(|e| format!("Failed to save funder file: {}", e))?;
-    
-    // println!("File saved successfully");
-    
+
+    ResolvedUploadNaming { stored_filename, final_funder_name, is_clearview }
+}
+
+/// Everything that happens once a funder upload's full bytes are in hand:
+/// content-address and write the blob, insert the DB row, and run pivot
+/// processing. Used by [`save_funder_upload`] directly.
+#[instrument(skip(file_data), fields(portfolio = %portfolio_name, funder = %funder_name, report_date = %report_date, upload_type = %upload_type))]
+fn complete_funder_upload(
+    portfolio_name: &str,
+    funder_name: &str,
+    file_data: Vec<u8>,
+    file_name: &str,
+    report_date: &str,
+    upload_type: &str,
+) -> Result<UploadResponse, String> {
+    ensure_directories()?;
+    debug!("directories ensured");
+
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let portfolio_dir = get_portfolio_dir(portfolio_name)?;
+
+    info!(file_name, file_size = file_data.len(), "processing funder upload");
+
+    let naming = resolve_upload_naming(funder_name, file_name, report_date, upload_type);
+
     let file_size = file_data.len() as i64;
+    let (content_sha256, content_md5) = crate::database::hash_content(&file_data);
+
+    // Funder CSVs are the highly-compressible, weekly-accumulating case this
+    // targets; Excel uploads (Kings/Boom) are left raw to avoid compressing
+    // files the non-ClearView parsers (BigParser, BoomParser, etc.) still
+    // open straight off disk via calamine.
+    let is_csv = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false);
+    let compression_config = compression_config_for(portfolio_name);
+
+    // Write once under a content-addressed path shared by the whole
+    // portfolio, so re-uploading an unchanged file (or the same daily file
+    // logged under two funder-name spellings) never duplicates bytes on
+    // disk — only a new metadata row pointing at the existing blob.
+    let blobs_dir = funder_blobs_dir_for(&portfolio_dir);
+    let (file_path, codec, compressed_size) = write_funder_blob(
+        &blobs_dir,
+        &content_sha256,
+        &file_data,
+        is_csv && compression_config.enabled,
+        compression_config.level,
+    )?;
+    debug!(file_path = %file_path.display(), codec = ?codec, "wrote funder upload blob");
+
+    finish_funder_upload_record(
+        portfolio_name,
+        file_name,
+        report_date,
+        upload_type,
+        naming,
+        file_path,
+        file_size,
+        content_sha256,
+        content_md5,
+        codec,
+        compressed_size,
+    )
+}
+
+/// Insert the DB row for an already-written blob and run (or defer) pivot
+/// processing. Shared tail for [`complete_funder_upload`] (bytes arrived in
+/// one call) and [`finish_upload`] (bytes assembled from chunks and moved
+/// into the blob store directly).
+#[allow(clippy::too_many_arguments)]
+fn finish_funder_upload_record(
+    portfolio_name: &str,
+    file_name: &str,
+    report_date: &str,
+    upload_type: &str,
+    naming: ResolvedUploadNaming,
+    file_path: PathBuf,
+    file_size: i64,
+    content_sha256: String,
+    content_md5: String,
+    codec: Option<String>,
+    compressed_size: Option<i64>,
+) -> Result<UploadResponse, String> {
+    let ResolvedUploadNaming { stored_filename, final_funder_name, is_clearview } = naming;
     let upload_id = Uuid::new_v4().to_string();
-    
+
     // Save to database with normalized funder name
     let funder_upload = FunderUpload {
         id: upload_id.clone(),
@@ -731,27 +1277,42 @@ pub fn save_funder_upload(
         report_date: report_date.to_string(),
         upload_type: upload_type.to_string(), // Keep the original upload_type (daily remains daily)
         original_filename: file_name.to_string(),
-        stored_filename: stored_filename.clone(),
+        stored_filename,
         file_path: file_path.to_string_lossy().to_string(),
         file_size,
         upload_timestamp: Utc::now(),
+        content_sha256: Some(content_sha256),
+        content_md5: Some(content_md5),
+        codec,
+        compressed_size,
+        deleted_at: None,
     };
-    
+
     // Insert funder upload to database and immediately release the lock
+    let mut duplicate_note = String::new();
     {
         let db_lock = DB.lock().unwrap();
         if let Some(db) = db_lock.as_ref() {
-            db.insert_funder_upload(&funder_upload)
+            let duplicate_of = db.insert_funder_upload(&funder_upload)
                 .map_err(|e| format!("Failed to save funder upload to database: {}", e))?;
+            if let Some(duplicate) = duplicate_of {
+                duplicate_note = format!(
+                    " (note: identical content already uploaded as {} on {})",
+                    duplicate.stored_filename, duplicate.report_date
+                );
+            }
         }
     }  // db_lock is dropped here
-    
+    info!(upload_id = %upload_id, "funder upload row inserted");
+
     // For Clear View daily files, skip immediate processing to allow multiple files to be uploaded first
     // The frontend should call process_clearview_daily_pivot after all files are uploaded
     let pivot_result = if is_clearview && upload_type == "daily" {
+        debug!("Clear View daily file, deferring pivot processing");
         Ok(()) // Skip processing for Clear View daily files
     } else {
         // Process other funders normally
+        debug!("processing pivot table for upload");
         process_funder_file(
             &file_path,
             portfolio_name,
@@ -761,7 +1322,7 @@ pub fn save_funder_upload(
             &upload_id,
         )
     };
-    
+
     let (success, message) = if is_clearview && upload_type == "daily" {
         // Special message for Clear View daily files
         (true, format!("Clear View daily file saved successfully. Call process_clearview_daily_pivot to generate pivot table after all files are uploaded."))
@@ -776,7 +1337,8 @@ pub fn save_funder_upload(
             },
         }
     };
-    
+    let message = format!("{}{}", message, duplicate_note);
+
     Ok(UploadResponse {
         success,
         message,
@@ -786,6 +1348,90 @@ pub fn save_funder_upload(
     })
 }
 
+/// Begin a chunked upload: open a session in `upload_session` and return its
+/// token, which the frontend then feeds to [`push_upload_chunk`] instead of
+/// sending the whole file as one `Vec<u8>` over the IPC boundary.
+#[tauri::command]
+#[instrument(skip(metadata), fields(portfolio = %metadata.portfolio_name, funder = %metadata.funder_name, report_date = %metadata.report_date))]
+pub fn begin_upload(metadata: upload_session::UploadMetadata) -> Result<String, String> {
+    ensure_directories()?;
+    let token = upload_session::begin_upload(metadata)?;
+    info!(token = %token, "upload session started");
+    Ok(token)
+}
+
+/// Append one sequential chunk of bytes to `token`'s in-progress upload.
+#[tauri::command]
+#[instrument(skip(bytes), fields(token = %token, offset, len = bytes.len()))]
+pub fn push_upload_chunk(token: &str, offset: u64, bytes: Vec<u8>) -> Result<(), String> {
+    upload_session::push_upload_chunk(token, offset, &bytes)
+}
+
+/// Discard `token`'s in-progress upload and delete its temp file.
+#[tauri::command]
+#[instrument(fields(token = %token))]
+pub fn abort_upload(token: &str) -> Result<(), String> {
+    upload_session::abort_upload(token)?;
+    info!("upload session aborted");
+    Ok(())
+}
+
+/// Finalize a chunked upload: atomically move (or, if compressing, copy) the
+/// assembled temp file into the content-addressed blob store using the hash
+/// accumulated across `push_upload_chunk` calls, then run the same DB
+/// insert + pivot processing path as [`save_funder_upload`].
+#[tauri::command]
+#[instrument(fields(token = %token))]
+pub fn finish_upload(token: &str) -> Result<UploadResponse, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let assembled = upload_session::finish_upload(token)?;
+    let upload_session::AssembledUpload {
+        metadata,
+        temp_path,
+        file_size,
+        content_sha256,
+        content_md5,
+    } = assembled;
+    info!(file_size, "upload session assembled, moving blob into place");
+
+    let portfolio_dir = get_portfolio_dir(&metadata.portfolio_name)?;
+    let naming = resolve_upload_naming(&metadata.funder_name, &metadata.file_name, &metadata.report_date, &metadata.upload_type);
+
+    let is_csv = Path::new(&metadata.file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false);
+    let compression_config = compression_config_for(&metadata.portfolio_name);
+
+    let blobs_dir = funder_blobs_dir_for(&portfolio_dir);
+    let (file_path, codec, compressed_size) = move_funder_blob_from_temp(
+        &blobs_dir,
+        &content_sha256,
+        &temp_path,
+        is_csv && compression_config.enabled,
+        compression_config.level,
+    )?;
+    debug!(file_path = %file_path.display(), codec = ?codec, "moved assembled upload into blob store");
+
+    finish_funder_upload_record(
+        &metadata.portfolio_name,
+        &metadata.file_name,
+        &metadata.report_date,
+        &metadata.upload_type,
+        naming,
+        file_path,
+        file_size,
+        content_sha256,
+        content_md5,
+        codec,
+        compressed_size,
+    )
+}
+
 #[tauri::command]
 pub fn get_funder_upload_info(
     portfolio_name: &str,
@@ -851,50 +1497,355 @@ pub fn check_funder_upload_exists(
 }
 
 #[tauri::command]
+#[instrument(fields(upload_id = %upload_id))]
 pub fn delete_funder_upload(upload_id: &str) -> Result<bool, String> {
     if DB.lock().unwrap().is_none() {
         init_database()?;
     }
-    
+
     let db_lock = DB.lock().unwrap();
-    if let Some(db) = db_lock.as_ref() {
-        // First, get the upload details to find the file paths
-        let uploads = db.get_all_funder_uploads()
-            .map_err(|e| format!("Failed to get funder uploads: {}", e))?;
-        
-        let upload = uploads.iter().find(|u| u.id == upload_id)
-            .ok_or_else(|| "Upload not found".to_string())?;
-        
-        // Get the associated pivot table to delete its file too
-        let pivot = db.get_pivot_table_by_upload_id(upload_id)
-            .map_err(|e| format!("Failed to get pivot table: {}", e))?;
-        
-        // Delete the upload file from filesystem
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    let deleted = delete_funder_upload_impl(db, upload_id)?;
+    info!(deleted, "funder upload deletion complete");
+    Ok(deleted)
+}
+
+/// Remove a funder upload's blob (once no other upload row still references
+/// it), its pivot table file and row, and the upload's own DB row. Shared by
+/// the [`delete_funder_upload`] command and [`prune_funder_uploads`] so both
+/// go through the same reference-counted cleanup.
+fn delete_funder_upload_impl(db: &Database, upload_id: &str) -> Result<bool, String> {
+    // First, get the upload details to find the file paths
+    let uploads = db.get_all_funder_uploads()
+        .map_err(|e| format!("Failed to get funder uploads: {}", e))?;
+
+    let upload = uploads.iter().find(|u| u.id == upload_id)
+        .ok_or_else(|| "Upload not found".to_string())?;
+
+    // Get the associated pivot table to delete its file too
+    let pivot = db.get_pivot_table_by_upload_id(upload_id)
+        .map_err(|e| format!("Failed to get pivot table: {}", e))?;
+
+    // Only remove the blob from disk once no other upload row still
+    // references it (several uploads can share the same content-addressed
+    // blob). Uploads stored before blob dedup existed have no recorded
+    // hash, so they stay effectively unshared and are always removed.
+    let safe_to_remove_blob = match &upload.content_sha256 {
+        Some(content_sha256) => {
+            db.count_funder_uploads_referencing_hash(&upload.portfolio_name, content_sha256, upload_id)
+                .map_err(|e| format!("Failed to check blob references: {}", e))?
+                == 0
+        }
+        None => true,
+    };
+
+    if safe_to_remove_blob {
         let upload_path = Path::new(&upload.file_path);
         if upload_path.exists() {
             fs::remove_file(upload_path)
                 .map_err(|e| format!("Failed to delete upload file: {}", e))?;
         }
-        
-        // Delete the pivot table file from filesystem if it exists
-        if let Some(pivot_table) = pivot {
-            let pivot_path = Path::new(&pivot_table.pivot_file_path);
-            if pivot_path.exists() {
-                fs::remove_file(pivot_path)
-                    .map_err(|e| format!("Failed to delete pivot table file: {}", e))?;
+    }
+
+    // Delete the pivot table file from filesystem if it exists
+    if let Some(pivot_table) = pivot {
+        let pivot_path = Path::new(&pivot_table.pivot_file_path);
+        if pivot_path.exists() {
+            fs::remove_file(pivot_path)
+                .map_err(|e| format!("Failed to delete pivot table file: {}", e))?;
+        }
+
+        // Delete the pivot table from database
+        db.delete_pivot_table_by_upload_id(upload_id)
+            .map_err(|e| format!("Failed to delete pivot table from database: {}", e))?;
+    }
+
+    // Delete the upload from database
+    db.delete_funder_upload(upload_id)
+        .map_err(|e| format!("Failed to delete upload from database: {}", e))
+}
+
+/// Evaluate (and, unless `dry_run`, apply) a Proxmox-style keep-last/daily/
+/// weekly/monthly/yearly retention policy against every (funder, upload
+/// type) group of uploads in a portfolio, deleting whatever no bucket
+/// retains via [`delete_funder_upload_impl`]. Returns the uploads that were
+/// (or would be) removed.
+#[tauri::command]
+pub fn prune_funder_uploads(
+    portfolio_name: &str,
+    policy: RetentionPolicy,
+    dry_run: bool,
+) -> Result<Vec<FunderUpload>, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let expired = db.prune_funder_uploads_candidates(portfolio_name, &policy)
+        .map_err(|e| format!("Failed to evaluate retention policy: {}", e))?;
+
+    if !dry_run {
+        for upload in &expired {
+            delete_funder_upload_impl(db, &upload.id)?;
+        }
+    }
+
+    Ok(expired)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DanglingRecord {
+    pub id: String,
+    pub file_type: String, // "version" | "funder_upload" | "pivot_table"
+    pub file_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrphanFile {
+    pub file_path: String,
+    pub file_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SizeMismatch {
+    pub id: String,
+    pub file_type: String,
+    pub file_path: String,
+    pub recorded_size: i64,
+    pub actual_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub dangling: Vec<DanglingRecord>,
+    pub orphans: Vec<OrphanFile>,
+    pub size_mismatches: Vec<SizeMismatch>,
+}
+
+/// Recursively collect every file under `dir` not present in `referenced`,
+/// used to find files on disk with no corresponding DB row.
+fn collect_orphans(dir: &Path, referenced: &std::collections::HashSet<PathBuf>, out: &mut Vec<OrphanFile>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read directory {:?}: {}", dir, e))? {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_orphans(&path, referenced, out)?;
+        } else if !referenced.contains(&path) {
+            let file_size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            out.push(OrphanFile {
+                file_path: path.to_string_lossy().to_string(),
+                file_size,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Cross-check a portfolio's DB rows against what's actually on disk under
+/// `Funder Uploads` and `Funder Pivot Tables`: rows whose file is gone
+/// (dangling), files with no corresponding row (orphans), and funder
+/// uploads whose on-disk size no longer matches their recorded `file_size`.
+/// Versions are checked via their chunk manifest rather than `file_path`,
+/// since a chunked version's bytes live under `Workbook/.chunks/` rather
+/// than at a standalone file.
+#[tauri::command]
+pub fn verify_database_integrity(portfolio_name: &str) -> Result<IntegrityReport, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let portfolio_dir = get_portfolio_dir(portfolio_name)?;
+    let workbook_dir = portfolio_dir.join("Workbook");
+    let chunks_dir = chunks_dir_for(&workbook_dir);
+
+    let versions = db.get_versions_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load versions: {}", e))?;
+    let uploads = db.get_funder_uploads_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load funder uploads: {}", e))?;
+    let pivots = db.get_funder_pivot_tables_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load funder pivot tables: {}", e))?;
+
+    let mut dangling = Vec::new();
+    let mut size_mismatches = Vec::new();
+    let mut referenced: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+
+    for version in &versions {
+        let manifest = db.get_version_chunk_manifest(&version.id)
+            .map_err(|e| format!("Failed to load chunk manifest for {}: {}", version.id, e))?;
+
+        if manifest.is_empty() {
+            let path = PathBuf::from(&version.file_path);
+            if path.exists() {
+                referenced.insert(path);
+            } else {
+                dangling.push(DanglingRecord {
+                    id: version.id.clone(),
+                    file_type: "version".to_string(),
+                    file_path: version.file_path.clone(),
+                });
+            }
+        } else {
+            let mut missing_chunk_path = None;
+            for chunk_hash in &manifest {
+                let chunk_path = chunks_dir.join(chunk_hash);
+                referenced.insert(chunk_path.clone());
+                if missing_chunk_path.is_none() && !chunk_path.exists() {
+                    missing_chunk_path = Some(chunk_path);
+                }
+            }
+            if let Some(missing_path) = missing_chunk_path {
+                dangling.push(DanglingRecord {
+                    id: version.id.clone(),
+                    file_type: "version".to_string(),
+                    file_path: missing_path.to_string_lossy().to_string(),
+                });
             }
-            
-            // Delete the pivot table from database
-            db.delete_pivot_table_by_upload_id(upload_id)
-                .map_err(|e| format!("Failed to delete pivot table from database: {}", e))?;
         }
-        
-        // Delete the upload from database
-        db.delete_funder_upload(upload_id)
-            .map_err(|e| format!("Failed to delete upload from database: {}", e))
-    } else {
-        Err("Database not initialized".to_string())
     }
+
+    for upload in &uploads {
+        let path = PathBuf::from(&upload.file_path);
+        if !path.exists() {
+            dangling.push(DanglingRecord {
+                id: upload.id.clone(),
+                file_type: "funder_upload".to_string(),
+                file_path: upload.file_path.clone(),
+            });
+            continue;
+        }
+
+        referenced.insert(path.clone());
+        if let Ok(metadata) = fs::metadata(&path) {
+            let actual_size = metadata.len();
+            // A compressed blob's on-disk size is `compressed_size`;
+            // `file_size` holds the logical (uncompressed) size.
+            let recorded_size = upload.compressed_size.unwrap_or(upload.file_size);
+            if actual_size as i64 != recorded_size {
+                size_mismatches.push(SizeMismatch {
+                    id: upload.id.clone(),
+                    file_type: "funder_upload".to_string(),
+                    file_path: upload.file_path.clone(),
+                    recorded_size,
+                    actual_size,
+                });
+            }
+        }
+    }
+
+    for pivot in &pivots {
+        let path = PathBuf::from(&pivot.pivot_file_path);
+        if path.exists() {
+            referenced.insert(path);
+        } else {
+            dangling.push(DanglingRecord {
+                id: pivot.id.clone(),
+                file_type: "pivot_table".to_string(),
+                file_path: pivot.pivot_file_path.clone(),
+            });
+        }
+    }
+
+    let mut orphans = Vec::new();
+    let uploads_dir = portfolio_dir.join("Funder Uploads");
+    if uploads_dir.exists() {
+        collect_orphans(&uploads_dir, &referenced, &mut orphans)?;
+    }
+    let pivots_dir = portfolio_dir.join("Funder Pivot Tables");
+    if pivots_dir.exists() {
+        collect_orphans(&pivots_dir, &referenced, &mut orphans)?;
+    }
+
+    Ok(IntegrityReport {
+        dangling,
+        orphans,
+        size_mismatches,
+    })
+}
+
+/// Re-run [`verify_database_integrity`] and fix what it found: delete
+/// dangling records, recompute `file_size` for mismatched funder uploads,
+/// and re-import orphan files found under `Funder Uploads` as new upload
+/// rows (tagged for manual review, since their funder/date metadata can't
+/// be recovered from the file alone). Returns the report that was acted on.
+#[tauri::command]
+pub fn repair_database_integrity(portfolio_name: &str) -> Result<IntegrityReport, String> {
+    let report = verify_database_integrity(portfolio_name)?;
+
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    for record in &report.dangling {
+        match record.file_type.as_str() {
+            "version" => {
+                db.purge_version(&record.id)
+                    .map_err(|e| format!("Failed to purge dangling version {}: {}", record.id, e))?;
+            }
+            "funder_upload" => {
+                delete_funder_upload_impl(db, &record.id)?;
+            }
+            "pivot_table" => {
+                db.delete_pivot_table_by_id(&record.id)
+                    .map_err(|e| format!("Failed to delete dangling pivot table {}: {}", record.id, e))?;
+            }
+            other => {
+                eprintln!("Unknown dangling record type '{}' for {}, skipping", other, record.id);
+            }
+        }
+    }
+
+    for mismatch in &report.size_mismatches {
+        if mismatch.file_type == "funder_upload" {
+            db.update_funder_upload_file_size(&mismatch.id, mismatch.actual_size as i64)
+                .map_err(|e| format!("Failed to update size for {}: {}", mismatch.id, e))?;
+        }
+    }
+
+    let portfolio_dir = get_portfolio_dir(portfolio_name)?;
+    let uploads_dir = portfolio_dir.join("Funder Uploads");
+    for orphan in &report.orphans {
+        let orphan_path = Path::new(&orphan.file_path);
+        if !orphan_path.starts_with(&uploads_dir) {
+            continue; // only pivot-table orphans land outside Funder Uploads; those have no upload to re-attach to
+        }
+
+        let file_data = fs::read(orphan_path)
+            .map_err(|e| format!("Failed to read orphan file {}: {}", orphan.file_path, e))?;
+        let (content_sha256, content_md5) = crate::database::hash_content(&file_data);
+        let file_name = orphan_path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "orphan".to_string());
+
+        let reimported = FunderUpload {
+            id: Uuid::new_v4().to_string(),
+            portfolio_name: portfolio_name.to_string(),
+            funder_name: "Unknown (recovered)".to_string(),
+            report_date: Utc::now().format("%Y-%m-%d").to_string(),
+            upload_type: "weekly".to_string(),
+            original_filename: file_name.clone(),
+            stored_filename: file_name,
+            file_path: orphan.file_path.clone(),
+            file_size: orphan.file_size as i64,
+            upload_timestamp: Utc::now(),
+            content_sha256: Some(content_sha256),
+            content_md5: Some(content_md5),
+            codec: None,
+            compressed_size: None,
+            deleted_at: None,
+        };
+
+        db.insert_funder_upload(&reimported)
+            .map_err(|e| format!("Failed to re-import orphan {}: {}", orphan.file_path, e))?;
+    }
+
+    Ok(report)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -908,6 +1859,9 @@ pub struct DatabaseFileEntry {
     pub file_name: String,
     pub file_path: String,
     pub file_size: i64,
+    /// On-disk size when compressed (see `CompressionConfig`); `None` means
+    /// the blob is stored raw, so it equals `file_size`.
+    pub on_disk_size: Option<i64>,
     pub upload_timestamp: String,
     pub is_active: Option<bool>,
     pub total_gross: Option<f64>,
@@ -941,6 +1895,7 @@ pub fn get_all_database_files() -> Result<Vec<DatabaseFileEntry>, String> {
                 file_name: version.original_filename,
                 file_path: version.file_path,
                 file_size: version.file_size,
+                on_disk_size: None,
                 upload_timestamp: version.upload_timestamp.to_rfc3339(),
                 is_active: Some(version.is_active),
                 total_gross: None,
@@ -965,6 +1920,7 @@ pub fn get_all_database_files() -> Result<Vec<DatabaseFileEntry>, String> {
                 file_name: upload.original_filename,
                 file_path: upload.file_path,
                 file_size: upload.file_size,
+                on_disk_size: upload.compressed_size,
                 upload_timestamp: upload.upload_timestamp.to_rfc3339(),
                 is_active: None,
                 total_gross: None,
@@ -993,6 +1949,7 @@ pub fn get_all_database_files() -> Result<Vec<DatabaseFileEntry>, String> {
                 file_name,
                 file_path: pivot.pivot_file_path,
                 file_size: 0, // We don't store file size for pivot tables, could calculate if needed
+                on_disk_size: None,
                 upload_timestamp: pivot.created_timestamp.to_rfc3339(),
                 is_active: None,
                 total_gross: Some(pivot.total_gross),
@@ -1009,20 +1966,29 @@ pub fn get_all_database_files() -> Result<Vec<DatabaseFileEntry>, String> {
 }
 
 #[tauri::command]
-pub fn read_csv_file(file_path: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+pub fn read_csv_file(app_handle: tauri::AppHandle, file_path: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
     use csv::ReaderBuilder;
-    
+
     let path = Path::new(file_path);
     if !path.exists() {
         return Err("File not found".to_string());
     }
-    
-    let file = fs::File::open(path)
-        .map_err(|e| format!("Failed to open file: {}", e))?;
-    
+
+    // The file can still be held open by another process (Excel, a sync
+    // client) right after it lands on disk, so retry past a transient lock
+    // before giving up and telling the user.
+    let raw_bytes = crate::retry::retry_with_backoff(|| fs::read(path), crate::retry::RetryPolicy::default())
+        .map_err(|e| {
+            let message = format!("Failed to open file: {}", e);
+            let _ = crate::notification::NotificationManager::error(&app_handle, "Failed to read file", Some(message.clone()));
+            message
+        })?;
+    let bytes = compression::decompress_if_needed(&raw_bytes)
+        .map_err(|e| format!("Failed to decompress {}: {}", file_path, e))?;
+
     let mut reader = ReaderBuilder::new()
         .has_headers(true)
-        .from_reader(file);
+        .from_reader(bytes.as_slice());
     
     // Get headers
     let headers = reader.headers()
@@ -1056,15 +2022,23 @@ pub fn read_csv_file(file_path: &str) -> Result<(Vec<String>, Vec<Vec<String>>),
 }
 
 #[tauri::command]
-pub fn read_excel_file(file_path: &str) -> Result<serde_json::Value, String> {
-    use calamine::{Reader, open_workbook, Xlsx};
-    
+pub fn read_excel_file(app_handle: tauri::AppHandle, file_path: &str) -> Result<serde_json::Value, String> {
+    use calamine::{Reader, Xlsx};
+
     let path = Path::new(file_path);
     if !path.exists() {
         return Err("File not found".to_string());
     }
-    
-    let mut workbook: Xlsx<_> = open_workbook(path)
+
+    let raw_bytes = crate::retry::retry_with_backoff(|| fs::read(path), crate::retry::RetryPolicy::default())
+        .map_err(|e| {
+            let message = format!("Failed to open Excel file: {}", e);
+            let _ = crate::notification::NotificationManager::error(&app_handle, "Failed to read file", Some(message.clone()));
+            message
+        })?;
+    let bytes = compression::decompress_if_needed(&raw_bytes)
+        .map_err(|e| format!("Failed to decompress {}: {}", file_path, e))?;
+    let mut workbook: Xlsx<_> = Xlsx::new(std::io::Cursor::new(bytes))
         .map_err(|e| format!("Failed to open Excel file: {}", e))?;
     
     let mut sheets_data = Vec::new();
@@ -1110,6 +2084,37 @@ pub fn read_excel_file(file_path: &str) -> Result<serde_json::Value, String> {
     }))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutodetectProcessResponse {
+    pub funder_name: String,
+    pub pivot: PivotTable,
+}
+
+/// Auto-detect `file_path`'s funder via [`crate::parsers::ParserRegistry`]
+/// and process it with the highest-scoring parser, so the UI no longer has
+/// to force the user to pre-select a funder before uploading a statement.
+#[tauri::command]
+pub fn process_with_autodetect(file_path: &str) -> Result<AutodetectProcessResponse, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let registry = crate::parsers::ParserRegistry::with_builtin_parsers();
+    let funder_name = registry.detect(path)
+        .ok_or_else(|| format!("Could not detect a funder for {}", file_path))?;
+    let parser = registry.build(&funder_name)
+        .ok_or_else(|| format!("No parser registered for detected funder '{}'", funder_name))?;
+
+    let pivot = parser.process(path)
+        .map_err(|e| format!("Failed to process {} as {}: {}", file_path, funder_name, e))?;
+
+    Ok(AutodetectProcessResponse {
+        funder_name,
+        pivot,
+    })
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClearViewPivotResponse {
     pub success: bool,
@@ -1126,6 +2131,7 @@ pub struct ClearViewPivotResponse {
 }
 
 #[tauri::command]
+#[instrument(fields(portfolio = %portfolio_name, report_date = %report_date), skip(daily_file_paths, weekly_file_path))]
 pub fn process_clearview_pivots(
     portfolio_name: &str,
     report_date: &str,
@@ -1135,12 +2141,14 @@ pub fn process_clearview_pivots(
     if DB.lock().unwrap().is_none() {
         init_database()?;
     }
-    
+
+    info!(daily_files = daily_file_paths.len(), has_weekly_file = weekly_file_path.is_some(), "processing Clear View pivots");
+
     let processor = ClearViewPivotProcessor::new(
         portfolio_name.to_string(),
         report_date.to_string(),
-    );
-    
+    ).with_compression_config(compression_config_for(portfolio_name));
+
     let mut response = ClearViewPivotResponse {
         success: false,
         message: String::new(),
@@ -1175,10 +2183,11 @@ pub fn process_clearview_pivots(
                     &pivot,
                     crate::parsers::clearview_pivot_processor::PivotTableType::DailyAggregated,
                 ).map_err(|e| format!("Failed to store daily pivot metadata: {}", e))?;
-                
+                debug!(pivot_path = %path, "daily pivot metadata stored");
+
                 response.daily_pivot_path = Some(path);
-                response.daily_total_gross = Some(pivot.total_gross);
-                response.daily_total_net = Some(pivot.total_net);
+                response.daily_total_gross = Some(pivot.total_gross.to_f64().unwrap_or(0.0));
+                response.daily_total_net = Some(pivot.total_net.to_f64().unwrap_or(0.0));
                 Some(pivot)
             },
             Err(e) => {
@@ -1203,10 +2212,11 @@ pub fn process_clearview_pivots(
                     &pivot,
                     crate::parsers::clearview_pivot_processor::PivotTableType::WeeklyReport,
                 ).map_err(|e| format!("Failed to store weekly pivot metadata: {}", e))?;
-                
+                debug!(pivot_path = %path, "weekly pivot metadata stored");
+
                 response.weekly_pivot_path = Some(path);
-                response.weekly_total_gross = Some(pivot.total_gross);
-                response.weekly_total_net = Some(pivot.total_net);
+                response.weekly_total_gross = Some(pivot.total_gross.to_f64().unwrap_or(0.0));
+                response.weekly_total_net = Some(pivot.total_net.to_f64().unwrap_or(0.0));
                 Some(pivot)
             },
             Err(e) => {
@@ -1231,10 +2241,14 @@ pub fn process_clearview_pivots(
                     &pivot,
                     crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
                 ).map_err(|e| format!("Failed to store combined pivot metadata: {}", e))?;
-                
+
+                processor.record_weekly_collections(db, &pivot)
+                    .map_err(|e| format!("Failed to record ledger events: {}", e))?;
+                debug!(pivot_path = %path, "combined pivot metadata stored");
+
                 response.combined_pivot_path = Some(path);
-                response.combined_total_gross = Some(pivot.total_gross);
-                response.combined_total_net = Some(pivot.total_net);
+                response.combined_total_gross = Some(pivot.total_gross.to_f64().unwrap_or(0.0));
+                response.combined_total_net = Some(pivot.total_net.to_f64().unwrap_or(0.0));
             },
             Err(e) => {
                 response.message = format!("Failed to create combined pivot: {:?}", e);
@@ -1251,172 +2265,555 @@ pub fn process_clearview_pivots(
 }
 
 #[tauri::command]
+#[instrument(fields(portfolio = %portfolio_name, report_date = %report_date))]
 pub fn process_clearview_daily_pivot(
     portfolio_name: &str,
     report_date: &str,
 ) -> Result<UploadResponse, String> {
     use crate::parsers::clearview_pivot_processor::ClearViewPivotProcessor;
-    
+
     if DB.lock().unwrap().is_none() {
         init_database()?;
     }
-    
-    let processor = ClearViewPivotProcessor::new(
-        portfolio_name.to_string(),
-        report_date.to_string(),
-    );
-    
-    // Process all daily files in the folder
-    let (pivot, pivot_path) = processor.process_all_daily_files()
-        .map_err(|e| format!("Failed to process Clear View daily files: {:?}", e))?;
-    
-    // Store pivot metadata
-    let db_lock = DB.lock().unwrap();
-    if let Some(db) = db_lock.as_ref() {
-        let upload_id = uuid::Uuid::new_v4().to_string();
-        processor.store_pivot_metadata(
-            db,
-            &upload_id,
-            &pivot_path,
-            &pivot,
-            crate::parsers::clearview_pivot_processor::PivotTableType::DailyAggregated,
-        ).map_err(|e| format!("Failed to store pivot metadata: {}", e))?;
-        
-        // Check if we need to update the combined pivot
-        if let Ok(Some((combined_pivot, combined_path))) = processor.update_combined_pivot_if_needed() {
+
+    // Track this multi-stage operation (parse -> store -> regenerate combined
+    // -> store again) as a job, so a crash partway through leaves a row
+    // `get_jobs` can surface rather than silently losing track of it.
+    let job_id = {
+        let db_lock = DB.lock().unwrap();
+        let db = db_lock.as_ref().ok_or("Database not initialized")?;
+        crate::jobs::begin(db, "clearview_daily_pivot", portfolio_name, report_date)?
+    };
+
+    let outcome = (|| -> Result<UploadResponse, String> {
+        let processor = ClearViewPivotProcessor::new(
+            portfolio_name.to_string(),
+            report_date.to_string(),
+        ).with_compression_config(compression_config_for(portfolio_name));
+
+        // Process all daily files in the folder
+        info!("aggregating Clear View daily files");
+        let (pivot, pivot_path) = processor.process_all_daily_files()
+            .map_err(|e| format!("Failed to process Clear View daily files: {:?}", e))?;
+        debug!(pivot_path = %pivot_path, "daily aggregated pivot produced");
+
+        // Store pivot metadata
+        let db_lock = DB.lock().unwrap();
+        if let Some(db) = db_lock.as_ref() {
+            let _ = crate::jobs::advance(db, &job_id, "storing_daily_metadata");
+
+            let upload_id = uuid::Uuid::new_v4().to_string();
             processor.store_pivot_metadata(
                 db,
                 &upload_id,
-                &combined_path,
-                &combined_pivot,
-                crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
-            ).map_err(|e| format!("Failed to store combined pivot metadata: {}", e))?;
+                &pivot_path,
+                &pivot,
+                crate::parsers::clearview_pivot_processor::PivotTableType::DailyAggregated,
+            ).map_err(|e| format!("Failed to store pivot metadata: {}", e))?;
+            info!(upload_id = %upload_id, "daily pivot metadata stored");
+
+            // Check if we need to update the combined pivot
+            if let Ok(Some((combined_pivot, combined_path))) = processor.update_combined_pivot_if_needed() {
+                let _ = crate::jobs::advance(db, &job_id, "regenerating_combined_pivot");
+
+                processor.store_pivot_metadata(
+                    db,
+                    &upload_id,
+                    &combined_path,
+                    &combined_pivot,
+                    crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
+                ).map_err(|e| format!("Failed to store combined pivot metadata: {}", e))?;
+
+                processor.record_weekly_collections(db, &combined_pivot)
+                    .map_err(|e| format!("Failed to record ledger events: {}", e))?;
+            }
         }
+
+        Ok(UploadResponse {
+            success: true,
+            message: format!("Clear View daily pivot table created successfully. Total gross: ${:.2}, Total net: ${:.2}",
+                            pivot.total_gross, pivot.total_net),
+            file_path: Some(pivot_path),
+            version_id: None,
+            backup_path: None,
+        })
+    })();
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    let job_result = match &outcome {
+        Ok(_) => crate::jobs::finish(db, &job_id),
+        Err(e) => crate::jobs::fail(db, &job_id, e),
+    };
+    if let Err(e) = job_result {
+        eprintln!("Failed to update job {} after processing: {}", job_id, e);
     }
-    
-    Ok(UploadResponse {
-        success: true,
-        message: format!("Clear View daily pivot table created successfully. Total gross: ${:.2}, Total net: ${:.2}", 
-                        pivot.total_gross, pivot.total_net),
-        file_path: Some(pivot_path),
-        version_id: None,
-        backup_path: None,
-    })
+
+    outcome
 }
 
+/// List every tracked job, newest first, so the UI can show in-flight work
+/// and what happened to past runs (including ones [`init_database`] found
+/// still `Pending`/`InProgress` at startup and marked interrupted).
 #[tauri::command]
-pub fn delete_clearview_file(
+pub fn get_jobs() -> Result<Vec<crate::database::Job>, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    db.get_jobs().map_err(|e| format!("Failed to load jobs: {}", e))
+}
+
+/// Clear a non-terminal-looking job back to `Pending` so the caller can
+/// re-invoke the command that originally started it.
+///
+/// This subsystem doesn't yet serialize enough per-stage state to resume a
+/// job from the exact stage it stopped at — today's only job type
+/// (`clearview_daily_pivot`, started by `process_clearview_daily_pivot`) is
+/// safe to simply re-run from the start, so that's what a UI should do after
+/// calling this. A job type that isn't safe to blindly re-run would need
+/// its own resume logic before being wired into this subsystem.
+#[tauri::command]
+pub fn resume_job(job_id: &str) -> Result<crate::database::Job, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let job = db.get_job(job_id)
+        .map_err(|e| format!("Failed to load job: {}", e))?
+        .ok_or_else(|| format!("No such job: {}", job_id))?;
+
+    if job.status == crate::database::JobStatus::Completed || job.status == crate::database::JobStatus::Cancelled {
+        return Err(format!("Job {} is already {:?} and can't be resumed", job_id, job.status));
+    }
+
+    db.update_job_status(job_id, crate::database::JobStatus::Pending, None)
+        .map_err(|e| format!("Failed to resume job: {}", e))?;
+
+    db.get_job(job_id)
+        .map_err(|e| format!("Failed to reload job: {}", e))?
+        .ok_or_else(|| format!("No such job: {}", job_id))
+}
+
+/// Give up on a stuck or unwanted job without re-running it.
+#[tauri::command]
+pub fn cancel_job(job_id: &str) -> Result<(), String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    db.update_job_status(job_id, crate::database::JobStatus::Cancelled, None)
+        .map_err(|e| format!("Failed to cancel job: {}", e))
+}
+
+/// Every notification sent through `NotificationManager` since it was last
+/// cleared, oldest first, so a history panel can show what fired during a
+/// batch upload after its toast disappeared.
+#[tauri::command]
+pub fn get_notification_history() -> Vec<crate::notification::NotificationHistoryEntry> {
+    crate::notification::get_notification_history()
+}
+
+#[tauri::command]
+pub fn mark_notification_read(id: u64) -> Result<(), String> {
+    crate::notification::mark_notification_read(id)
+}
+
+#[tauri::command]
+pub fn clear_notification_history() {
+    crate::notification::clear_notification_history()
+}
+
+/// Move `temp_path` to `final_path`, falling back to copy-then-remove if
+/// they aren't on the same filesystem — mirrors `move_funder_blob_from_temp`'s
+/// fallback for the funder-upload blob store.
+fn rename_or_copy(temp_path: &Path, final_path: &Path) -> Result<(), String> {
+    if fs::rename(temp_path, final_path).is_err() {
+        fs::copy(temp_path, final_path)
+            .map_err(|e| format!("Failed to move staged pivot into place: {}", e))?;
+        let _ = fs::remove_file(temp_path);
+    }
+    Ok(())
+}
+
+/// Write-ahead a regenerated pivot into place: stage `pivot` to a temp file,
+/// record a [`PendingPivotSwap`] intent naming the stale row(s) it replaces,
+/// rename temp into its final path, then commit the swap (delete the stale
+/// rows, insert the new one) in a single transaction. If the process dies at
+/// any point here, [`recover_pending_pivot_swaps`] finishes or undoes it on
+/// the next startup instead of leaving the CSV and the DB disagreeing.
+fn stage_and_commit_pivot_swap(
+    db: &Database,
+    processor: &ClearViewPivotProcessor,
+    portfolio_name: &str,
+    report_date: &str,
+    pivot: &PivotTable,
+    pivot_type: crate::parsers::clearview_pivot_processor::PivotTableType,
     upload_id: &str,
+) -> Result<String, String> {
+    let (temp_path, final_path) = processor
+        .write_pivot_staged(pivot, pivot_type.clone())
+        .map_err(|e| format!("Failed to stage regenerated pivot: {:?}", e))?;
+    let final_path_str = final_path.to_string_lossy().to_string();
+
+    let stale_pivot_ids = db
+        .get_funder_pivot_table_ids_by_path(&final_path_str)
+        .map_err(|e| format!("Failed to look up stale pivot rows: {}", e))?;
+    let new_pivot_metadata =
+        processor.build_pivot_metadata(upload_id, &final_path_str, pivot, &pivot_type);
+
+    let swap = PendingPivotSwap {
+        id: Uuid::new_v4().to_string(),
+        portfolio_name: portfolio_name.to_string(),
+        report_date: report_date.to_string(),
+        temp_path: Some(temp_path.to_string_lossy().to_string()),
+        final_path: Some(final_path_str.clone()),
+        stale_pivot_ids,
+        new_pivot_metadata: Some(new_pivot_metadata),
+        created_timestamp: Utc::now(),
+    };
+    db.insert_pending_pivot_swap(&swap)
+        .map_err(|e| format!("Failed to record pivot swap intent: {}", e))?;
+
+    rename_or_copy(&temp_path, &final_path)?;
+
+    db.commit_pivot_swap(&swap)
+        .map_err(|e| format!("Failed to commit pivot swap: {}", e))?;
+
+    Ok(final_path_str)
+}
+
+/// Remove a stale pivot CSV that has no replacement (e.g. the last daily
+/// file for a week being deleted) together with its `funder_pivot_tables`
+/// row, using the same intent-record-then-commit shape as
+/// [`stage_and_commit_pivot_swap`] so a crash mid-delete can't leave the file
+/// gone but the row still present, or vice versa.
+fn remove_stale_pivot(db: &Database, portfolio_name: &str, report_date: &str, path: &Path) -> Result<(), String> {
+    let path_str = path.to_string_lossy().to_string();
+
+    let stale_pivot_ids = db
+        .get_funder_pivot_table_ids_by_path(&path_str)
+        .map_err(|e| format!("Failed to look up stale pivot rows: {}", e))?;
+
+    if !path.exists() && stale_pivot_ids.is_empty() {
+        return Ok(());
+    }
+
+    let swap = PendingPivotSwap {
+        id: Uuid::new_v4().to_string(),
+        portfolio_name: portfolio_name.to_string(),
+        report_date: report_date.to_string(),
+        temp_path: None,
+        final_path: None,
+        stale_pivot_ids,
+        new_pivot_metadata: None,
+        created_timestamp: Utc::now(),
+    };
+    db.insert_pending_pivot_swap(&swap)
+        .map_err(|e| format!("Failed to record pivot deletion intent: {}", e))?;
+
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove stale pivot file: {}", e))?;
+    }
+
+    db.commit_pivot_swap(&swap)
+        .map_err(|e| format!("Failed to commit pivot deletion: {}", e))?;
+
+    Ok(())
+}
+
+/// Scan for [`PendingPivotSwap`] intent records left behind by a process that
+/// died mid `delete_clearview_file`, and roll each one forward (finish the
+/// rename + DB commit) or back (drop the intent, since nothing durable
+/// happened yet) so a crash never leaves an orphaned temp CSV or a dangling
+/// `funder_pivot_tables` row. Called once at startup, after the database is
+/// initialized.
+pub fn recover_pending_pivot_swaps(db: &Database) -> Result<(), String> {
+    let swaps = db
+        .get_pending_pivot_swaps()
+        .map_err(|e| format!("Failed to list pending pivot swaps: {}", e))?;
+
+    for swap in swaps {
+        match (&swap.temp_path, &swap.final_path) {
+            (Some(temp_path), Some(final_path)) => {
+                let temp_path = Path::new(temp_path);
+                let final_path = Path::new(final_path);
+
+                if temp_path.exists() {
+                    // Rename never happened (or was interrupted) — finish it.
+                    rename_or_copy(temp_path, final_path)?;
+                    db.commit_pivot_swap(&swap)
+                        .map_err(|e| format!("Failed to commit recovered pivot swap: {}", e))?;
+                } else if final_path.exists() {
+                    // Rename succeeded but the DB commit never ran — finish that.
+                    db.commit_pivot_swap(&swap)
+                        .map_err(|e| format!("Failed to commit recovered pivot swap: {}", e))?;
+                } else {
+                    // Neither file exists: the swap never got far enough to
+                    // leave anything durable behind, so just drop the intent.
+                    db.delete_pending_pivot_swap(&swap.id)
+                        .map_err(|e| format!("Failed to discard stale pivot swap intent: {}", e))?;
+                }
+            }
+            _ => {
+                // Pure deletion: the commit (stale rows + intent record) is
+                // all that's left to finish, regardless of whether the file
+                // itself made it out before the crash.
+                db.commit_pivot_swap(&swap)
+                    .map_err(|e| format!("Failed to commit recovered pivot deletion: {}", e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// After a Clear View upload has been deleted, regenerate the daily/combined
+/// pivots from whatever files remain (or remove them if none do). Shared by
+/// [`delete_clearview_file`] and [`recover_pending_clearview_deletions`], so
+/// a crash between the upload deletion and this step can be replayed
+/// idempotently from either the live call or the startup recovery scan.
+fn regenerate_or_remove_clearview_pivots(
+    db: &Database,
     portfolio_name: &str,
     report_date: &str,
     is_daily: bool,
-) -> Result<UploadResponse, String> {
-    if DB.lock().unwrap().is_none() {
-        init_database()?;
-    }
-    
-    // First delete the file using the standard deletion
-    delete_funder_upload(upload_id)?;
-    
+) -> Result<Option<String>, String> {
     if is_daily {
         // After deleting a daily file, regenerate the daily aggregated pivot
         // if there are remaining daily files
         let processor = ClearViewPivotProcessor::new(
             portfolio_name.to_string(),
             report_date.to_string(),
-        );
-        
+        ).with_compression_config(compression_config_for(portfolio_name));
+
         let remaining_files = processor.get_daily_files_for_week("")
             .map_err(|e| format!("Failed to get remaining daily files: {}", e))?;
-        
+
         if !remaining_files.is_empty() {
             // Regenerate the daily aggregated pivot
-            let (pivot, pivot_path) = processor.process_all_daily_files()
+            let (pivot, _) = processor.process_all_daily_files()
                 .map_err(|e| format!("Failed to regenerate daily pivot: {:?}", e))?;
-            
-            // Store updated pivot metadata
-            let db_lock = DB.lock().unwrap();
-            if let Some(db) = db_lock.as_ref() {
-                let new_upload_id = uuid::Uuid::new_v4().to_string();
-                processor.store_pivot_metadata(
+
+            let new_upload_id = Uuid::new_v4().to_string();
+            let pivot_path = stage_and_commit_pivot_swap(
+                db,
+                &processor,
+                portfolio_name,
+                report_date,
+                &pivot,
+                crate::parsers::clearview_pivot_processor::PivotTableType::DailyAggregated,
+                &new_upload_id,
+            )?;
+            info!(pivot_path = %pivot_path, "Regenerated daily aggregated pivot");
+
+            // Check if we need to update the combined pivot
+            if let Ok(Some((combined_pivot, _))) = processor.update_combined_pivot_if_needed() {
+                stage_and_commit_pivot_swap(
                     db,
+                    &processor,
+                    portfolio_name,
+                    report_date,
+                    &combined_pivot,
+                    crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
                     &new_upload_id,
-                    &pivot_path,
-                    &pivot,
-                    crate::parsers::clearview_pivot_processor::PivotTableType::DailyAggregated,
-                ).map_err(|e| format!("Failed to store pivot metadata: {}", e))?;
-                
-                // Check if we need to update the combined pivot
-                if let Ok(Some((combined_pivot, combined_path))) = processor.update_combined_pivot_if_needed() {
-                    processor.store_pivot_metadata(
-                        db,
-                        &new_upload_id,
-                        &combined_path,
-                        &combined_pivot,
-                        crate::parsers::clearview_pivot_processor::PivotTableType::Combined,
-                    ).map_err(|e| format!("Failed to store combined pivot metadata: {}", e))?;
-                }
-            }
-            
-            return Ok(UploadResponse {
-                success: true,
-                message: "Clear View daily file deleted and pivots updated".to_string(),
-                file_path: Some(pivot_path),
-                version_id: None,
-                backup_path: None,
-            });
-        } else {
-            // No remaining daily files, delete the daily pivot and combined pivot
-            let base_dir = get_excelerate_dir()?;
-            let daily_pivot_path = base_dir
-                .join(portfolio_name)
-                .join("Funder Pivot Tables")
-                .join("Weekly")
-                .join("Clear View")
-                .join("Daily")
-                .join(format!("{}.csv", report_date.replace('/', "-")));
-            
-            if daily_pivot_path.exists() {
-                fs::remove_file(&daily_pivot_path).ok();
-            }
-            
-            let combined_pivot_path = base_dir
-                .join(portfolio_name)
-                .join("Funder Pivot Tables")
-                .join("Weekly")
-                .join("Clear View")
-                .join("Combined")
-                .join(format!("{}.csv", report_date.replace('/', "-")));
-            
-            if combined_pivot_path.exists() {
-                fs::remove_file(&combined_pivot_path).ok();
+                )?;
+
+                processor.record_weekly_collections(db, &combined_pivot)
+                    .map_err(|e| format!("Failed to record ledger events: {}", e))?;
             }
+
+            return Ok(Some(pivot_path));
         }
+
+        // No remaining daily files: remove the stale daily and combined pivots
+        let daily_path = processor
+            .pivot_target_path(&crate::parsers::clearview_pivot_processor::PivotTableType::DailyAggregated)
+            .map_err(|e| format!("Failed to resolve daily pivot path: {:?}", e))?;
+        remove_stale_pivot(db, portfolio_name, report_date, &daily_path)?;
+
+        let combined_path = processor
+            .pivot_target_path(&crate::parsers::clearview_pivot_processor::PivotTableType::Combined)
+            .map_err(|e| format!("Failed to resolve combined pivot path: {:?}", e))?;
+        remove_stale_pivot(db, portfolio_name, report_date, &combined_path)?;
     } else {
         // Weekly file deleted, also delete the combined pivot
-        let base_dir = get_excelerate_dir()?;
-        let combined_pivot_path = base_dir
-            .join(portfolio_name)
-            .join("Funder Pivot Tables")
-            .join("Weekly")
-            .join("Clear View")
-            .join("Combined")
-            .join(format!("{}.csv", report_date.replace('/', "-")));
-        
-        if combined_pivot_path.exists() {
-            fs::remove_file(&combined_pivot_path).ok();
-        }
+        let processor = ClearViewPivotProcessor::new(
+            portfolio_name.to_string(),
+            report_date.to_string(),
+        );
+        let combined_path = processor
+            .pivot_target_path(&crate::parsers::clearview_pivot_processor::PivotTableType::Combined)
+            .map_err(|e| format!("Failed to resolve combined pivot path: {:?}", e))?;
+        remove_stale_pivot(db, portfolio_name, report_date, &combined_path)?;
     }
-    
+
+    Ok(None)
+}
+
+#[tauri::command]
+#[instrument(fields(portfolio = %portfolio_name, report_date = %report_date, is_daily))]
+pub fn delete_clearview_file(
+    upload_id: &str,
+    portfolio_name: &str,
+    report_date: &str,
+    is_daily: bool,
+) -> Result<UploadResponse, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    // Record an intent to delete this upload *before* actually deleting it
+    // (mirroring the write-ahead shape `stage_and_commit_pivot_swap`/
+    // `remove_stale_pivot` already use for the regenerate-or-remove step that
+    // follows): a crash between `delete_funder_upload` succeeding and that
+    // step finishing would otherwise leave no durable record for
+    // `recover_pending_clearview_deletions` to pick back up on restart.
+    let intent = PendingClearviewDeletion {
+        id: Uuid::new_v4().to_string(),
+        upload_id: upload_id.to_string(),
+        portfolio_name: portfolio_name.to_string(),
+        report_date: report_date.to_string(),
+        is_daily,
+        created_timestamp: Utc::now(),
+    };
+    {
+        let db_lock = DB.lock().unwrap();
+        let db = db_lock.as_ref().ok_or("Database not initialized")?;
+        db.insert_pending_clearview_deletion(&intent)
+            .map_err(|e| format!("Failed to record deletion intent: {}", e))?;
+    }
+
+    delete_funder_upload(upload_id)?;
+
+    let pivot_path = {
+        let db_lock = DB.lock().unwrap();
+        let db = db_lock.as_ref().ok_or("Database not initialized")?;
+        regenerate_or_remove_clearview_pivots(db, portfolio_name, report_date, is_daily)?
+    };
+
+    {
+        let db_lock = DB.lock().unwrap();
+        let db = db_lock.as_ref().ok_or("Database not initialized")?;
+        db.delete_pending_clearview_deletion(&intent.id)
+            .map_err(|e| format!("Failed to clear deletion intent: {}", e))?;
+    }
+
     Ok(UploadResponse {
         success: true,
-        message: "Clear View file deleted successfully".to_string(),
-        file_path: None,
+        message: if pivot_path.is_some() {
+            "Clear View daily file deleted and pivots updated".to_string()
+        } else {
+            "Clear View file deleted successfully".to_string()
+        },
+        file_path: pivot_path,
         version_id: None,
         backup_path: None,
     })
 }
 
+/// Scan for [`PendingClearviewDeletion`] intent records left behind by a
+/// process that died mid `delete_clearview_file` — either before the upload
+/// was deleted, or after it but before the pivot regenerate-or-remove step
+/// finished — and finish each one: delete the upload if it's still there,
+/// redo the regenerate-or-remove step, then clear the intent. Called once at
+/// startup, after the database is initialized, alongside
+/// [`recover_pending_pivot_swaps`].
+pub fn recover_pending_clearview_deletions(db: &Database) -> Result<(), String> {
+    let pending = db
+        .get_pending_clearview_deletions()
+        .map_err(|e| format!("Failed to list pending Clear View deletions: {}", e))?;
+
+    // Keep going past a single intent that fails to recover (e.g. its source
+    // directory moved) instead of aborting the whole startup scan via `?`,
+    // which would leave every other pending intent unprocessed and
+    // `init_database` failing outright.
+    let mut errors = Vec::new();
+    for intent in pending {
+        if let Err(e) = recover_one_pending_clearview_deletion(db, &intent) {
+            errors.push(format!("{}: {}", intent.id, e));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(format!(
+            "Failed to recover {} pending Clear View deletion(s): {}",
+            errors.len(),
+            errors.join("; ")
+        ));
+    }
+
+    Ok(())
+}
+
+fn recover_one_pending_clearview_deletion(
+    db: &Database,
+    intent: &PendingClearviewDeletion,
+) -> Result<(), String> {
+    let uploads = db.get_all_funder_uploads()
+        .map_err(|e| format!("Failed to get funder uploads: {}", e))?;
+    if uploads.iter().any(|u| u.id == intent.upload_id) {
+        delete_funder_upload_impl(db, &intent.upload_id)?;
+    }
+
+    regenerate_or_remove_clearview_pivots(db, &intent.portfolio_name, &intent.report_date, intent.is_daily)?;
+
+    db.delete_pending_clearview_deletion(&intent.id)
+        .map_err(|e| format!("Failed to clear recovered deletion intent: {}", e))?;
+
+    Ok(())
+}
+
+/// Apply `policy` to a portfolio's Clear View pivot CSVs (see
+/// [`Database::plan_clearview_pivot_prune`] for the bucketing/guard rules).
+/// With `dry_run: true`, nothing is touched — the returned decisions just
+/// show what would happen, including which rule kept each survivor. With
+/// `dry_run: false`, every pivot marked `removed` is deleted through
+/// [`remove_stale_pivot`], the same write-ahead-staged path
+/// `delete_clearview_file` uses, so an interrupted sweep recovers the same
+/// way an interrupted delete does.
+#[tauri::command]
+pub fn prune_clearview_pivots(
+    portfolio_name: &str,
+    policy: RetentionPolicy,
+    dry_run: bool,
+) -> Result<Vec<crate::database::PivotPruneDecision>, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let decisions = {
+        let db_lock = DB.lock().unwrap();
+        let db = db_lock.as_ref().ok_or("Database not initialized")?;
+        db.plan_clearview_pivot_prune(portfolio_name, &policy)
+            .map_err(|e| format!("Failed to plan pivot prune: {}", e))?
+    };
+
+    if !dry_run {
+        for decision in &decisions {
+            if decision.removed {
+                let db_lock = DB.lock().unwrap();
+                let db = db_lock.as_ref().ok_or("Database not initialized")?;
+                remove_stale_pivot(
+                    db,
+                    portfolio_name,
+                    &decision.pivot.report_date,
+                    Path::new(&decision.pivot.pivot_file_path),
+                )?;
+            }
+        }
+    }
+
+    Ok(decisions)
+}
+
 #[tauri::command]
 pub fn get_clearview_daily_files_for_week(
     portfolio_name: &str,
@@ -1443,29 +2840,139 @@ pub fn extract_merchants_from_portfolio(portfolio_name: &str) -> Result<ExtractM
     if DB.lock().unwrap().is_none() {
         init_database()?;
     }
-    
+
     let portfolio_path = get_portfolio_workbook_path(portfolio_name)?;
     let file_path = Path::new(&portfolio_path);
-    
+
     if !file_path.exists() {
         return Err(format!("Portfolio workbook not found: {}", portfolio_path));
     }
-    
+
     let db_lock = DB.lock().unwrap();
     let db = db_lock.as_ref().ok_or("Database not initialized")?;
-    
+
+    let column_mappings = db.get_all_column_mappings()
+        .map_err(|e| format!("Failed to load column mappings: {}", e))?;
+
     // Create parser and extract merchants
     let parser = PortfolioParser::new(portfolio_name.to_string());
-    let merchant_count = parser.parse_portfolio_workbook(file_path, db)
+    let extraction = parser.parse_portfolio_workbook_in_memory(file_path, &column_mappings)
         .map_err(|e| format!("Failed to extract merchants: {}", e))?;
-    
+
+    let mut merchant_count = 0;
+    for merchant in &extraction.merchants {
+        if let Err(e) = db.insert_or_update_merchant(merchant) {
+            eprintln!("Failed to save merchant: {}", e);
+        } else {
+            merchant_count += 1;
+        }
+    }
+    for learned in &extraction.learned_column_mappings {
+        let _ = db.upsert_column_mapping(
+            &learned.funder_name,
+            &learned.normalized_header,
+            &learned.field,
+            learned.confidence,
+        );
+    }
+
     Ok(ExtractMerchantsResponse {
         success: true,
         message: format!("Successfully extracted {} merchants from portfolio", merchant_count),
         merchant_count,
+        portfolio_name: portfolio_name.to_string(),
+        warnings: extraction.warnings,
     })
 }
 
+/// Batched, parallel version of [`extract_merchants_from_portfolio`] for
+/// extracting merchants from several portfolios at once.
+///
+/// Every workbook is opened and parsed concurrently via rayon — parsing
+/// never touches `DB`, since each worker scores headers against a single
+/// up-front snapshot of `column_mappings` instead of querying the database
+/// per header. Only the merged merchants and newly learned mappings are
+/// committed afterward, under one short-lived lock, so the parallel parse
+/// above never serializes on the mutex.
+#[tauri::command]
+pub fn extract_merchants_from_portfolios(portfolio_names: Vec<String>) -> Result<Vec<ExtractMerchantsResponse>, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let column_mappings = {
+        let db_lock = DB.lock().unwrap();
+        let db = db_lock.as_ref().ok_or("Database not initialized")?;
+        db.get_all_column_mappings()
+            .map_err(|e| format!("Failed to load column mappings: {}", e))?
+    };
+
+    let parsed: Vec<(String, Result<crate::parsers::PortfolioExtractionResult, String>)> = portfolio_names
+        .par_iter()
+        .map(|portfolio_name| {
+            let outcome = (|| {
+                let portfolio_path = get_portfolio_workbook_path(portfolio_name)?;
+                let file_path = Path::new(&portfolio_path);
+                if !file_path.exists() {
+                    return Err(format!("Portfolio workbook not found: {}", portfolio_path));
+                }
+
+                let parser = PortfolioParser::new(portfolio_name.clone());
+                parser.parse_portfolio_workbook_in_memory(file_path, &column_mappings)
+            })();
+            (portfolio_name.clone(), outcome)
+        })
+        .collect();
+
+    // Commit every portfolio's merchants and newly learned column mappings
+    // under one lock, now that the parallel parse above is done with `DB`.
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let mut responses = Vec::with_capacity(parsed.len());
+    for (portfolio_name, outcome) in parsed {
+        match outcome {
+            Ok(extraction) => {
+                let mut merchant_count = 0;
+                for merchant in &extraction.merchants {
+                    if let Err(e) = db.insert_or_update_merchant(merchant) {
+                        eprintln!("Failed to save merchant: {}", e);
+                    } else {
+                        merchant_count += 1;
+                    }
+                }
+                for learned in &extraction.learned_column_mappings {
+                    let _ = db.upsert_column_mapping(
+                        &learned.funder_name,
+                        &learned.normalized_header,
+                        &learned.field,
+                        learned.confidence,
+                    );
+                }
+
+                responses.push(ExtractMerchantsResponse {
+                    success: true,
+                    message: format!("Successfully extracted {} merchants from portfolio", merchant_count),
+                    merchant_count,
+                    portfolio_name,
+                    warnings: extraction.warnings,
+                });
+            }
+            Err(e) => {
+                responses.push(ExtractMerchantsResponse {
+                    success: false,
+                    message: e,
+                    merchant_count: 0,
+                    portfolio_name,
+                    warnings: Vec::new(),
+                });
+            }
+        }
+    }
+
+    Ok(responses)
+}
+
 #[tauri::command]
 pub fn get_merchants_by_portfolio(portfolio_name: &str) -> Result<Vec<MerchantInfo>, String> {
     if DB.lock().unwrap().is_none() {
@@ -1514,6 +3021,8 @@ pub struct ExtractMerchantsResponse {
     pub success: bool,
     pub message: String,
     pub merchant_count: usize,
+    pub portfolio_name: String,
+    pub warnings: Vec<crate::parsers::ExtractionWarning>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1557,4 +3066,155 @@ impl From<Merchant> for MerchantInfo {
             updated_timestamp: merchant.updated_timestamp.to_rfc3339(),
         }
     }
+}
+
+/// Recursively collect every file under `dir`, returning each one's path
+/// expressed relative to `base_dir` (so it can be re-joined onto a
+/// different `get_excelerate_dir()` on another machine at import time).
+fn collect_files_relative(dir: &Path, base_dir: &Path, out: &mut Vec<archive::ArchiveEntry>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read directory {:?}: {}", dir, e))? {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files_relative(&path, base_dir, out)?;
+        } else {
+            let relative_path = path
+                .strip_prefix(base_dir)
+                .map_err(|e| format!("Failed to compute relative path for {:?}: {}", path, e))?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let bytes = fs::read(&path)
+                .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+            out.push(archive::ArchiveEntry { relative_path, bytes });
+        }
+    }
+    Ok(())
+}
+
+/// Export a portfolio's entire on-disk tree (main workbook, version files and
+/// chunks, funder uploads, pivot CSVs) plus the DB rows that describe them
+/// into a single compressed archive at `dest_path`, so it can be copied to
+/// another machine or kept as an off-site backup.
+#[tauri::command]
+pub fn export_portfolio_archive(portfolio_name: &str, dest_path: &str) -> Result<(), String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let excelerate_dir = get_excelerate_dir()?;
+    let portfolio_dir = get_portfolio_dir(portfolio_name)?;
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let file_versions = db.get_versions_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load versions: {}", e))?;
+    let funder_uploads = db.get_funder_uploads_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load funder uploads: {}", e))?;
+    let funder_pivot_tables = db.get_funder_pivot_tables_by_portfolio(portfolio_name)
+        .map_err(|e| format!("Failed to load funder pivot tables: {}", e))?;
+
+    let mut version_chunk_manifests = std::collections::HashMap::new();
+    for version in &file_versions {
+        let manifest = db.get_version_chunk_manifest(&version.id)
+            .map_err(|e| format!("Failed to load chunk manifest for {}: {}", version.id, e))?;
+        if !manifest.is_empty() {
+            version_chunk_manifests.insert(version.id.clone(), manifest);
+        }
+    }
+
+    let manifest = archive::ArchiveManifest {
+        format_version: archive::FORMAT_VERSION,
+        portfolio_name: portfolio_name.to_string(),
+        exported_at: Utc::now(),
+        file_versions,
+        funder_uploads,
+        funder_pivot_tables,
+        version_chunk_manifests,
+    };
+
+    let mut entries = Vec::new();
+    collect_files_relative(&portfolio_dir, &excelerate_dir, &mut entries)?;
+
+    let archive_bytes = archive::build_archive(&manifest, &entries)?;
+    fs::write(dest_path, archive_bytes)
+        .map_err(|e| format!("Failed to write archive to {}: {}", dest_path, e))
+}
+
+/// Import a portfolio archive produced by [`export_portfolio_archive`],
+/// writing its files back under `get_excelerate_dir()` and re-inserting its
+/// DB rows, regenerating UUIDs for any version or funder upload whose id
+/// already exists locally. Returns the imported portfolio's name.
+#[tauri::command]
+pub fn import_portfolio_archive(archive_path: &str) -> Result<String, String> {
+    if DB.lock().unwrap().is_none() {
+        init_database()?;
+    }
+
+    let archive_bytes = fs::read(archive_path)
+        .map_err(|e| format!("Failed to read archive {}: {}", archive_path, e))?;
+    let (manifest, entries) = archive::read_archive(&archive_bytes)?;
+
+    let excelerate_dir = get_excelerate_dir()?;
+    for entry in &entries {
+        let dest_path = excelerate_dir.join(&entry.relative_path);
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+        fs::write(&dest_path, &entry.bytes)
+            .map_err(|e| format!("Failed to write {:?}: {}", dest_path, e))?;
+    }
+
+    let db_lock = DB.lock().unwrap();
+    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+
+    let mut version_id_remap: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    for mut version in manifest.file_versions {
+        let original_id = version.id.clone();
+        if db.get_version_portfolio_name(&version.id)
+            .map_err(|e| format!("Failed to check version {}: {}", version.id, e))?
+            .is_some()
+        {
+            let new_id = Uuid::new_v4().to_string();
+            version_id_remap.insert(original_id.clone(), new_id.clone());
+            version.id = new_id;
+        }
+
+        let portfolio_name = version.portfolio_name.clone();
+        db.insert_file_version(&version)
+            .map_err(|e| format!("Failed to import version {}: {}", version.id, e))?;
+
+        if let Some(chunk_hashes) = manifest.version_chunk_manifests.get(&original_id) {
+            db.record_version_chunks(&version.id, &portfolio_name, chunk_hashes)
+                .map_err(|e| format!("Failed to import chunk manifest for {}: {}", version.id, e))?;
+        }
+    }
+
+    let mut upload_id_remap: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    for mut upload in manifest.funder_uploads {
+        let original_id = upload.id.clone();
+        if db.funder_upload_id_exists(&upload.id)
+            .map_err(|e| format!("Failed to check funder upload {}: {}", upload.id, e))?
+        {
+            let new_id = Uuid::new_v4().to_string();
+            upload_id_remap.insert(original_id, new_id.clone());
+            upload.id = new_id;
+        }
+
+        db.insert_funder_upload(&upload)
+            .map_err(|e| format!("Failed to import funder upload {}: {}", upload.id, e))?;
+    }
+
+    for mut pivot in manifest.funder_pivot_tables {
+        if let Some(new_upload_id) = upload_id_remap.get(&pivot.upload_id) {
+            pivot.upload_id = new_upload_id.clone();
+        }
+        pivot.id = Uuid::new_v4().to_string();
+
+        db.insert_funder_pivot_table(&pivot)
+            .map_err(|e| format!("Failed to import funder pivot table: {}", e))?;
+    }
+
+    Ok(manifest.portfolio_name)
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+//! Crash-resilient tracking for long, multi-stage operations so a panic or
+//! forced quit mid-operation leaves a [`Job`] row behind instead of silently
+//! losing track of the half-finished work. Today only
+//! `process_clearview_daily_pivot` runs as a job; funder uploads and
+//! `delete_clearview_file` are not tracked here.
+//!
+//! A command wraps its stages with [`begin`]/[`advance`] and a terminal
+//! [`finish`] or [`fail`]; [`install_panic_hook`] logs whichever job (if
+//! any) is recorded in the shared in-flight slot when the panic hook fires;
+//! and [`recover_stuck_jobs`], called once from `init_database`, marks any
+//! job still `Pending`/`InProgress` after a restart as failed so it's
+//! surfaced by `get_jobs` for the user to act on via `resume_job`/`cancel_job`.
+
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::database::{Database, Job, JobStatus};
+
+/// `(job_id, stage)` of whatever job is currently running, so the panic
+/// hook can report it. Like the rest of this codebase's global `DB` lock,
+/// this is one shared slot rather than per-thread state — fine as long as
+/// commands that drive jobs don't run concurrently with each other, which
+/// holds today since they all serialize on the `DB` mutex anyway.
+static CURRENT_JOB: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+fn set_current(job_id: &str, stage: &str) {
+    *CURRENT_JOB.lock().unwrap() = Some((job_id.to_string(), stage.to_string()));
+}
+
+fn clear_current() {
+    *CURRENT_JOB.lock().unwrap() = None;
+}
+
+/// Start tracking a new job: persist a `Pending`-turned-`InProgress` row and
+/// return its id. Call [`advance`] as the operation reaches each later
+/// stage, then exactly one of [`finish`] or [`fail`] when it's done.
+pub fn begin(db: &Database, job_type: &str, portfolio_name: &str, report_date: &str) -> Result<String, String> {
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now();
+
+    let job = Job {
+        id: id.clone(),
+        job_type: job_type.to_string(),
+        portfolio_name: portfolio_name.to_string(),
+        report_date: report_date.to_string(),
+        stage: "started".to_string(),
+        status: JobStatus::InProgress,
+        error: None,
+        created_timestamp: now,
+        updated_timestamp: now,
+    };
+
+    db.create_job(&job).map_err(|e| format!("Failed to create job: {}", e))?;
+    set_current(&id, "started");
+    Ok(id)
+}
+
+/// Record that `job_id` has moved on to `stage`.
+pub fn advance(db: &Database, job_id: &str, stage: &str) -> Result<(), String> {
+    db.update_job_stage(job_id, stage)
+        .map_err(|e| format!("Failed to update job stage: {}", e))?;
+    set_current(job_id, stage);
+    Ok(())
+}
+
+/// Mark `job_id` `Completed` and stop tracking it as the in-flight job.
+pub fn finish(db: &Database, job_id: &str) -> Result<(), String> {
+    db.update_job_status(job_id, JobStatus::Completed, None)
+        .map_err(|e| format!("Failed to complete job: {}", e))?;
+    clear_current();
+    Ok(())
+}
+
+/// Mark `job_id` `Failed` with `error` and stop tracking it as the in-flight
+/// job. Callers should still propagate the original error to the caller;
+/// this only records it against the job row.
+pub fn fail(db: &Database, job_id: &str, error: &str) -> Result<(), String> {
+    db.update_job_status(job_id, JobStatus::Failed, Some(error))
+        .map_err(|e| format!("Failed to fail job: {}", e))?;
+    clear_current();
+    Ok(())
+}
+
+/// On startup, any job still `Pending`/`InProgress` can't genuinely still be
+/// running — the process that owned it is gone. Mark each one `Failed` with
+/// a note that it was interrupted, so `get_jobs` surfaces it and the user
+/// can `resume_job` (re-run the underlying command) or `cancel_job` it away.
+pub fn recover_stuck_jobs(db: &Database) -> Result<(), String> {
+    let stuck = db.get_incomplete_jobs()
+        .map_err(|e| format!("Failed to load incomplete jobs: {}", e))?;
+
+    for job in stuck {
+        db.update_job_status(
+            &job.id,
+            JobStatus::Failed,
+            Some("Interrupted: app restarted before this job reached a terminal stage"),
+        ).map_err(|e| format!("Failed to mark job {} as interrupted: {}", job.id, e))?;
+    }
+
+    Ok(())
+}
+
+/// Chain onto whatever panic hook is already installed (if any) a line
+/// logging the job (if any) recorded in the shared in-flight slot, so
+/// a crash log always names the job to look up in `get_jobs` rather than
+/// just a stack trace.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some((job_id, stage)) = CURRENT_JOB.lock().unwrap().clone() {
+            tracing::error!(job_id = %job_id, stage = %stage, "panic occurred while a job was in flight");
+        }
+        previous(info);
+    }));
+}
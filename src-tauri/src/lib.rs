@@ -1,6 +1,15 @@
+mod archive;
+mod chunk_store;
+mod compression;
 mod database;
 mod file_handler;
+mod jobs;
+mod logging;
+mod notification;
 pub mod parsers;
+mod retry;
+mod upload_session;
+mod validated_file_handler;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -14,7 +23,19 @@ pub fn run() {
     if let Err(e) = file_handler::ensure_directories() {
         eprintln!("Failed to create Excelerate directories: {}", e);
     }
-    
+
+    // Route tracing spans/events from the upload and pivot commands to a
+    // rolling log file (and, optionally, an OTLP collector) before the
+    // database is initialized, so its startup (and any job recovery) is covered.
+    match file_handler::get_excelerate_dir() {
+        Ok(app_data_dir) => logging::init(&app_data_dir),
+        Err(e) => eprintln!("Failed to determine Excelerate directory for logging: {}", e),
+    }
+
+    // So a crash log always names the job (if any) that was running when a
+    // panic hit, rather than just a bare stack trace.
+    jobs::install_panic_hook();
+
     // Initialize database on app startup
     if let Err(e) = file_handler::init_database() {
         eprintln!("Failed to initialize database: {}", e);
@@ -31,20 +52,49 @@ pub fn run() {
             file_handler::get_portfolio_versions,
             file_handler::get_versions_by_date,
             file_handler::restore_version,
+            file_handler::verify_version,
+            file_handler::verify_portfolio,
             file_handler::get_active_version,
             file_handler::check_version_exists,
             file_handler::delete_version,
+            file_handler::purge_version,
+            file_handler::set_retention_policy,
+            file_handler::run_retention,
+            file_handler::set_compression_config,
+            file_handler::get_compression_config,
+            file_handler::set_log_level,
             file_handler::save_funder_upload,
+            file_handler::begin_upload,
+            file_handler::push_upload_chunk,
+            file_handler::abort_upload,
+            file_handler::finish_upload,
             file_handler::get_funder_upload_info,
             file_handler::get_funder_uploads_for_date,
             file_handler::check_funder_upload_exists,
             file_handler::delete_funder_upload,
+            file_handler::prune_funder_uploads,
+            file_handler::verify_database_integrity,
+            file_handler::repair_database_integrity,
             file_handler::get_all_database_files,
             file_handler::read_csv_file,
             file_handler::read_excel_file,
+            file_handler::process_with_autodetect,
             file_handler::process_clearview_pivots,
             file_handler::process_clearview_daily_pivot,
-            file_handler::get_clearview_daily_files_for_week
+            file_handler::delete_clearview_file,
+            file_handler::prune_clearview_pivots,
+            file_handler::get_clearview_daily_files_for_week,
+            file_handler::extract_merchants_from_portfolio,
+            file_handler::extract_merchants_from_portfolios,
+            file_handler::get_jobs,
+            file_handler::resume_job,
+            file_handler::cancel_job,
+            file_handler::get_notification_history,
+            file_handler::mark_notification_read,
+            file_handler::clear_notification_history,
+            file_handler::export_portfolio_archive,
+            file_handler::import_portfolio_archive,
+            validated_file_handler::validate_funder_file
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
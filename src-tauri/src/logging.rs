@@ -0,0 +1,90 @@
+//! Structured tracing setup for the upload/pivot commands: a rolling daily
+//! log file under the app data dir, plus an optional OpenTelemetry OTLP
+//! exporter, both driven by a reloadable `EnvFilter` so [`set_log_level`]
+//! can raise verbosity in the field without a rebuild.
+
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing_subscriber::{filter::EnvFilter, fmt, prelude::*, reload, Registry};
+
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+static FILTER_HANDLE: OnceLock<FilterHandle> = OnceLock::new();
+static LOG_GUARD: OnceLock<tracing_appender::non_blocking::WorkerGuard> = OnceLock::new();
+
+/// Wire up the global subscriber: a rolling daily log file under
+/// `<app_data_dir>/logs`, an optional OTLP layer when
+/// `EXCELERATE_OTLP_ENDPOINT` is set, and a reloadable `EnvFilter` (seeded
+/// from `EXCELERATE_LOG`, default `info`). Call once, at startup.
+pub fn init(app_data_dir: &Path) {
+    let log_dir = app_data_dir.join("logs");
+    if let Err(e) = std::fs::create_dir_all(&log_dir) {
+        eprintln!("Failed to create log directory {}: {}", log_dir.display(), e);
+    }
+
+    let file_appender = tracing_appender::rolling::daily(&log_dir, "excelerate.log");
+    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+    let _ = LOG_GUARD.set(guard);
+
+    let default_filter = EnvFilter::try_from_env("EXCELERATE_LOG")
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(default_filter);
+    let _ = FILTER_HANDLE.set(handle);
+
+    let file_layer = fmt::layer()
+        .with_writer(non_blocking)
+        .with_ansi(false);
+
+    let otlp_layer = std::env::var("EXCELERATE_OTLP_ENDPOINT")
+        .ok()
+        .and_then(|endpoint| match build_otlp_layer(&endpoint) {
+            Ok(layer) => Some(layer),
+            Err(e) => {
+                eprintln!("Failed to initialize OTLP exporter: {}", e);
+                None
+            }
+        });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(otlp_layer)
+        .init();
+}
+
+/// Build the optional OTLP tracing layer, exporting spans to `endpoint` over
+/// gRPC. Kept separate from [`init`] so a bad/unreachable endpoint only
+/// disables telemetry export rather than the whole logging subsystem.
+fn build_otlp_layer(
+    endpoint: &str,
+) -> Result<impl tracing_subscriber::Layer<Registry> + Send + Sync, String> {
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .map_err(|e| format!("OTLP pipeline error: {}", e))?;
+
+    let tracer = provider.tracer("excelerate-desktop");
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Raise or lower verbosity at runtime — e.g. `"debug"`, or a full
+/// `EnvFilter` directive string like `"excelerate_lib::file_handler=trace"`
+/// — without restarting the app. Backs the `set_log_level` Tauri command so
+/// support staff can turn up logging on a machine that's already
+/// reproducing a bug.
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    let handle = FILTER_HANDLE.get().ok_or("Logging has not been initialized")?;
+    let filter = EnvFilter::try_new(level)
+        .map_err(|e| format!("Invalid log level '{}': {}", level, e))?;
+    handle
+        .reload(filter)
+        .map_err(|e| format!("Failed to reload log filter: {}", e))
+}
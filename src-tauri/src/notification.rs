@@ -1,6 +1,20 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use tauri::{AppHandle, Emitter};
 
+/// History keeps at most this many entries; the oldest is dropped once a
+/// new one would push the ring buffer past it.
+const HISTORY_CAPACITY: usize = 500;
+
+lazy_static::lazy_static! {
+    static ref HISTORY: Mutex<VecDeque<NotificationHistoryEntry>> = Mutex::new(VecDeque::new());
+}
+
+static NEXT_HISTORY_ID: AtomicU64 = AtomicU64::new(1);
+
 /// Notification types that can be sent from backend to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -20,6 +34,56 @@ pub struct NotificationPayload {
     pub duration: Option<u32>,
 }
 
+/// A [`NotificationPayload`] as recorded in history: stamped with a
+/// monotonically increasing id and the time it was sent, and tracking
+/// whether it's been acknowledged. Error notifications are the ones most
+/// worth keeping here since their toast (`duration: None`) requires manual
+/// dismissal and is easy to miss during a batch upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationHistoryEntry {
+    pub id: u64,
+    pub notification: NotificationPayload,
+    pub timestamp: DateTime<Utc>,
+    pub read: bool,
+}
+
+/// Append `notification` to the in-memory history ring buffer, dropping the
+/// oldest entry first if it's already at [`HISTORY_CAPACITY`].
+fn record_history(notification: &NotificationPayload) {
+    let entry = NotificationHistoryEntry {
+        id: NEXT_HISTORY_ID.fetch_add(1, Ordering::Relaxed),
+        notification: notification.clone(),
+        timestamp: Utc::now(),
+        read: false,
+    };
+
+    let mut history = HISTORY.lock().unwrap();
+    if history.len() >= HISTORY_CAPACITY {
+        history.pop_front();
+    }
+    history.push_back(entry);
+}
+
+/// Every recorded notification, oldest first.
+pub fn get_notification_history() -> Vec<NotificationHistoryEntry> {
+    HISTORY.lock().unwrap().iter().cloned().collect()
+}
+
+/// Mark the entry with `id` as read. Errors if no such entry exists (it may
+/// already have been pruned by the ring buffer's capacity).
+pub fn mark_notification_read(id: u64) -> Result<(), String> {
+    let mut history = HISTORY.lock().unwrap();
+    let entry = history.iter_mut().find(|e| e.id == id)
+        .ok_or_else(|| format!("No notification history entry with id {}", id))?;
+    entry.read = true;
+    Ok(())
+}
+
+/// Drop every recorded notification.
+pub fn clear_notification_history() {
+    HISTORY.lock().unwrap().clear();
+}
+
 /// File validation error details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationError {
@@ -142,8 +206,11 @@ impl ValidationResult {
 pub struct NotificationManager;
 
 impl NotificationManager {
-    /// Send a notification to the frontend
+    /// Send a notification to the frontend, recording it in history first so
+    /// it's still reviewable after the toast (especially an error toast,
+    /// which requires manual dismissal) disappears.
     pub fn send(app_handle: &AppHandle, notification: NotificationPayload) -> Result<(), String> {
+        record_history(&notification);
         app_handle
             .emit("backend-notification", notification)
             .map_err(|e| format!("Failed to send notification: {}", e))
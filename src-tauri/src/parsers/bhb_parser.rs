@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::Path;
+use rust_decimal::Decimal;
 use super::base_parser::*;
 
 pub struct BhbParser {
@@ -41,7 +42,7 @@ impl BaseParser for BhbParser {
             .ok_or(ParserError::UnsupportedFormat)?;
         
         match extension.to_lowercase().as_str() {
-            "csv" => read_csv_file(file_path),
+            "csv" => self.parse_csv_with_options(file_path),
             "xlsx" => read_excel_file(file_path, "Sheet1"),
             _ => Err(ParserError::UnsupportedFormat),
         }
@@ -64,69 +65,181 @@ impl BaseParser for BhbParser {
         // Get Deal ID and validate it's numeric
         let deal_id = row.get("Deal ID")
             .ok_or_else(|| ParserError::ProcessingError("Missing Deal ID".to_string()))?;
-        
+
         // Skip non-numeric Deal IDs
         if deal_id.parse::<f64>().is_err() {
             return Ok(None);
         }
-        
+
         let deal_name = row.get("Deal Name")
             .ok_or_else(|| ParserError::ProcessingError("Missing Deal Name".to_string()))?
             .clone();
-        
+
         let gross_amount = row.get("Participator Gross Amount")
             .ok_or_else(|| ParserError::ProcessingError("Missing Participator Gross Amount".to_string()))?;
-        let gross_amount = self.currency_to_float(gross_amount)?;
-        
+        let gross_amount = self.currency_to_decimal(gross_amount)?;
+
+        let non_qualifying_collections = row.get("Non Qualifying Collections")
+            .ok_or_else(|| ParserError::ProcessingError("Missing Non Qualifying Collections".to_string()))?;
+        let non_qualifying_collections = self.currency_to_decimal(non_qualifying_collections)?.abs();
+
+        let total_reversals = row.get("Total Reversals")
+            .ok_or_else(|| ParserError::ProcessingError("Missing Total Reversals".to_string()))?;
+        let total_reversals = self.currency_to_decimal(total_reversals)?.abs();
+
         let fee = row.get("Fee")
             .ok_or_else(|| ParserError::ProcessingError("Missing Fee".to_string()))?;
-        let fee = self.currency_to_float(fee)?.abs(); // Use absolute value of fee
-        
+        let fee = self.currency_to_decimal(fee)?.abs(); // Use absolute value of fee
+
+        let reserve_commission = row.get("Res. Commission")
+            .ok_or_else(|| ParserError::ProcessingError("Missing Res. Commission".to_string()))?;
+        let reserve_commission = self.currency_to_decimal(reserve_commission)?.abs();
+
         let net_amount = row.get("Net Payment Amount")
             .ok_or_else(|| ParserError::ProcessingError("Missing Net Payment Amount".to_string()))?;
-        let net_amount = self.currency_to_float(net_amount)?;
-        
+        let net_amount = self.currency_to_decimal(net_amount)?;
+
+        // Reversals and non-qualifying collections reduce gross; reserve
+        // commission folds into the combined fee. `net_amount` is still
+        // taken directly from "Net Payment Amount" (the funder's own
+        // bottom line), so the breakdown fields are kept for reconciliation
+        // and display rather than re-derived from them.
+        let adjusted_gross = gross_amount - total_reversals - non_qualifying_collections;
+        let combined_fee = fee + reserve_commission;
+
         Ok(Some(ProcessedData {
             advance_id: deal_id.clone(),
             merchant_name: deal_name,
-            gross_payment: gross_amount,
-            fees: fee,
+            gross_payment: adjusted_gross,
+            fees: combined_fee,
             net: net_amount,
+            non_qualifying_collections,
+            total_reversals,
+            reserve_commission,
+            status: String::new(),
         }))
     }
-    
+
     fn create_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
+        self.create_breakdown_pivot_table(data).map(|breakdown| breakdown.pivot)
+    }
+}
+
+/// One funder's worth of breakdown detail alongside the combined row it
+/// rolls up into, for the `Non Qualifying Collections` / `Total Reversals` /
+/// `Res. Commission` columns BHB statements need surfaced separately.
+#[derive(Debug, Clone)]
+pub struct BhbBreakdownRow {
+    pub advance_id: String,
+    pub merchant_name: String,
+    pub non_qualifying_collections: Decimal,
+    pub total_reversals: Decimal,
+    pub reserve_commission: Decimal,
+}
+
+/// A [`PivotTable`] plus the BHB-specific breakdown columns it was built
+/// from. `pivot` alone remains backward compatible (same combined
+/// `total_fee`/`total_net` every other funder produces); `breakdown` is
+/// additive detail for callers that want it.
+#[derive(Debug, Clone)]
+pub struct BhbPivotTable {
+    pub pivot: PivotTable,
+    pub breakdown: Vec<BhbBreakdownRow>,
+}
+
+impl BhbPivotTable {
+    /// Render the combined pivot columns followed by the BHB breakdown
+    /// columns, so a CSV reviewer can see exactly how a row's fee/net were
+    /// composed without touching the shared `PivotTable::to_csv_string`
+    /// format every other funder relies on.
+    pub fn to_csv_string(&self) -> ParserResult<String> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+
+        writer.write_record(&[
+            "Advance ID",
+            "Merchant Name",
+            "Sum of Syn Gross Amount",
+            "Total Servicing Fee",
+            "Sum of Syn Net Amount",
+            "Non Qualifying Collections",
+            "Total Reversals",
+            "Res. Commission",
+        ])?;
+
+        let breakdown_by_advance_id: HashMap<&str, &BhbBreakdownRow> = self
+            .breakdown
+            .iter()
+            .map(|row| (row.advance_id.as_str(), row))
+            .collect();
+
+        for row in &self.pivot.rows {
+            let breakdown = breakdown_by_advance_id.get(row.advance_id.as_str());
+            writer.write_record(&[
+                row.advance_id.clone(),
+                row.merchant_name.clone(),
+                row.sum_of_syn_gross_amount.to_string(),
+                row.total_servicing_fee.to_string(),
+                row.sum_of_syn_net_amount.to_string(),
+                breakdown.map(|b| b.non_qualifying_collections.to_string()).unwrap_or_default(),
+                breakdown.map(|b| b.total_reversals.to_string()).unwrap_or_default(),
+                breakdown.map(|b| b.reserve_commission.to_string()).unwrap_or_default(),
+            ])?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| {
+            ParserError::ProcessingError(format!("Failed to get CSV writer bytes: {}", e))
+        })?;
+
+        String::from_utf8(bytes).map_err(|e| {
+            ParserError::ProcessingError(format!("Failed to convert CSV to string: {}", e))
+        })
+    }
+}
+
+impl BhbParser {
+    /// Like `create_pivot_table`, but also returns the per-deal breakdown of
+    /// non-qualifying collections, reversals, and reserve commission that
+    /// went into each row's combined fee/gross.
+    pub fn create_breakdown_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<BhbPivotTable> {
         // Group by Advance ID and Merchant Name, summing the values
-        let mut grouped_data: HashMap<(String, String), (f64, f64, f64)> = HashMap::new();
-        
+        let mut grouped_data: HashMap<(String, String), (Decimal, Decimal, Decimal, Decimal, Decimal, Decimal)> = HashMap::new();
+
         for row in data {
             let key = (row.advance_id, row.merchant_name);
-            let entry = grouped_data.entry(key).or_insert((0.0, 0.0, 0.0));
+            let entry = grouped_data.entry(key).or_insert((
+                Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO,
+            ));
             entry.0 += row.gross_payment;
             entry.1 += row.fees;
             entry.2 += row.net;
+            entry.3 += row.non_qualifying_collections;
+            entry.4 += row.total_reversals;
+            entry.5 += row.reserve_commission;
         }
-        
+
         let mut pivot = PivotTable::new();
-        
+        let mut breakdown = Vec::new();
+
         // Sort by Advance ID
         let mut sorted_entries: Vec<_> = grouped_data.into_iter().collect();
         sorted_entries.sort_by(|a, b| a.0.0.cmp(&b.0.0));
-        
-        // Add data rows
-        for ((advance_id, merchant_name), (gross, fee, net)) in sorted_entries {
-            pivot.add_row(
+
+        // Add data rows. Values are exact sums of Decimal currency amounts;
+        // rounding only happens at display time (PivotTable::to_csv_string).
+        for ((advance_id, merchant_name), (gross, fee, net, nqc, reversals, reserve_commission)) in sorted_entries {
+            pivot.add_row(advance_id.clone(), merchant_name.clone(), gross, fee, net);
+            breakdown.push(BhbBreakdownRow {
                 advance_id,
                 merchant_name,
-                (gross * 100.0).round() / 100.0, // Round to 2 decimal places
-                (fee * 100.0).round() / 100.0,
-                (net * 100.0).round() / 100.0,
-            );
+                non_qualifying_collections: nqc,
+                total_reversals: reversals,
+                reserve_commission,
+            });
         }
-        
+
         // Add totals row
         pivot.add_totals_row();
-        
-        Ok(pivot)
+
+        Ok(BhbPivotTable { pivot, breakdown })
     }
 }
\ No newline at end of file
@@ -1,7 +1,58 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
+use rust_decimal::Decimal;
 use super::base_parser::*;
-use calamine::{Reader, Xlsx, open_workbook, Data};
+use crate::notification::{ValidationError, ValidationResult};
+use crate::retry::{retry_with_backoff, RetryPolicy};
+use calamine::{Reader, Xlsx, open_workbook};
+
+/// Outcome of [`ProcessedTradeRegistry::record`] for a single row.
+enum DedupOutcome {
+    /// First time this advance_id has been recorded; the row should be kept.
+    New,
+    /// Seen before with the same gross/fee/net — a confirmed duplicate
+    /// (e.g. the same advance repeated across portfolio tabs); the row
+    /// should be dropped instead of double-counted.
+    ConfirmedDuplicate,
+    /// Seen before with different amounts — likely a corrected re-upload
+    /// rather than a true duplicate; the caller should flag this instead of
+    /// silently merging the numbers.
+    Conflicting { previous_net: Decimal },
+}
+
+/// Dedup/accumulation registry for BIG advances, modeled on the investments
+/// crate's `processed_trades: Rc<RefCell<HashMap<u64,bool>>>` pattern: a
+/// cheaply-cloneable handle to shared, interior-mutable state so the same
+/// registry can be threaded through every sheet of a workbook (and, once a
+/// caller keeps one around across calls, across re-uploads of the same
+/// report) without double-counting an advance that shows up more than once.
+#[derive(Clone, Default)]
+struct ProcessedTradeRegistry {
+    seen: Rc<RefCell<HashMap<String, (Decimal, Decimal, Decimal)>>>,
+}
+
+impl ProcessedTradeRegistry {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `advance_id`'s (gross, fee, net) and report whether this is
+    /// its first appearance, a confirmed duplicate, or a conflict with a
+    /// prior recording under the same advance_id.
+    fn record(&self, advance_id: &str, gross: Decimal, fee: Decimal, net: Decimal) -> DedupOutcome {
+        let mut seen = self.seen.borrow_mut();
+        match seen.get(advance_id).copied() {
+            None => {
+                seen.insert(advance_id.to_string(), (gross, fee, net));
+                DedupOutcome::New
+            }
+            Some(previous) if previous == (gross, fee, net) => DedupOutcome::ConfirmedDuplicate,
+            Some(previous) => DedupOutcome::Conflicting { previous_net: previous.2 },
+        }
+    }
+}
 
 pub struct BigParser {
     funder_name: String,
@@ -13,133 +64,234 @@ impl BigParser {
             funder_name: "BIG".to_string(),
         }
     }
-    
-    fn detect_portfolio_sheet(&self, file_path: &Path) -> ParserResult<(String, String)> {
-        let workbook: Xlsx<_> = open_workbook(file_path)
-            .map_err(|_| ParserError::ProcessingError("Failed to open workbook".to_string()))?;
-        
-        // Look for sheets containing "R&H" (Alder) or "White Rabbit"
-        for sheet_name in workbook.sheet_names() {
-            if sheet_name.contains("R&H") {
-                return Ok(("Alder".to_string(), sheet_name.to_string()));
-            } else if sheet_name.contains("White Rabbit") {
-                return Ok(("White Rabbit".to_string(), sheet_name.to_string()));
-            }
-        }
-        
-        Err(ParserError::ProcessingError(
-            "Could not find portfolio sheet (R&H or White Rabbit)".to_string()
-        ))
-    }
-    
-    fn clean_advance_id(&self, value: &Data) -> Option<String> {
-        match value {
-            Data::Empty => None,
-            Data::String(s) => {
-                let trimmed = s.trim();
-                if trimmed.is_empty() {
-                    None
-                } else {
-                    Some(trimmed.to_string())
-                }
+
+    /// The columns `process_sheet_data` needs, declared rather than indexed
+    /// by hand: each one is resolved against the detected header row first,
+    /// falling back to BIG's historical fixed layout (column A = advance id,
+    /// C = merchant name, AI = total) only when the header can't be found.
+    fn column_specs() -> Vec<ColumnSpec> {
+        vec![
+            ColumnSpec {
+                field: "advance_id",
+                header_aliases: &["funding id", "fundingid", "funding_id", "advance id", "advanceid"],
+                fixed_offset: Some(0),
+                coercion: CellCoercion::Text,
+            },
+            ColumnSpec {
+                field: "merchant_name",
+                header_aliases: &["business name", "merchant name", "dba"],
+                fixed_offset: Some(2),
+                coercion: CellCoercion::Text,
+            },
+            ColumnSpec {
+                field: "total_amount",
+                header_aliases: &["total", "amount financed"],
+                fixed_offset: Some(34),
+                coercion: CellCoercion::Decimal,
             },
-            Data::Float(f) => {
-                // Convert float to integer string if it's a whole number
-                if f.fract() == 0.0 {
-                    Some((*f as i64).to_string())
+            ColumnSpec {
+                field: "fee_amount",
+                // No fixed_offset: BIG's historical layout has no dedicated
+                // fee column, so this only resolves for exports whose
+                // header names one; otherwise it stays unmapped and
+                // contributes zero.
+                header_aliases: &["servicing fee", "management fee", "fee", "adjustment"],
+                fixed_offset: None,
+                coercion: CellCoercion::Decimal,
+            },
+        ]
+    }
+
+    /// Every portfolio-bearing sheet in the workbook, keyed by portfolio
+    /// name — a workbook can contain both Alder/R&H and White Rabbit in one
+    /// upload, each on its own sheet.
+    fn detect_portfolio_sheets(&self, file_path: &Path) -> ParserResult<Vec<(String, String)>> {
+        let workbook: Xlsx<_> = retry_with_backoff(|| open_workbook(file_path), RetryPolicy::default())
+            .map_err(|_| ParserError::ProcessingError("Failed to open workbook".to_string()))?;
+
+        let portfolios: Vec<(String, String)> = workbook
+            .sheet_names()
+            .into_iter()
+            .filter_map(|sheet_name| {
+                if sheet_name.contains("R&H") {
+                    Some(("Alder".to_string(), sheet_name))
+                } else if sheet_name.contains("White Rabbit") {
+                    Some(("White Rabbit".to_string(), sheet_name))
                 } else {
-                    Some(f.to_string())
+                    None
                 }
-            },
-            Data::Int(i) => Some(i.to_string()),
-            _ => Some(value.to_string()),
+            })
+            .collect();
+
+        if portfolios.is_empty() {
+            return Err(ParserError::ProcessingError(
+                "Could not find portfolio sheet (R&H or White Rabbit)".to_string()
+            ));
         }
+
+        Ok(portfolios)
     }
-    
-    fn process_sheet_data(&self, file_path: &Path, sheet_name: &str) -> ParserResult<Vec<ProcessedData>> {
-        let mut workbook: Xlsx<_> = open_workbook(file_path)
+
+    /// The first portfolio-bearing sheet, for callers (like [`process`](BaseParser::process))
+    /// that only handle a single portfolio per upload.
+    fn detect_portfolio_sheet(&self, file_path: &Path) -> ParserResult<(String, String)> {
+        self.detect_portfolio_sheets(file_path)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ParserError::ProcessingError(
+                "Could not find portfolio sheet (R&H or White Rabbit)".to_string()
+            ))
+    }
+
+    /// Process every portfolio-bearing sheet in the workbook, keyed by
+    /// portfolio name, so a workbook containing both Alder/R&H and White
+    /// Rabbit produces a pivot for each rather than only the first one
+    /// found. Each portfolio gets its own [`ProcessedTradeRegistry`], so
+    /// duplicate detection stays scoped to that portfolio's own rows.
+    pub fn process_all(&self, file_path: &Path) -> ParserResult<HashMap<String, PivotTable>> {
+        let extension = file_path.extension()
+            .and_then(|e| e.to_str())
+            .ok_or(ParserError::UnsupportedFormat)?;
+
+        if extension.to_lowercase() != "xlsx" && extension.to_lowercase() != "xls" {
+            return Err(ParserError::UnsupportedFormat);
+        }
+
+        let portfolios = self.detect_portfolio_sheets(file_path)?;
+        let mut pivots = HashMap::new();
+
+        for (portfolio, sheet_name) in portfolios {
+            let processed_data = self.process_sheet_data(file_path, &sheet_name)?;
+            if processed_data.is_empty() {
+                continue;
+            }
+            pivots.insert(portfolio, self.create_pivot_table(processed_data)?);
+        }
+
+        if pivots.is_empty() {
+            return Err(ParserError::ProcessingError("No valid data found".to_string()));
+        }
+
+        Ok(pivots)
+    }
+
+    /// Like [`process_sheet_data`](Self::process_sheet_data), but also
+    /// returns how many rows `registry` collapsed as confirmed duplicates
+    /// and a warning per row whose advance_id repeated with different
+    /// amounts (kept, not dropped, since it's more likely a correction than
+    /// true double-counting).
+    fn process_sheet_data_deduped(
+        &self,
+        file_path: &Path,
+        sheet_name: &str,
+        registry: &ProcessedTradeRegistry,
+    ) -> ParserResult<(Vec<ProcessedData>, usize, Vec<String>)> {
+        let mut workbook: Xlsx<_> = retry_with_backoff(|| open_workbook(file_path), RetryPolicy::default())
             .map_err(|_| ParserError::ProcessingError("Failed to open workbook".to_string()))?;
-        
+
         let range = workbook.worksheet_range(sheet_name)
             .map_err(|e| ParserError::ProcessingError(format!("Failed to read sheet '{}': {:?}", sheet_name, e)))?;
-        
+
         let mut processed_data = Vec::new();
-        
-        // Find the header row (look for "Funding ID" or similar in column A)
-        let mut data_start_row = 3; // Default start row
-        let header_values = vec!["funding id", "fundingid", "funding_id", "id", "advance id", "advanceid"];
-        
-        for (row_idx, row) in range.rows().enumerate().take(10) {
-            if let Some(first_cell) = row.get(0) {
-                let cell_str = first_cell.to_string().to_lowercase();
-                if header_values.iter().any(|h| cell_str.contains(h)) {
-                    data_start_row = row_idx + 1;
-                    break;
-                }
-            }
-        }
-        
+        let mut duplicates_collapsed = 0usize;
+        let mut conflict_warnings = Vec::new();
+
+        // Same 3-anchor-token approach BoomParser uses: require "funding",
+        // "business" and "total" to all appear in the same row, rather than
+        // a single header string, so a stray cell that happens to contain
+        // one of these words doesn't get mistaken for the real header row.
+        let header_row_index = self
+            .find_header_row(&range, &["funding", "business", "total"], 10)
+            .ok();
+        let data_start_row = header_row_index.map(|idx| idx + 1).unwrap_or(3); // Default start row
+        let header_row: Vec<calamine::Data> = header_row_index
+            .and_then(|idx| range.rows().nth(idx))
+            .map(|row| row.to_vec())
+            .unwrap_or_default();
+        let mapper = RowMapper::from_header_row(&header_row, &Self::column_specs());
+
         // Process data rows
-        for (_row_idx, row) in range.rows().enumerate().skip(data_start_row) {
-            // Column A (0): Funding ID / Advance ID
-            let advance_id = row.get(0)
-                .and_then(|cell| self.clean_advance_id(cell));
-            
+        for row in range.rows().skip(data_start_row) {
+            let advance_id = mapper.text(row, "advance_id");
+
             if advance_id.is_none() {
                 continue; // Skip rows without valid advance ID
             }
-            
-            // Column C (2): Business Name / Merchant Name
-            let merchant_name = row.get(2)
-                .map(|cell| cell.to_string())
-                .unwrap_or_default();
-            
-            // Column AI (34): Total amount (usually has SUM formula)
-            // First try column AI
-            let mut net_amount = row.get(34)
-                .and_then(|cell| match cell {
-                    Data::Float(f) => Some(*f),
-                    Data::Int(i) => Some(*i as f64),
-                    _ => None,
-                })
-                .unwrap_or(0.0);
-            
-            // If column AI is 0 or not available, try summing columns AJ to AP (35-41)
-            // These are the daily payment columns (skip AF which is % completed)
-            // Column 35 (AJ) = "Payments 9/5/25"
-            // Column 36 (AK) = "Payments 9/4/25" 
-            // ... through column 41 (AP)
-            if net_amount == 0.0 {
-                let mut sum = 0.0;
+
+            let merchant_name = mapper.text(row, "merchant_name").unwrap_or_default();
+
+            // Column AI: Total amount collected (usually has SUM formula)
+            let mut gross_amount = mapper.decimal(row, "total_amount");
+
+            // If the total column is 0 or not available, try summing columns
+            // AJ to AP (35-41) — the daily payment columns (e.g. "Payments
+            // 9/5/25", "Payments 9/4/25", ...). These are date-named, not a
+            // stable header, so they're summed by position rather than via
+            // a ColumnSpec. A negative cell here is a refund/clawback/
+            // reversal on that day, not an empty cell — it's summed in
+            // along with the rest rather than filtered out, so it nets
+            // against the advance's other collections instead of being lost.
+            if gross_amount.is_zero() {
+                let mut sum = Decimal::ZERO;
                 for col_idx in 35..=41 {
                     if let Some(cell) = row.get(col_idx) {
-                        match cell {
-                            Data::Float(f) => sum += f,
-                            Data::Int(i) => sum += *i as f64,
-                            _ => {}
-                        }
+                        sum += cell_to_decimal(cell);
                     }
                 }
-                if sum != 0.0 {
-                    net_amount = sum;
+                if !sum.is_zero() {
+                    gross_amount = sum;
                 }
             }
-            
-            // Skip rows with zero amounts (likely empty or summary rows)
-            if net_amount == 0.0 && merchant_name.trim().is_empty() {
+
+            // A dedicated fee/adjustment column, when the export breaks one
+            // out, reduces net rather than being discarded, so gross - fee
+            // == net still reconciles even when reversals are present.
+            let fee_amount = mapper.decimal(row, "fee_amount");
+            let net_amount = gross_amount - fee_amount;
+
+            // Skip rows with nothing at all (likely empty or summary rows)
+            // — but not a row whose net merely nets to zero because a
+            // reversal offset a collection; that row still needs recording.
+            if gross_amount.is_zero() && fee_amount.is_zero() && merchant_name.trim().is_empty() {
                 continue;
             }
-            
+
+            let advance_id = advance_id.unwrap();
+
+            match registry.record(&advance_id, gross_amount, fee_amount, net_amount) {
+                DedupOutcome::ConfirmedDuplicate => {
+                    duplicates_collapsed += 1;
+                    continue;
+                }
+                DedupOutcome::Conflicting { previous_net } => {
+                    conflict_warnings.push(format!(
+                        "Advance {} appeared more than once with different amounts (previously {}, now {}); both were kept — please verify which is correct",
+                        advance_id, previous_net, net_amount
+                    ));
+                }
+                DedupOutcome::New => {}
+            }
+
             processed_data.push(ProcessedData {
-                advance_id: advance_id.unwrap(),
+                advance_id,
                 merchant_name,
-                gross_payment: net_amount,  // BIG doesn't separate gross/net
-                fees: 0.0,  // BIG doesn't provide separate fee information
+                gross_payment: gross_amount,
+                fees: fee_amount,
                 net: net_amount,
+                ..Default::default()
             });
         }
-        
-        Ok(processed_data)
+
+        Ok((processed_data, duplicates_collapsed, conflict_warnings))
+    }
+
+    /// Thin wrapper over [`process_sheet_data_deduped`](Self::process_sheet_data_deduped)
+    /// for callers that only need the rows, with a fresh registry scoped to
+    /// this one call.
+    fn process_sheet_data(&self, file_path: &Path, sheet_name: &str) -> ParserResult<Vec<ProcessedData>> {
+        let registry = ProcessedTradeRegistry::new();
+        self.process_sheet_data_deduped(file_path, sheet_name, &registry)
+            .map(|(data, _duplicates_collapsed, _conflicts)| data)
     }
 }
 
@@ -165,7 +317,74 @@ impl BaseParser for BigParser {
         // BIG files are validated differently (by sheet names)
         Ok(())
     }
-    
+
+    /// BIG has no header row to sniff (the default impl always scores `0.0`
+    /// via the empty `get_required_columns`), so score on the same
+    /// sheet-name signal `detect_portfolio_sheet` uses instead — same
+    /// pattern `BoomParser` uses for its own fixed-layout detection.
+    /// Overriding `detection_score` rather than `matches_file` directly
+    /// keeps BIG detectable through both the score-based
+    /// [`super::ParserRegistry`] and `matches_file`'s default (which derives
+    /// from `detection_score`), instead of only the latter.
+    fn detection_score(&self, file_path: &Path) -> f64 {
+        if self.detect_portfolio_sheet(file_path).is_ok() {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// The default impl checks `get_required_columns` against row 1, which
+    /// tells the caller nothing useful for BIG (empty, positional layout).
+    /// Instead, run the real parse with a [`ProcessedTradeRegistry`] and
+    /// surface what it found: a missing/unreadable portfolio sheet as an
+    /// error, and any collapsed or conflicting duplicate advances as
+    /// warnings, so `save_funder_upload_validated`'s notification reports
+    /// them instead of the upload silently double-counting.
+    fn validate_file_structure(&self, file_path: &Path) -> ValidationResult {
+        let mut result = ValidationResult::valid();
+
+        let (_portfolio, sheet_name) = match self.detect_portfolio_sheet(file_path) {
+            Ok(found) => found,
+            Err(e) => {
+                result.add_error(ValidationError {
+                    field: "File Format".to_string(),
+                    expected: format!("{} file format", self.get_funder_name()),
+                    found: format!("Invalid format: {}", e),
+                    line: None,
+                    column: None,
+                });
+                return result;
+            }
+        };
+
+        let registry = ProcessedTradeRegistry::new();
+        match self.process_sheet_data_deduped(file_path, &sheet_name, &registry) {
+            Ok((_data, duplicates_collapsed, conflicts)) => {
+                if duplicates_collapsed > 0 {
+                    result.add_warning(format!(
+                        "Collapsed {} duplicate advance row(s) that appeared more than once with identical amounts",
+                        duplicates_collapsed
+                    ));
+                }
+                for conflict in conflicts {
+                    result.add_warning(conflict);
+                }
+            }
+            Err(e) => {
+                result.add_error(ValidationError {
+                    field: "File Format".to_string(),
+                    expected: format!("{} file format", self.get_funder_name()),
+                    found: format!("Invalid format: {}", e),
+                    line: None,
+                    column: None,
+                });
+            }
+        }
+
+        result
+    }
+
     fn process_row(&self, _row: &HashMap<String, String>) -> ParserResult<Option<ProcessedData>> {
         // Not used for BIG parser
         Err(ParserError::ProcessingError(
@@ -175,22 +394,22 @@ impl BaseParser for BigParser {
     
     fn create_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
         // Group by Advance ID and Merchant Name, summing the values
-        let mut grouped_data: HashMap<(String, String), (f64, f64, f64)> = HashMap::new();
-        
+        let mut grouped_data: HashMap<(String, String), (Decimal, Decimal, Decimal)> = HashMap::new();
+
         for row in data {
             let key = (row.advance_id, row.merchant_name);
-            let entry = grouped_data.entry(key).or_insert((0.0, 0.0, 0.0));
+            let entry = grouped_data.entry(key).or_insert((Decimal::ZERO, Decimal::ZERO, Decimal::ZERO));
             entry.0 += row.gross_payment;
             entry.1 += row.fees;
             entry.2 += row.net;
         }
-        
+
         let mut pivot = PivotTable::new();
-        
+
         // Sort by Advance ID
         let mut sorted_entries: Vec<_> = grouped_data.into_iter().collect();
         sorted_entries.sort_by(|a, b| a.0.0.cmp(&b.0.0));
-        
+
         // Add data rows
         for ((advance_id, merchant_name), (gross, fee, net)) in sorted_entries {
             pivot.add_row(
@@ -201,13 +420,16 @@ impl BaseParser for BigParser {
                 net,
             );
         }
-        
+
         // Add totals row
         pivot.add_totals_row();
-        
+
         Ok(pivot)
     }
-    
+
+    /// Single-portfolio case: builds a pivot only for the first
+    /// portfolio-bearing sheet found. Use [`process_all`](Self::process_all)
+    /// for a workbook that may contain more than one portfolio.
     fn process(&self, file_path: &Path) -> ParserResult<PivotTable> {
         // Check file extension
         let extension = file_path.extension()
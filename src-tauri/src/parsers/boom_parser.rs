@@ -1,7 +1,61 @@
 use std::collections::HashMap;
 use std::path::Path;
+use rust_decimal::Decimal;
+use std::str::FromStr;
 use super::base_parser::*;
-use calamine::{Reader, Xlsx, open_workbook, Data};
+use crate::notification::{ValidationError, ValidationResult};
+use crate::retry::{retry_with_backoff, RetryPolicy};
+use calamine::{Reader, Xlsx, open_workbook, Data, Range};
+
+/// How many rows from the top of the sheet to scan looking for a header
+/// row, before giving up and falling back to the fixed layout. Boom's
+/// header has always been within the first dozen rows; 25 leaves headroom
+/// for an extra banner row or two without scanning the whole sheet.
+const HEADER_SCAN_ROWS: usize = 25;
+
+/// The logical fields Boom's statement needs, each with the header text
+/// variations its export has used, in priority order. Used to locate each
+/// field's column by fuzzy-matching the detected header row instead of
+/// trusting a fixed index that breaks the moment Boom reorders a column.
+const BOOM_FIELD_VARIATIONS: &[(&str, &[&str])] = &[
+    ("advance_id", &["advance", "advance name", "advance id"]),
+    ("merchant_name", &["merchant", "merchant name"]),
+    ("gross_amount", &["gross", "gross amount"]),
+    ("management_fee", &["management fee", "fee"]),
+    ("net_amount", &["amount", "net amount", "net"]),
+];
+
+/// Where each logical field lives in Boom's current template (Excel row 11,
+/// calamine index 10) — the layout this parser used before header detection
+/// existed, kept as a fallback for the rare file detection can't read.
+const BOOM_FIXED_COLUMN_INDICES: &[(&str, usize)] = &[
+    ("advance_id", 0),
+    ("merchant_name", 2),
+    ("gross_amount", 13),
+    ("management_fee", 14),
+    ("net_amount", 15),
+];
+
+/// Which calamine row holds the header, and which column each logical field
+/// maps to, resolved either by scanning for the header row or by falling
+/// back to [`BOOM_FIXED_COLUMN_INDICES`].
+struct BoomColumnMap {
+    header_row_index: usize,
+    indices: HashMap<&'static str, usize>,
+    /// Fields [`BOOM_FIELD_VARIATIONS`] lists but couldn't be matched to any
+    /// column in the detected header row.
+    missing_fields: Vec<&'static str>,
+}
+
+impl BoomColumnMap {
+    fn fixed_layout() -> Self {
+        BoomColumnMap {
+            header_row_index: 10,
+            indices: BOOM_FIXED_COLUMN_INDICES.iter().copied().collect(),
+            missing_fields: Vec::new(),
+        }
+    }
+}
 
 pub struct BoomParser {
     funder_name: String,
@@ -38,27 +92,89 @@ impl BoomParser {
         }
     }
     
-    fn parse_currency(&self, value: &Data) -> f64 {
+    fn parse_currency(&self, value: &Data) -> Decimal {
         match value {
-            Data::Float(f) => *f,
-            Data::Int(i) => *i as f64,
+            Data::Float(f) => Decimal::from_f64_retain(*f).unwrap_or(Decimal::ZERO),
+            Data::Int(i) => Decimal::from(*i),
             Data::String(s) => {
                 // Try to parse string as currency
-                s.replace('$', "")
-                    .replace(',', "")
-                    .replace('(', "-")
-                    .replace(')', "")
-                    .trim()
-                    .parse::<f64>()
-                    .unwrap_or(0.0)
+                Decimal::from_str(
+                    s.replace('$', "")
+                        .replace(',', "")
+                        .replace('(', "-")
+                        .replace(')', "")
+                        .trim(),
+                )
+                .unwrap_or(Decimal::ZERO)
             },
-            _ => 0.0,
+            _ => Decimal::ZERO,
         }
     }
-    
+
+    /// Scan the first [`HEADER_SCAN_ROWS`] rows for the one whose cells
+    /// contain "advance", "merchant", and "gross" (Boom's template has never
+    /// shipped without all three), then fuzzy-match every other logical
+    /// field's column against that row. Returns `None` if no row in range
+    /// matches all three anchor tokens, so the caller can fall back to the
+    /// fixed layout.
+    fn detect_column_map(&self, range: &Range<Data>) -> Option<BoomColumnMap> {
+        for (row_index, row) in range.rows().enumerate().take(HEADER_SCAN_ROWS) {
+            let cells: Vec<String> = row
+                .iter()
+                .filter_map(|cell| self.clean_value(cell))
+                .map(|value| value.to_lowercase())
+                .collect();
+
+            let has_advance = cells.iter().any(|c| c.contains("advance"));
+            let has_merchant = cells.iter().any(|c| c.contains("merchant"));
+            let has_gross = cells.iter().any(|c| c.contains("gross"));
+            if !(has_advance && has_merchant && has_gross) {
+                continue;
+            }
+
+            let mut indices: HashMap<&'static str, usize> = HashMap::new();
+            let mut missing_fields = Vec::new();
+
+            for (field, variations) in BOOM_FIELD_VARIATIONS {
+                // Variations are tried in priority order, skipping any column
+                // an earlier field already claimed (so net's "amount" can't
+                // land on the "Gross Amount" column).
+                let column = variations.iter().find_map(|variation| {
+                    row.iter().enumerate().find_map(|(col, cell)| {
+                        let value = self.clean_value(cell)?.to_lowercase();
+                        (value.contains(variation) && !indices.values().any(|&claimed| claimed == col))
+                            .then_some(col)
+                    })
+                });
+
+                match column {
+                    Some(col) => {
+                        indices.insert(*field, col);
+                    }
+                    None => missing_fields.push(*field),
+                }
+            }
+
+            return Some(BoomColumnMap {
+                header_row_index: row_index,
+                indices,
+                missing_fields,
+            });
+        }
+
+        None
+    }
+
     fn process_sheet_data(&self, file_path: &Path) -> ParserResult<Vec<ProcessedData>> {
-        let mut workbook: Xlsx<_> = open_workbook(file_path)
-            .map_err(|_| ParserError::ProcessingError("Failed to open workbook".to_string()))?;
+        // Boom statements are typically dropped into a synced folder, so a
+        // sync client or Excel itself can still be holding the file open
+        // when this runs; retry past that instead of failing the whole
+        // upload on a transient lock.
+        let mut workbook: Xlsx<_> = retry_with_backoff(
+            || open_workbook(file_path),
+            RetryPolicy::default(),
+        )
+        .map_err(|_| ParserError::ProcessingError("Failed to open workbook".to_string()))?;
         
         // Get the first sheet (usually "Syndicator Remittance Details -")
         let sheet_names = workbook.sheet_names();
@@ -70,97 +186,72 @@ impl BoomParser {
         
         let range = workbook.worksheet_range(&sheet_name)
             .map_err(|e| ParserError::ProcessingError(format!("Failed to read sheet '{}': {:?}", sheet_name, e)))?;
-        
+
+        // Boom's template has moved its header row before (an extra banner
+        // row, a reordered column); detect it by content instead of trusting
+        // a fixed offset, only falling back to the known-good fixed layout
+        // when nothing in the first rows looks like a header.
+        let column_map = self.detect_column_map(&range).unwrap_or_else(BoomColumnMap::fixed_layout);
+
+        let advance_id_col = *column_map.indices.get("advance_id")
+            .ok_or_else(|| ParserError::ProcessingError("Could not locate an 'Advance' column".to_string()))?;
+        let merchant_col = *column_map.indices.get("merchant_name")
+            .ok_or_else(|| ParserError::ProcessingError("Could not locate a 'Merchant' column".to_string()))?;
+        let gross_col = *column_map.indices.get("gross_amount")
+            .ok_or_else(|| ParserError::ProcessingError("Could not locate a 'Gross Amount' column".to_string()))?;
+        let fee_col = column_map.indices.get("management_fee").copied();
+        let net_col = column_map.indices.get("net_amount").copied();
+
+        let data_start_row = column_map.header_row_index + 1;
         let mut processed_data = Vec::new();
-        
-        // IMPORTANT: Excel column A is completely empty and calamine skips it
-        // So calamine's indexing is shifted - what Excel shows as column B becomes index 0
-        // 
-        // Headers are on Excel row 11 (calamine index 10), data starts from Excel row 12 (calamine index 11)
-        // Column mapping (Excel column → calamine index):
-        // Excel B → Index 0: "Advance: Advance Name" (Advance ID)
-        // Excel C → Index 1: Empty
-        // Excel D → Index 2: "Merchant" (Merchant Name)
-        // Excel E → Index 3: "Funded Date"
-        // ...
-        // Excel O → Index 13: "Gross Amount"
-        // Excel P → Index 14: "Management Fee"  
-        // Excel Q → Index 15: "Amount" (Net Amount)
-        
-        let header_row_idx = 10;  // Row 11 in Excel (0-indexed)
-        let data_start_row = header_row_idx + 1;  // Row 12 in Excel
-        
-        // Verify headers are in expected positions
-        if let Some(header_row) = range.rows().nth(header_row_idx) {
-            // Check if we have the expected headers
-            let advance_header = header_row.get(0).and_then(|cell| self.clean_value(cell));
-            let merchant_header = header_row.get(2).and_then(|cell| self.clean_value(cell));
-            let gross_header = header_row.get(13).and_then(|cell| self.clean_value(cell));
-            
-            if advance_header.is_none() || 
-               !advance_header.as_ref().unwrap().to_lowercase().contains("advance") ||
-               merchant_header.is_none() || 
-               !merchant_header.as_ref().unwrap().to_lowercase().contains("merchant") ||
-               gross_header.is_none() || 
-               !gross_header.as_ref().unwrap().to_lowercase().contains("gross") {
-                return Err(ParserError::ProcessingError(
-                    format!("Expected headers not found. Looking for 'Advance' in column A (found: {:?}), 'Merchant' in column C (found: {:?}), and 'Gross Amount' in column N (found: {:?})",
-                        advance_header, merchant_header, gross_header)
-                ));
-            }
-        } else {
-            return Err(ParserError::ProcessingError("Header row (row 11) not found".to_string()));
-        }
-        
+
         // Process data rows
-        for (_row_idx, row) in range.rows().enumerate().skip(data_start_row) {
-            // Column A (0): Advance ID
-            let advance_id = row.get(0)
+        for row in range.rows().skip(data_start_row) {
+            let advance_id = row.get(advance_id_col)
                 .and_then(|cell| self.clean_value(cell));
-            
+
             if advance_id.is_none() {
                 continue; // Skip rows without valid advance ID
             }
-            
-            // Column C (2): Merchant Name (Note: Column B is empty, so merchant is at index 2)
-            let merchant_name = row.get(2)
+
+            let merchant_name = row.get(merchant_col)
                 .and_then(|cell| self.clean_value(cell))
                 .unwrap_or_default();
-            
+
             // Skip if merchant name is empty
             if merchant_name.is_empty() {
                 continue;
             }
-            
-            // Column N (13): Gross Amount
-            let gross_payment = row.get(13)
+
+            let gross_payment = row.get(gross_col)
                 .map(|cell| self.parse_currency(cell))
-                .unwrap_or(0.0);
-            
-            // Column O (14): Management Fee
-            let fees = row.get(14)
+                .unwrap_or(Decimal::ZERO);
+
+            let fees = fee_col
+                .and_then(|col| row.get(col))
                 .map(|cell| self.parse_currency(cell))
-                .unwrap_or(0.0);
-            
-            // Column P (15): Net Amount
-            let net = row.get(15)
+                .unwrap_or(Decimal::ZERO);
+
+            let net = net_col
+                .and_then(|col| row.get(col))
                 .map(|cell| self.parse_currency(cell))
-                .unwrap_or(0.0);
-            
+                .unwrap_or(Decimal::ZERO);
+
             // Skip rows with all zero amounts
-            if gross_payment == 0.0 && fees == 0.0 && net == 0.0 {
+            if gross_payment.is_zero() && fees.is_zero() && net.is_zero() {
                 continue;
             }
-            
+
             processed_data.push(ProcessedData {
                 advance_id: advance_id.unwrap(),
                 merchant_name,
                 gross_payment,
                 fees,
                 net,
+                ..Default::default()
             });
         }
-        
+
         Ok(processed_data)
     }
 }
@@ -187,7 +278,118 @@ impl BaseParser for BoomParser {
         // Boom files are validated differently (by column positions)
         Ok(())
     }
-    
+
+    /// Boom's header row is Excel row 11 (calamine index 10), not row 1, so
+    /// the default `get_required_columns`-against-row-1 check never applies.
+    /// Score by how many of the three fixed-position cells that row is
+    /// expected to hold ("Advance", "Merchant", "Gross") actually look right.
+    fn detection_score(&self, file_path: &Path) -> f64 {
+        let Ok(mut workbook) = open_workbook::<Xlsx<_>, _>(file_path) else {
+            return 0.0;
+        };
+        let Some(sheet_name) = workbook.sheet_names().first().cloned() else {
+            return 0.0;
+        };
+        let Ok(range) = workbook.worksheet_range(&sheet_name) else {
+            return 0.0;
+        };
+        let Some(header_row) = range.rows().nth(10) else {
+            return 0.0;
+        };
+
+        let matches_at = |col: usize, needle: &str| {
+            header_row
+                .get(col)
+                .and_then(|cell| self.clean_value(cell))
+                .map(|value| value.to_lowercase().contains(needle))
+                .unwrap_or(false)
+        };
+
+        let hits = [
+            matches_at(0, "advance"),
+            matches_at(2, "merchant"),
+            matches_at(13, "gross"),
+        ]
+        .iter()
+        .filter(|matched| **matched)
+        .count();
+
+        hits as f64 / 3.0
+    }
+
+    /// The default impl checks `get_required_columns` against row 1, which
+    /// is always empty for Boom since it has no named columns. Instead, run
+    /// the same header-row detection [`BoomParser::process_sheet_data`]
+    /// uses and warn about any logical field it couldn't locate, so the
+    /// notification layer can tell the user exactly which column went
+    /// missing instead of the parser just silently falling back.
+    fn validate_file_structure(&self, file_path: &Path) -> ValidationResult {
+        let mut result = ValidationResult::valid();
+
+        let workbook: Result<Xlsx<_>, _> = retry_with_backoff(|| open_workbook(file_path), RetryPolicy::default());
+        let mut workbook = match workbook {
+            Ok(workbook) => workbook,
+            Err(e) => {
+                result.add_error(ValidationError {
+                    field: "File Format".to_string(),
+                    expected: format!("{} file format", self.get_funder_name()),
+                    found: format!("Invalid format: {}", e),
+                    line: None,
+                    column: None,
+                });
+                return result;
+            }
+        };
+
+        let Some(sheet_name) = workbook.sheet_names().first().cloned() else {
+            result.add_error(ValidationError {
+                field: "Sheet".to_string(),
+                expected: "at least one sheet".to_string(),
+                found: "none".to_string(),
+                line: None,
+                column: None,
+            });
+            return result;
+        };
+
+        let range = match workbook.worksheet_range(&sheet_name) {
+            Ok(range) => range,
+            Err(e) => {
+                result.add_error(ValidationError {
+                    field: "Sheet".to_string(),
+                    expected: sheet_name,
+                    found: format!("Unreadable: {:?}", e),
+                    line: None,
+                    column: None,
+                });
+                return result;
+            }
+        };
+
+        match self.detect_column_map(&range) {
+            Some(column_map) => {
+                for field in &column_map.missing_fields {
+                    result.add_warning(format!(
+                        "Could not locate a column for '{}' in the detected header row — {}",
+                        field,
+                        if matches!(*field, "management_fee" | "net_amount") {
+                            "its amounts are read as zero"
+                        } else {
+                            "the file can't be processed without it"
+                        }
+                    ));
+                }
+            }
+            None => {
+                result.add_warning(
+                    "Could not detect a header row by column names; falling back to the fixed Boom layout (row 11)".to_string(),
+                );
+            }
+        }
+
+        result
+    }
+
     fn process_row(&self, _row: &HashMap<String, String>) -> ParserResult<Option<ProcessedData>> {
         // Not used for Boom parser
         Err(ParserError::ProcessingError(
@@ -197,22 +399,22 @@ impl BaseParser for BoomParser {
     
     fn create_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
         // Group by Advance ID and Merchant Name, summing the values
-        let mut grouped_data: HashMap<(String, String), (f64, f64, f64)> = HashMap::new();
-        
+        let mut grouped_data: HashMap<(String, String), (Decimal, Decimal, Decimal)> = HashMap::new();
+
         for row in data {
             let key = (row.advance_id, row.merchant_name);
-            let entry = grouped_data.entry(key).or_insert((0.0, 0.0, 0.0));
+            let entry = grouped_data.entry(key).or_insert((Decimal::ZERO, Decimal::ZERO, Decimal::ZERO));
             entry.0 += row.gross_payment;
             entry.1 += row.fees;
             entry.2 += row.net;
         }
-        
+
         let mut pivot = PivotTable::new();
-        
+
         // Sort by Advance ID
         let mut sorted_entries: Vec<_> = grouped_data.into_iter().collect();
         sorted_entries.sort_by(|a, b| a.0.0.cmp(&b.0.0));
-        
+
         // Add data rows
         for ((advance_id, merchant_name), (gross, fee, net)) in sorted_entries {
             pivot.add_row(
@@ -271,7 +473,7 @@ mod tests {
             assert!(!pivot.rows.is_empty(), "No data rows found in pivot table");
             
             // Should have totals
-            assert!(pivot.total_gross > 0.0, "Total gross amount should be greater than 0");
+            assert!(pivot.total_gross > Decimal::ZERO, "Total gross amount should be greater than 0");
             
             // Last row should be totals
             if let Some(last_row) = pivot.rows.last() {
@@ -1,11 +1,20 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use rayon::prelude::*;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use super::base_parser::*;
 
+/// Per-AdvanceID accumulator: summed gross, summed net, and the first
+/// non-empty merchant name captured (see [`ClearViewDailyParser::capture_merchant_name`]).
+type AdvanceGroup = HashMap<String, (Decimal, Decimal, Option<String>)>;
+
 pub struct ClearViewDailyParser {
     funder_name: String,
     required_columns: Vec<String>,
     file_paths: Vec<std::path::PathBuf>,
+    merchant_column: Option<String>,
+    merchant_map: Option<HashMap<String, String>>,
 }
 
 impl ClearViewDailyParser {
@@ -19,6 +28,8 @@ impl ClearViewDailyParser {
                 "Advance Status".to_string(),
             ],
             file_paths,
+            merchant_column: None,
+            merchant_map: None,
         }
     }
 
@@ -32,51 +43,262 @@ impl ClearViewDailyParser {
                 "Advance Status".to_string(),
             ],
             file_paths: vec![file_path.to_path_buf()],
+            merchant_column: None,
+            merchant_map: None,
         }
     }
-    
-    fn parse_currency(&self, value: &str) -> ParserResult<f64> {
-        let cleaned = value
-            .replace('$', "")
-            .replace(',', "")
-            .replace('(', "-")
-            .replace(')', "")
-            .replace('"', "")
-            .trim()
-            .to_string();
-        
-        if cleaned.is_empty() || cleaned == "0.00" {
-            return Ok(0.0);
-        }
-        
-        cleaned.parse::<f64>().map_err(|e| {
-            ParserError::TypeConversion {
-                column: "currency".to_string(),
-                message: format!("Failed to parse '{}': {}", value, e),
+
+    /// Resolve each row's merchant name from `column` (e.g. "Merchant Name",
+    /// "Business Name") instead of reusing the AdvanceID, capturing the
+    /// first non-empty value seen per AdvanceID during grouping. Falls back
+    /// to the AdvanceID when the column is absent or empty on every row for
+    /// a given advance.
+    pub fn with_merchant_column(mut self, column: impl Into<String>) -> Self {
+        self.merchant_column = Some(column.into());
+        self
+    }
+
+    /// Resolve each row's merchant name by joining AdvanceID against an
+    /// external roster (e.g. loaded from a separate merchant-list file).
+    /// Takes priority over [`Self::with_merchant_column`] when both are set
+    /// and the map has a non-empty entry for a given AdvanceID.
+    pub fn with_merchant_map(mut self, map: HashMap<String, String>) -> Self {
+        self.merchant_map = Some(map);
+        self
+    }
+
+    /// `merchant_map` wins when it has a non-empty entry for `advance_id`;
+    /// otherwise fall back to `captured` (the first non-empty value seen in
+    /// `merchant_column`, if configured), then to the AdvanceID itself.
+    fn resolve_merchant_name(&self, advance_id: &str, captured: Option<&str>) -> String {
+        if let Some(name) = self.merchant_map.as_ref().and_then(|map| map.get(advance_id)) {
+            if !name.is_empty() {
+                return name.clone();
             }
-        })
+        }
+
+        match captured {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => advance_id.to_string(),
+        }
+    }
+
+    /// First non-empty value of `self.merchant_column` on `row`, or `None`
+    /// if the column isn't configured, absent, or empty on this row.
+    fn capture_merchant_name(&self, row: &HashMap<String, String>) -> Option<String> {
+        let column = self.merchant_column.as_ref()?;
+        let value = row.get(column)?.trim();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+
+    /// Delegates to [`BaseParser::currency_to_decimal`] (the single,
+    /// locale-aware currency parser all funders share) rather than
+    /// maintaining its own cleaning logic.
+    fn parse_currency(&self, value: &str) -> ParserResult<Decimal> {
+        if value.trim().is_empty() {
+            return Ok(Decimal::ZERO);
+        }
+
+        self.currency_to_decimal(value)
+    }
+
+    /// `f64` counterpart to [`Self::parse_currency`], for
+    /// [`Self::process_streaming`]'s constant-memory accumulator.
+    fn parse_currency_f64(&self, value: &str) -> ParserResult<f64> {
+        Ok(self.parse_currency(value)?.to_f64().unwrap_or(0.0))
     }
     
     pub fn process(&self) -> ParserResult<PivotTable> {
-        let mut all_data: Vec<HashMap<String, String>> = Vec::new();
-        
-        // Read and combine all files
+        // Validate columns against the first file only (assuming all have
+        // the same structure), same as the old single-pass read did, before
+        // fanning the rest of the work out across threads below.
+        if let Some(first_file) = self.file_paths.first() {
+            if let Some(first_row) = read_csv_file_with_layout(first_file, &self.csv_layout())?.first() {
+                let headers: Vec<String> = first_row.keys().cloned().collect();
+                self.validate_columns(&headers)?;
+            }
+        }
+
+        let grouped_data = self.group_by_advance_id()?;
+
+        // Create pivot table
+        let mut pivot = PivotTable::new();
+
+        for (advance_id, (gross, net, captured)) in sorted_by_advance_id(grouped_data) {
+            let fee = (gross - net).abs();
+            let merchant_name = self.resolve_merchant_name(&advance_id, captured.as_deref());
+            pivot.add_row(advance_id, merchant_name, gross, fee, net);
+        }
+
+        // Add totals row
+        pivot.add_totals_row();
+
+        Ok(pivot)
+    }
+
+    /// Record-streaming counterpart to [`Self::process`]: iterates
+    /// `csv::StringRecord`s directly instead of collecting every row into a
+    /// `Vec<HashMap<String, String>>` first, resolving the AdvanceID/amount
+    /// columns to fixed indices once per file rather than hashing a header
+    /// string per row. No per-row maps are built, but each file's decoded
+    /// text is still read into memory whole (via `decode_csv_layout`) before
+    /// its records are iterated, so peak memory is one file's text plus one
+    /// accumulator entry per distinct AdvanceID, not a constant.
+    ///
+    /// Single-threaded by design (unlike [`Self::process`]'s rayon fan-out):
+    /// the point of this path is a small, predictable memory footprint, and
+    /// parallel chunking would mean holding multiple files' worth of rows in
+    /// flight at once.
+    pub fn process_streaming(&self) -> ParserResult<PivotTable> {
+        let layout = self.csv_layout();
+        let mut grouped_data: HashMap<String, (f64, f64, Option<String>)> = HashMap::new();
+
         for file_path in &self.file_paths {
-            let file_data = read_csv_file(file_path)?;
-            all_data.extend(file_data);
-        }
-        
-        // Validate columns from first file (assuming all have same structure)
-        if !all_data.is_empty() {
-            let first_row = &all_data[0];
-            let headers: Vec<String> = first_row.keys().cloned().collect();
-            self.validate_columns(&headers)?;
-        }
-        
-        // Group by AdvanceID and sum amounts
-        let mut grouped_data: HashMap<String, (f64, f64)> = HashMap::new();
-        
-        for row in all_data {
+            self.stream_file_into(file_path, &layout, &mut grouped_data)?;
+        }
+
+        Ok(self.pivot_from_f64_grouped(grouped_data))
+    }
+
+    /// Stream one file's rows into `grouped_data`, resolving the
+    /// AdvanceID/Syn Gross Amount/Syn Net Amount columns to indices from
+    /// this file's own header rather than assuming a fixed layout.
+    fn stream_file_into(
+        &self,
+        file_path: &Path,
+        layout: &CsvLayout,
+        grouped_data: &mut HashMap<String, (f64, f64, Option<String>)>,
+    ) -> ParserResult<()> {
+        let (body, delimiter) = decode_csv_layout(file_path, layout)?;
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(body.as_bytes());
+
+        let headers = reader.headers()?.clone();
+        let advance_id_col = headers.iter().position(|h| h == "AdvanceID");
+        let gross_col = headers.iter().position(|h| h == "Syn Gross Amount");
+        let net_col = headers.iter().position(|h| h == "Syn Net Amount");
+        let merchant_col = self.merchant_column.as_ref()
+            .and_then(|column| headers.iter().position(|h| h == column));
+
+        let (Some(advance_id_col), Some(gross_col), Some(net_col)) = (advance_id_col, gross_col, net_col) else {
+            return Err(ParserError::MissingColumns { columns: self.required_columns.clone() });
+        };
+        let max_col = advance_id_col.max(gross_col).max(net_col);
+
+        let mut record = csv::StringRecord::new();
+        while reader.read_record(&mut record)? {
+            if record.len() <= max_col {
+                continue;
+            }
+
+            if let Some(first_field) = record.get(0) {
+                if layout.summary_row_markers.iter().any(|marker| first_field.contains(marker.as_str())) {
+                    continue;
+                }
+            }
+
+            let advance_id = record.get(advance_id_col).unwrap_or("").trim();
+            if advance_id.is_empty() || advance_id == "0" {
+                continue;
+            }
+
+            let gross = self.parse_currency_f64(record.get(gross_col).unwrap_or("0"))?;
+            let net = self.parse_currency_f64(record.get(net_col).unwrap_or("0"))?;
+
+            if gross == 0.0 && net == 0.0 {
+                continue;
+            }
+
+            let captured = merchant_col
+                .and_then(|col| record.get(col))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string);
+
+            let entry = grouped_data
+                .entry(advance_id.to_string())
+                .or_insert((0.0, 0.0, None));
+            entry.0 += gross;
+            entry.1 += net;
+            if entry.2.is_none() {
+                entry.2 = captured;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Same row shape as [`Self::process`]'s pivot construction (fee is the
+    /// absolute gross/net difference, merchant name resolved via
+    /// [`Self::resolve_merchant_name`]), over the `f64` accumulator
+    /// [`Self::process_streaming`] folds into. Rows are sorted by AdvanceID
+    /// for the same reason [`Self::process_parallel`]'s are: deterministic
+    /// output regardless of `HashMap`'s randomized hasher.
+    fn pivot_from_f64_grouped(&self, grouped_data: HashMap<String, (f64, f64, Option<String>)>) -> PivotTable {
+        let mut pivot = PivotTable::new();
+
+        for (advance_id, (gross, net, captured)) in sorted_by_advance_id(grouped_data) {
+            let gross = Decimal::from_f64(gross).unwrap_or(Decimal::ZERO);
+            let net = Decimal::from_f64(net).unwrap_or(Decimal::ZERO);
+            let fee = (gross - net).abs();
+            let merchant_name = self.resolve_merchant_name(&advance_id, captured.as_deref());
+            pivot.add_row(advance_id, merchant_name, gross, fee, net);
+        }
+
+        pivot.add_totals_row();
+        pivot
+    }
+
+    /// Read every daily file and sum Syn Gross/Net amounts per AdvanceID.
+    /// Files are split into adaptively-sized byte chunks (see
+    /// [`super::adaptive_file_chunks`]) and grouped in parallel via rayon,
+    /// rather than reading the whole week serially, so a week with many
+    /// small files doesn't thrash; fewer files than
+    /// `adaptive_file_chunks`' per-chunk minimum share a single chunk. Each
+    /// chunk's partial totals are then merged into one map.
+    fn group_by_advance_id(&self) -> ParserResult<AdvanceGroup> {
+        let chunks = super::adaptive_file_chunks(&self.file_paths);
+
+        let partials: Vec<ParserResult<AdvanceGroup>> = chunks
+            .par_iter()
+            .map(|chunk| self.group_chunk(chunk))
+            .collect();
+
+        let mut grouped_data = AdvanceGroup::new();
+        for partial in partials {
+            Self::merge_group(&mut grouped_data, partial?);
+        }
+
+        Ok(grouped_data)
+    }
+
+    /// Read and group one chunk of files; the partial totals this returns
+    /// are merged into the full result by [`Self::group_by_advance_id`].
+    fn group_chunk(&self, chunk: &[PathBuf]) -> ParserResult<AdvanceGroup> {
+        let mut grouped_data = AdvanceGroup::new();
+
+        for file_path in chunk {
+            self.group_one_file(file_path, &mut grouped_data)?;
+        }
+
+        Ok(grouped_data)
+    }
+
+    /// Read one file and fold its AdvanceID/Syn Gross/Syn Net totals (plus
+    /// any captured merchant name) into `into`. Shared by [`Self::group_chunk`]
+    /// (several files per rayon task) and [`Self::process_parallel`] (one
+    /// rayon task per file).
+    fn group_one_file(&self, file_path: &Path, into: &mut AdvanceGroup) -> ParserResult<()> {
+        let file_data = read_csv_file_with_layout(file_path, &self.csv_layout())?;
+
+        for row in file_data {
             // Skip rows with empty or invalid AdvanceID
             let advance_id = match row.get("AdvanceID") {
                 Some(id) => {
@@ -88,39 +310,102 @@ impl ClearViewDailyParser {
                 },
                 None => continue,
             };
-            
+
             // Parse amounts
             let syn_gross = self.parse_currency(row.get("Syn Gross Amount").unwrap_or(&"0".to_string()))?;
             let syn_net = self.parse_currency(row.get("Syn Net Amount").unwrap_or(&"0".to_string()))?;
-            
+
             // Skip rows where both amounts are zero
-            if syn_gross == 0.0 && syn_net == 0.0 {
+            if syn_gross.is_zero() && syn_net.is_zero() {
                 continue;
             }
-            
+
+            let captured = self.capture_merchant_name(&row);
+
             // Add to grouped data
-            let entry = grouped_data.entry(advance_id).or_insert((0.0, 0.0));
+            let entry = into
+                .entry(advance_id)
+                .or_insert((Decimal::ZERO, Decimal::ZERO, None));
             entry.0 += syn_gross;
             entry.1 += syn_net;
+            if entry.2.is_none() {
+                entry.2 = captured;
+            }
         }
-        
-        // Create pivot table
+
+        Ok(())
+    }
+
+    /// Fold `partial`'s totals into `into`, summing gross/net and keeping
+    /// the first non-empty captured merchant name seen for each AdvanceID.
+    fn merge_group(into: &mut AdvanceGroup, partial: AdvanceGroup) {
+        for (advance_id, (gross, net, captured)) in partial {
+            let entry = into
+                .entry(advance_id)
+                .or_insert((Decimal::ZERO, Decimal::ZERO, None));
+            entry.0 += gross;
+            entry.1 += net;
+            if entry.2.is_none() {
+                entry.2 = captured;
+            }
+        }
+    }
+
+    /// Per-file counterpart to [`Self::process`]: maps each file to its own
+    /// grouped AdvanceID totals in parallel via rayon (one task per file,
+    /// rather than [`Self::process`]'s byte-sized chunks), so a batch of
+    /// many small daily files fans out as far as it can. A single file's
+    /// parse error no longer aborts the whole batch: every failing file's
+    /// error is collected and surfaced together in one
+    /// [`ParserError::ProcessingError`] once every file has been attempted.
+    /// Rows are sorted by AdvanceID before being added to the pivot so
+    /// totals and row order are stable regardless of thread scheduling.
+    pub fn process_parallel(&self) -> ParserResult<PivotTable> {
+        // Validate columns against the first file only, same as `process`,
+        // before fanning every file out across threads below.
+        if let Some(first_file) = self.file_paths.first() {
+            if let Some(first_row) = read_csv_file_with_layout(first_file, &self.csv_layout())?.first() {
+                let headers: Vec<String> = first_row.keys().cloned().collect();
+                self.validate_columns(&headers)?;
+            }
+        }
+
+        let per_file: Vec<(&PathBuf, ParserResult<AdvanceGroup>)> = self.file_paths
+            .par_iter()
+            .map(|file_path| {
+                let mut grouped = AdvanceGroup::new();
+                let outcome = self.group_one_file(file_path, &mut grouped).map(|_| grouped);
+                (file_path, outcome)
+            })
+            .collect();
+
+        let mut grouped_data = AdvanceGroup::new();
+        let mut errors = Vec::new();
+
+        for (file_path, outcome) in per_file {
+            match outcome {
+                Ok(partial) => Self::merge_group(&mut grouped_data, partial),
+                Err(e) => errors.push(format!("{}: {}", file_path.display(), e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ParserError::ProcessingError(format!(
+                "{} of {} files failed to parse: {}",
+                errors.len(),
+                self.file_paths.len(),
+                errors.join("; ")
+            )));
+        }
+
         let mut pivot = PivotTable::new();
-        
-        for (advance_id, (gross, net)) in grouped_data {
+        for (advance_id, (gross, net, captured)) in sorted_by_advance_id(grouped_data) {
             let fee = (gross - net).abs();
-            pivot.add_row(
-                advance_id.clone(),
-                advance_id, // Using AdvanceID as merchant name for now
-                gross,
-                fee,
-                net,
-            );
-        }
-        
-        // Add totals row
+            let merchant_name = self.resolve_merchant_name(&advance_id, captured.as_deref());
+            pivot.add_row(advance_id, merchant_name, gross, fee, net);
+        }
         pivot.add_totals_row();
-        
+
         Ok(pivot)
     }
 }
@@ -135,28 +420,37 @@ impl BaseParser for ClearViewDailyParser {
     }
     
     fn parse_file(&self, file_path: &Path) -> ParserResult<Vec<HashMap<String, String>>> {
-        read_csv_file(file_path)
+        read_csv_file_with_layout(file_path, &self.csv_layout())
     }
-    
+
     fn validate_columns(&self, headers: &[String]) -> ParserResult<()> {
         let missing: Vec<String> = self.required_columns
             .iter()
             .filter(|col| !headers.contains(col))
             .cloned()
             .collect();
-        
+
         if !missing.is_empty() {
             return Err(ParserError::MissingColumns { columns: missing });
         }
-        
+
         Ok(())
     }
-    
+
+    /// Daily exports end with a trailing "N Deal(s)" summary row, same as the
+    /// weekly report; no banner lines or non-comma delimiter observed so far.
+    fn csv_layout(&self) -> CsvLayout {
+        CsvLayout {
+            summary_row_markers: vec!["Deal(s)".to_string()],
+            ..CsvLayout::default()
+        }
+    }
+
     fn process_row(&self, _row: &HashMap<String, String>) -> ParserResult<Option<ProcessedData>> {
         // This parser uses grouped processing, so we don't process individual rows
         Ok(None)
     }
-    
+
     fn create_pivot_table(&self, _data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
         // This parser creates its own pivot table in the process method
         self.process()
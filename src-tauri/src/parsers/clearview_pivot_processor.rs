@@ -1,17 +1,24 @@
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::str::FromStr;
 use chrono::{NaiveDate, Datelike};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use uuid::Uuid;
 use super::base_parser::*;
 use super::clearview_daily_parser::ClearViewDailyParser;
 use super::clearview_weekly_parser::ClearViewWeeklyParser;
-use crate::database::{Database, FunderPivotTable};
+use crate::database::{CompressionConfig, Database, FunderPivotTable};
 use crate::file_handler::get_excelerate_dir;
 
 #[derive(Debug, Clone)]
 pub struct ClearViewPivotProcessor {
     portfolio_name: String,
     report_date: String,
+    /// Defaults to disabled so existing call sites (and tests) that build a
+    /// processor without a `Database` in scope keep writing pivot CSVs raw;
+    /// production call sites opt in via [`with_compression_config`](Self::with_compression_config).
+    compression_config: CompressionConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -26,9 +33,18 @@ impl ClearViewPivotProcessor {
         ClearViewPivotProcessor {
             portfolio_name,
             report_date,
+            compression_config: CompressionConfig::default(),
         }
     }
-    
+
+    /// Opt this processor's pivot CSV writes into the portfolio's
+    /// [`CompressionConfig`]. Reads are unaffected either way — they always
+    /// detect compression from the file's own magic bytes.
+    pub fn with_compression_config(mut self, config: CompressionConfig) -> Self {
+        self.compression_config = config;
+        self
+    }
+
     /// Process all daily files in the folder and create/update the aggregated pivot table
     pub fn process_all_daily_files(
         &self,
@@ -68,9 +84,13 @@ impl ClearViewPivotProcessor {
             return Err(ParserError::ProcessingError("No daily files provided".to_string()));
         }
         
-        // Create parser with all daily files
+        // Create parser with all daily files. `process_parallel` (rather than
+        // `process`) attempts every file and reports every failing file's
+        // error together (any failure still fails the whole week's
+        // aggregation), and guarantees row order is stable regardless of
+        // thread scheduling.
         let parser = ClearViewDailyParser::new(daily_file_paths);
-        let pivot = parser.process()?;
+        let pivot = parser.process_parallel()?;
         
         // Save pivot table to file
         let pivot_path = self.save_pivot_table(
@@ -141,159 +161,312 @@ impl ClearViewPivotProcessor {
     
     /// Load pivot table from CSV file
     fn load_pivot_from_csv(&self, path: &Path) -> ParserResult<PivotTable> {
-        let csv_content = std::fs::read_to_string(path)
+        let raw_bytes = std::fs::read(path)
             .map_err(|e| ParserError::Io(e))?;
-        
-        let mut reader = csv::Reader::from_reader(csv_content.as_bytes());
+        let bytes = crate::compression::decompress_if_needed(&raw_bytes)
+            .map_err(|e| ParserError::Io(e))?;
+
+        let mut reader = csv::Reader::from_reader(bytes.as_slice());
         let mut pivot = PivotTable::new();
-        
+
         for result in reader.records() {
             let record = result.map_err(|e| ParserError::Csv(e))?;
-            
+
             if record.len() >= 5 {
                 let advance_id = record.get(0).unwrap_or("").to_string();
-                
+
                 // Skip the totals row when loading
                 if advance_id == "Totals" {
                     continue;
                 }
-                
+
                 let merchant_name = record.get(1).unwrap_or("").to_string();
-                let gross = record.get(2).unwrap_or("0")
-                    .parse::<f64>()
-                    .unwrap_or(0.0);
-                let fee = record.get(3).unwrap_or("0")
-                    .parse::<f64>()
-                    .unwrap_or(0.0);
-                let net = record.get(4).unwrap_or("0")
-                    .parse::<f64>()
-                    .unwrap_or(0.0);
-                
+                let gross = Decimal::from_str(record.get(2).unwrap_or("0")).unwrap_or(Decimal::ZERO);
+                let fee = Decimal::from_str(record.get(3).unwrap_or("0")).unwrap_or(Decimal::ZERO);
+                let net = Decimal::from_str(record.get(4).unwrap_or("0")).unwrap_or(Decimal::ZERO);
+
                 pivot.add_row(advance_id, merchant_name, gross, fee, net);
+
+                // Older pivots (or ones with no running totals yet) only have
+                // the original 5 columns; cumulative_gross/cumulative_net are
+                // only present once `build_with_running_totals` has annotated
+                // a row, so treat them as optional trailing columns.
+                if record.len() >= 7 {
+                    if let Some(last_row) = pivot.rows.last_mut() {
+                        last_row.cumulative_gross = record.get(5).and_then(|s| Decimal::from_str(s).ok());
+                        last_row.cumulative_net = record.get(6).and_then(|s| Decimal::from_str(s).ok());
+                    }
+                }
             }
         }
-        
+
+        Ok(pivot)
+    }
+
+    /// Week start (Sunday) of `date_str` as a [`NaiveDate`], for comparing
+    /// report dates across year boundaries — [`Self::get_week_start`] returns
+    /// an `MM/DD/YYYY` string, which sorts correctly within a year but not
+    /// across one.
+    fn week_start_date(date_str: &str) -> ParserResult<NaiveDate> {
+        let week_start_str = Self::get_week_start(date_str).map_err(ParserError::ProcessingError)?;
+        NaiveDate::parse_from_str(&week_start_str, "%m/%d/%Y").map_err(|e| {
+            ParserError::ProcessingError(format!("Failed to parse week start '{}': {}", week_start_str, e))
+        })
+    }
+
+    /// Every previously-saved `pivot_type` pivot for this portfolio whose
+    /// report week is strictly earlier than `self.report_date`'s, oldest
+    /// first. Bounding by week (rather than by exact file date) means a
+    /// same-week file is never double counted against the current pivot,
+    /// which already reflects that week's activity.
+    fn prior_weekly_pivots(&self, pivot_type: &PivotTableType) -> ParserResult<Vec<PivotTable>> {
+        let current_path = self.pivot_target_path(pivot_type)?;
+        let dir = match current_path.parent() {
+            Some(dir) if dir.exists() => dir,
+            _ => return Ok(Vec::new()),
+        };
+
+        let current_week_start = Self::week_start_date(&self.report_date)?;
+
+        let mut dated_files: Vec<(NaiveDate, PathBuf)> = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(ParserError::Io)? {
+            let entry = entry.map_err(ParserError::Io)?;
+            let path = entry.path();
+
+            if path.extension().and_then(|e| e.to_str()) != Some("csv") || path == current_path {
+                continue;
+            }
+
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            let Ok(file_date) = NaiveDate::parse_from_str(stem, "%m-%d-%Y") else { continue };
+
+            let file_report_date = file_date.format("%m/%d/%Y").to_string();
+            let Ok(file_week_start) = Self::week_start_date(&file_report_date) else { continue };
+
+            if file_week_start < current_week_start {
+                dated_files.push((file_date, path));
+            }
+        }
+
+        dated_files.sort_by_key(|(date, _)| *date);
+
+        dated_files
+            .into_iter()
+            .map(|(_, path)| self.load_pivot_from_csv(&path))
+            .collect()
+    }
+
+    /// Annotate `current_pivot`'s rows with lifetime-to-date `cumulative_gross`
+    /// and `cumulative_net`, by summing each advance's gross/net across every
+    /// prior `pivot_type` pivot saved for this portfolio plus the current
+    /// period. Advances with no row in `current_pivot` are not surfaced, even
+    /// if they appear in the history — this reports progress on this period's
+    /// advances, not a full ledger of every advance ever seen.
+    pub fn build_with_running_totals(
+        &self,
+        current_pivot: &PivotTable,
+        pivot_type: &PivotTableType,
+    ) -> ParserResult<PivotTable> {
+        let history = self.prior_weekly_pivots(pivot_type)?;
+
+        let mut prior_totals: HashMap<String, (Decimal, Decimal)> = HashMap::new();
+        for prior in &history {
+            for row in &prior.rows {
+                if row.advance_id == "Totals" {
+                    continue;
+                }
+                let entry = prior_totals.entry(row.advance_id.clone()).or_insert((Decimal::ZERO, Decimal::ZERO));
+                entry.0 += row.sum_of_syn_gross_amount;
+                entry.1 += row.sum_of_syn_net_amount;
+            }
+        }
+
+        let mut pivot = current_pivot.clone();
+        let mut total_cumulative_gross = Decimal::ZERO;
+        let mut total_cumulative_net = Decimal::ZERO;
+
+        for row in pivot.rows.iter_mut() {
+            if row.advance_id == "Totals" {
+                continue;
+            }
+
+            let (prior_gross, prior_net) = prior_totals.get(&row.advance_id).copied().unwrap_or_default();
+            let cumulative_gross = prior_gross + row.sum_of_syn_gross_amount;
+            let cumulative_net = prior_net + row.sum_of_syn_net_amount;
+
+            row.cumulative_gross = Some(cumulative_gross);
+            row.cumulative_net = Some(cumulative_net);
+            total_cumulative_gross += cumulative_gross;
+            total_cumulative_net += cumulative_net;
+        }
+
+        if let Some(totals_row) = pivot.rows.iter_mut().find(|row| row.advance_id == "Totals") {
+            totals_row.cumulative_gross = Some(total_cumulative_gross);
+            totals_row.cumulative_net = Some(total_cumulative_net);
+        }
+
         Ok(pivot)
     }
     
+    /// The group-by/measures shape shared by every daily+weekly combine:
+    /// group by advance ID and merchant name, summing gross/fee/net.
+    fn combined_pivot_spec() -> PivotSpec {
+        PivotSpec {
+            row_keys: vec!["advance_id".to_string(), "merchant_name".to_string()],
+            measures: vec![
+                ("gross".to_string(), AggFn::Sum),
+                ("fee".to_string(), AggFn::Sum),
+                ("net".to_string(), AggFn::Sum),
+            ],
+            time_dimension: None,
+        }
+    }
+
+    /// Turn a `PivotTable`'s rows (excluding its own totals row) into the
+    /// input records `PivotEngine::run` expects.
+    fn pivot_table_to_engine_rows(pivot: &PivotTable) -> Vec<HashMap<ColumnId, PivotFieldValue>> {
+        pivot
+            .rows
+            .iter()
+            .filter(|row| row.advance_id != "Totals")
+            .map(|row| {
+                let mut record = HashMap::new();
+                record.insert("advance_id".to_string(), PivotFieldValue::Text(row.advance_id.clone()));
+                record.insert("merchant_name".to_string(), PivotFieldValue::Text(row.merchant_name.clone()));
+                record.insert("gross".to_string(), PivotFieldValue::Number(row.sum_of_syn_gross_amount));
+                record.insert("fee".to_string(), PivotFieldValue::Number(row.total_servicing_fee));
+                record.insert("net".to_string(), PivotFieldValue::Number(row.sum_of_syn_net_amount));
+                record
+            })
+            .collect()
+    }
+
     /// Combine daily aggregated and weekly report pivot tables
     pub fn create_combined_pivot(
         &self,
         daily_pivot: &PivotTable,
         weekly_pivot: &PivotTable,
     ) -> ParserResult<(PivotTable, String)> {
-        let mut combined_data: HashMap<String, (String, f64, f64, f64)> = HashMap::new();
-        
-        // Add daily pivot data (excluding totals row)
-        for row in &daily_pivot.rows {
-            if row.advance_id != "Totals" {
-                let entry = combined_data
-                    .entry(row.advance_id.clone())
-                    .or_insert((row.merchant_name.clone(), 0.0, 0.0, 0.0));
-                entry.1 += row.sum_of_syn_gross_amount;
-                entry.2 += row.total_servicing_fee;
-                entry.3 += row.sum_of_syn_net_amount;
-            }
-        }
-        
-        // Add weekly pivot data (excluding totals row)
-        for row in &weekly_pivot.rows {
-            if row.advance_id != "Totals" {
-                let entry = combined_data
-                    .entry(row.advance_id.clone())
-                    .or_insert((row.merchant_name.clone(), 0.0, 0.0, 0.0));
-                entry.1 += row.sum_of_syn_gross_amount;
-                entry.2 += row.total_servicing_fee;
-                entry.3 += row.sum_of_syn_net_amount;
-            }
-        }
-        
+        let mut records = Self::pivot_table_to_engine_rows(daily_pivot);
+        records.extend(Self::pivot_table_to_engine_rows(weekly_pivot));
+
+        let result = PivotEngine::run(&Self::combined_pivot_spec(), &records);
+
         // Create combined pivot table
         let mut combined_pivot = PivotTable::new();
-        for (advance_id, (merchant_name, gross, fee, net)) in combined_data {
+        for row in &result.rows {
+            let advance_id = row.row_key[0].clone();
+            let merchant_name = row.row_key[1].clone();
+            let gross = row.measures.get("gross").copied().unwrap_or(Decimal::ZERO);
+            let fee = row.measures.get("fee").copied().unwrap_or(Decimal::ZERO);
+            let net = row.measures.get("net").copied().unwrap_or(Decimal::ZERO);
             combined_pivot.add_row(advance_id, merchant_name, gross, fee, net);
         }
-        
+
         // Add totals row
         combined_pivot.add_totals_row();
-        
+
         // Save pivot table to file
         let pivot_path = self.save_pivot_table(
             &combined_pivot,
             PivotTableType::Combined,
         )?;
-        
+
         Ok((combined_pivot, pivot_path))
     }
     
     /// Save pivot table to file system
-    fn save_pivot_table(
-        &self,
-        pivot: &PivotTable,
-        pivot_type: PivotTableType,
-    ) -> ParserResult<String> {
+    /// Where a pivot of this type/report date lives (or will live) on disk,
+    /// regardless of whether anything has been written there yet. Shared by
+    /// [`Self::save_pivot_table`] and [`Self::write_pivot_staged`] so both the
+    /// direct-write path and the write-ahead-staging path agree on the final
+    /// destination.
+    pub(crate) fn pivot_target_path(&self, pivot_type: &PivotTableType) -> ParserResult<PathBuf> {
         let base_dir = get_excelerate_dir()
             .map_err(|e| ParserError::ProcessingError(e))?;
-        
-        // Determine subdirectory and filename based on pivot type
-        let (sub_dir, filename) = match pivot_type {
-            PivotTableType::DailyAggregated => {
-                // Daily aggregated pivots go to Funder Pivot Tables/Weekly/Clear View/Daily/
-                let dir = "Daily";
-                let name = format!("{}.csv", self.report_date.replace('/', "-"));
-                (dir, name)
-            },
-            PivotTableType::WeeklyReport => {
-                // Weekly report pivots go to Funder Pivot Tables/Weekly/Clear View/Weekly/
-                let dir = "Weekly";
-                let name = format!("{}.csv", self.report_date.replace('/', "-"));
-                (dir, name)
-            },
-            PivotTableType::Combined => {
-                // Combined pivots go to Funder Pivot Tables/Weekly/Clear View/Combined/
-                let dir = "Combined";
-                let name = format!("{}.csv", self.report_date.replace('/', "-"));
-                (dir, name)
-            },
+
+        // Determine subdirectory based on pivot type; every type shares the
+        // same filename convention (the report date).
+        let sub_dir = match pivot_type {
+            PivotTableType::DailyAggregated => "Daily",
+            PivotTableType::WeeklyReport => "Weekly",
+            PivotTableType::Combined => "Combined",
         };
-        
-        let pivot_dir = base_dir
+        let filename = format!("{}.csv", self.report_date.replace('/', "-"));
+
+        Ok(base_dir
             .join(&self.portfolio_name)
             .join("Funder Pivot Tables")
             .join("Weekly")
             .join("Clear View")
-            .join(sub_dir);
-        
-        // Ensure directory exists
-        std::fs::create_dir_all(&pivot_dir)
-            .map_err(|e| ParserError::Io(e))?;
-        
-        let file_path = pivot_dir.join(&filename);
-        
-        // Convert pivot table to CSV and save
+            .join(sub_dir)
+            .join(filename))
+    }
+
+    /// Serialize `pivot` to CSV (compressing it first if this portfolio has
+    /// opted in — see `with_compression_config`) and write it to `path`,
+    /// creating parent directories as needed.
+    fn write_pivot_bytes(&self, pivot: &PivotTable, path: &Path) -> ParserResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ParserError::Io(e))?;
+        }
+
         let csv_content = pivot.to_csv_string()?;
-        std::fs::write(&file_path, csv_content)
-            .map_err(|e| ParserError::Io(e))?;
-        
+        let bytes = if self.compression_config.enabled {
+            crate::compression::compress(csv_content.as_bytes(), self.compression_config.level)
+                .map_err(|e| ParserError::Io(e))?
+        } else {
+            csv_content.into_bytes()
+        };
+        std::fs::write(path, bytes).map_err(|e| ParserError::Io(e))
+    }
+
+    fn save_pivot_table(
+        &self,
+        pivot: &PivotTable,
+        pivot_type: PivotTableType,
+    ) -> ParserResult<String> {
+        let file_path = self.pivot_target_path(&pivot_type)?;
+        self.write_pivot_bytes(pivot, &file_path)?;
         Ok(file_path.to_string_lossy().to_string())
     }
-    
-    /// Store pivot table metadata in database
-    pub fn store_pivot_metadata(
+
+    /// Write `pivot` to a temp path next to its eventual destination (same
+    /// directory, so the later rename is same-filesystem and therefore
+    /// atomic), without touching the real destination yet. Returns
+    /// `(temp_path, final_path)` for the caller to stage a
+    /// [`crate::database::PendingPivotSwap`] intent record around before
+    /// renaming temp into place — see `file_handler::delete_clearview_file`.
+    pub fn write_pivot_staged(
+        &self,
+        pivot: &PivotTable,
+        pivot_type: PivotTableType,
+    ) -> ParserResult<(PathBuf, PathBuf)> {
+        let final_path = self.pivot_target_path(&pivot_type)?;
+        let temp_path = final_path.with_extension(format!("csv.tmp-{}", Uuid::new_v4()));
+        self.write_pivot_bytes(pivot, &temp_path)?;
+        Ok((temp_path, final_path))
+    }
+
+    /// Build the [`FunderPivotTable`] row `pivot` would be stored as, without
+    /// touching the database — the write-ahead staging flow needs this
+    /// serialized into a [`crate::database::PendingPivotSwap`] intent record
+    /// before the swap is committed. [`Self::store_pivot_metadata`] is a thin
+    /// wrapper over this for the direct (non-staged) path.
+    pub fn build_pivot_metadata(
         &self,
-        db: &Database,
         upload_id: &str,
         pivot_path: &str,
         pivot: &PivotTable,
-        pivot_type: PivotTableType,
-    ) -> Result<(), String> {
+        pivot_type: &PivotTableType,
+    ) -> FunderPivotTable {
         let upload_type = match pivot_type {
             PivotTableType::DailyAggregated => "daily_aggregated",
             PivotTableType::WeeklyReport => "weekly",
             PivotTableType::Combined => "combined",
         };
-        
-        let pivot_metadata = FunderPivotTable {
+
+        FunderPivotTable {
             id: Uuid::new_v4().to_string(),
             upload_id: upload_id.to_string(),
             portfolio_name: self.portfolio_name.clone(),
@@ -301,16 +474,62 @@ impl ClearViewPivotProcessor {
             report_date: self.report_date.clone(),
             upload_type: upload_type.to_string(),
             pivot_file_path: pivot_path.to_string(),
-            total_gross: pivot.total_gross,
-            total_fee: pivot.total_fee,
-            total_net: pivot.total_net,
+            // FunderPivotTable is a SQLite-backed record; store display-precision f64
+            // since the DB schema (and its downstream consumers) predates Decimal.
+            total_gross: pivot.total_gross.to_f64().unwrap_or(0.0),
+            total_fee: pivot.total_fee.to_f64().unwrap_or(0.0),
+            total_net: pivot.total_net.to_f64().unwrap_or(0.0),
             row_count: (pivot.rows.len() - 1) as i32, // Exclude totals row
             created_timestamp: chrono::Utc::now(),
-        };
-        
+        }
+    }
+    
+    /// Feed this processor's weekly net collections into each advance's FIFO
+    /// ledger as `Collection` events, dated to `report_date`, so outstanding
+    /// balance and realized servicing gain stay current as weekly pivots
+    /// land. Skips the pivot's own totals row.
+    pub fn record_weekly_collections(&self, db: &Database, pivot: &PivotTable) -> Result<(), String> {
+        let event_date = NaiveDate::parse_from_str(&self.report_date, "%Y-%m-%d")
+            .or_else(|_| NaiveDate::parse_from_str(&self.report_date, "%m/%d/%Y"))
+            .or_else(|_| NaiveDate::parse_from_str(&self.report_date.replace('-', "/"), "%m/%d/%Y"))
+            .map_err(|e| format!("Could not parse report date {}: {}", self.report_date, e))?;
+
+        for row in &pivot.rows {
+            if row.advance_id == "Totals" {
+                continue;
+            }
+
+            let event = crate::database::AdvanceLedgerEvent {
+                id: Uuid::new_v4().to_string(),
+                advance_id: row.advance_id.clone(),
+                portfolio_name: self.portfolio_name.clone(),
+                event_date,
+                event_type: crate::database::LedgerEventType::Collection,
+                amount: row.sum_of_syn_net_amount,
+                buy_rate: None,
+            };
+
+            db.insert_ledger_event(&event)
+                .map_err(|e| format!("Failed to record ledger event for {}: {}", row.advance_id, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Store pivot table metadata in database
+    pub fn store_pivot_metadata(
+        &self,
+        db: &Database,
+        upload_id: &str,
+        pivot_path: &str,
+        pivot: &PivotTable,
+        pivot_type: PivotTableType,
+    ) -> Result<(), String> {
+        let pivot_metadata = self.build_pivot_metadata(upload_id, pivot_path, pivot, &pivot_type);
+
         db.insert_funder_pivot_table(&pivot_metadata)
             .map_err(|e| format!("Failed to store pivot metadata: {}", e))?;
-        
+
         Ok(())
     }
     
@@ -1,11 +1,14 @@
 use std::path::Path;
 use std::collections::HashMap;
+use rust_decimal::Decimal;
 use super::base_parser::*;
 
 pub struct ClearViewWeeklyParser {
     funder_name: String,
     required_columns: Vec<String>,
     file_path: std::path::PathBuf,
+    merchant_column: Option<String>,
+    merchant_map: Option<HashMap<String, String>>,
 }
 
 impl ClearViewWeeklyParser {
@@ -19,33 +22,71 @@ impl ClearViewWeeklyParser {
                 "Net Payment Amount".to_string(),
             ],
             file_path: file_path.to_path_buf(),
+            merchant_column: None,
+            merchant_map: None,
         }
     }
-    
-    fn parse_currency(&self, value: &str) -> ParserResult<f64> {
-        let cleaned = value
-            .replace('$', "")
-            .replace(',', "")
-            .replace('(', "-")
-            .replace(')', "")
-            .replace('"', "")
-            .trim()
-            .to_string();
-        
-        if cleaned.is_empty() {
-            return Ok(0.0);
-        }
-        
-        cleaned.parse::<f64>().map_err(|e| {
-            ParserError::TypeConversion {
-                column: "currency".to_string(),
-                message: format!("Failed to parse '{}': {}", value, e),
+
+    /// Resolve each row's merchant name from `column` (e.g. "Merchant Name",
+    /// "Business Name") instead of reusing the Deal Id, capturing the first
+    /// non-empty value seen per Deal Id during grouping. Falls back to the
+    /// Deal Id when the column is absent or empty on every row for a given
+    /// deal.
+    pub fn with_merchant_column(mut self, column: impl Into<String>) -> Self {
+        self.merchant_column = Some(column.into());
+        self
+    }
+
+    /// Resolve each row's merchant name by joining Deal Id against an
+    /// external roster (e.g. loaded from a separate merchant-list file).
+    /// Takes priority over [`Self::with_merchant_column`] when both are set
+    /// and the map has a non-empty entry for a given Deal Id.
+    pub fn with_merchant_map(mut self, map: HashMap<String, String>) -> Self {
+        self.merchant_map = Some(map);
+        self
+    }
+
+    /// `merchant_map` wins when it has a non-empty entry for `deal_id`;
+    /// otherwise fall back to `captured` (the first non-empty value seen in
+    /// `merchant_column`, if configured), then to the Deal Id itself.
+    fn resolve_merchant_name(&self, deal_id: &str, captured: Option<&str>) -> String {
+        if let Some(name) = self.merchant_map.as_ref().and_then(|map| map.get(deal_id)) {
+            if !name.is_empty() {
+                return name.clone();
             }
-        })
+        }
+
+        match captured {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => deal_id.to_string(),
+        }
     }
-    
+
+    /// First non-empty value of `self.merchant_column` on `row`, or `None`
+    /// if the column isn't configured, absent, or empty on this row.
+    fn capture_merchant_name(&self, row: &HashMap<String, String>) -> Option<String> {
+        let column = self.merchant_column.as_ref()?;
+        let value = row.get(column)?.trim();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+
+    /// Delegates to [`BaseParser::currency_to_decimal`] (the single,
+    /// locale-aware currency parser all funders share) rather than
+    /// maintaining its own cleaning logic.
+    fn parse_currency(&self, value: &str) -> ParserResult<Decimal> {
+        if value.trim().is_empty() {
+            return Ok(Decimal::ZERO);
+        }
+
+        self.currency_to_decimal(value)
+    }
+
     pub fn process(&self) -> ParserResult<PivotTable> {
-        let data = read_csv_file(&self.file_path)?;
+        let data = read_csv_file_with_layout(&self.file_path, &self.csv_layout())?;
         
         // Validate columns
         if !data.is_empty() {
@@ -55,8 +96,8 @@ impl ClearViewWeeklyParser {
         }
         
         // Group by Deal ID and sum amounts
-        let mut grouped_data: HashMap<String, (f64, f64, f64)> = HashMap::new();
-        
+        let mut grouped_data: HashMap<String, (Decimal, Decimal, Decimal, Option<String>)> = HashMap::new();
+
         for row in data {
             // Skip rows with empty Deal Id
             let deal_id = match row.get("Deal Id") {
@@ -69,35 +110,37 @@ impl ClearViewWeeklyParser {
                 },
                 None => continue,
             };
-            
+
             // Parse amounts
             let gross = self.parse_currency(row.get("Participator Gross Amount").unwrap_or(&"0".to_string()))?;
             let fee = self.parse_currency(row.get("Fee").unwrap_or(&"0".to_string()))?;
             let net = self.parse_currency(row.get("Net Payment Amount").unwrap_or(&"0".to_string()))?;
-            
+
             // Skip rows where all amounts are zero
-            if gross == 0.0 && fee == 0.0 && net == 0.0 {
+            if gross.is_zero() && fee.is_zero() && net.is_zero() {
                 continue;
             }
-            
+
+            let captured = self.capture_merchant_name(&row);
+
             // Add to grouped data
-            let entry = grouped_data.entry(deal_id).or_insert((0.0, 0.0, 0.0));
+            let entry = grouped_data
+                .entry(deal_id)
+                .or_insert((Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, None));
             entry.0 += gross;
             entry.1 += fee;
             entry.2 += net;
+            if entry.3.is_none() {
+                entry.3 = captured;
+            }
         }
-        
+
         // Create pivot table
         let mut pivot = PivotTable::new();
-        
-        for (deal_id, (gross, fee, net)) in grouped_data {
-            pivot.add_row(
-                deal_id.clone(),
-                deal_id, // Using Deal ID as merchant name
-                gross,
-                fee,
-                net,
-            );
+
+        for (deal_id, (gross, fee, net, captured)) in sorted_by_advance_id(grouped_data) {
+            let merchant_name = self.resolve_merchant_name(&deal_id, captured.as_deref());
+            pivot.add_row(deal_id, merchant_name, gross, fee, net);
         }
         
         // Add totals row
@@ -117,23 +160,31 @@ impl BaseParser for ClearViewWeeklyParser {
     }
     
     fn parse_file(&self, file_path: &Path) -> ParserResult<Vec<HashMap<String, String>>> {
-        read_csv_file(file_path)
+        read_csv_file_with_layout(file_path, &self.csv_layout())
     }
-    
+
     fn validate_columns(&self, headers: &[String]) -> ParserResult<()> {
         let missing: Vec<String> = self.required_columns
             .iter()
             .filter(|col| !headers.contains(col))
             .cloned()
             .collect();
-        
+
         if !missing.is_empty() {
             return Err(ParserError::MissingColumns { columns: missing });
         }
-        
+
         Ok(())
     }
-    
+
+    /// Weekly reports end with a trailing "N Deal(s)" summary row.
+    fn csv_layout(&self) -> CsvLayout {
+        CsvLayout {
+            summary_row_markers: vec!["Deal(s)".to_string()],
+            ..CsvLayout::default()
+        }
+    }
+
     fn process_row(&self, _row: &HashMap<String, String>) -> ParserResult<Option<ProcessedData>> {
         // This parser uses grouped processing, so we don't process individual rows
         Ok(None)
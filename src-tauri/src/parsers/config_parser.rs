@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer};
+use crate::parsers::base_parser::{
+    apply_reversal_ledger, sorted_by_advance_id, BaseParser, CsvOptions, Encoding, NumberLocale, ParserError,
+    ParserResult, PivotTable, ProcessedData,
+};
+
+/// Maps the canonical fields [`BaseParser::process_row`] needs to the column
+/// names one funder's export actually uses, so a funder with a standard
+/// gross/fee/net layout can be onboarded by dropping in a `.toml` file
+/// instead of writing a new `BaseParser` impl.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnMapping {
+    pub advance_id: String,
+    pub merchant_name: String,
+    pub gross_payment: String,
+    pub fees: String,
+    pub net: String,
+    /// Source column holding a per-row status (e.g. "Reversed"). Left unset,
+    /// every row's `status` comes through empty and `reversal_statuses` can
+    /// never match.
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// CSV dialect and character encoding for one funder definition; mirrors
+/// [`CsvOptions`]/[`Encoding`]/[`NumberLocale`] rather than introducing a
+/// parallel representation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DialectConfig {
+    pub delimiter: char,
+    pub skip_rows: usize,
+    pub encoding: Encoding,
+    pub number_locale: NumberLocale,
+}
+
+impl Default for DialectConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            skip_rows: 0,
+            encoding: Encoding::Auto,
+            number_locale: NumberLocale::UsEnglish,
+        }
+    }
+}
+
+/// Parses a `YYYY-MM-DD` TOML string into a `NaiveDate`; `toml`'s own
+/// `Deserialize` only understands its native `Datetime` type, not chrono's,
+/// so a funder definition that wants one needs this custom deserializer.
+fn deserialize_optional_naive_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    match raw {
+        Some(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// A funder definition loaded from a `.toml` file: the funder name, which
+/// columns are required, how the canonical fields map onto this funder's
+/// column names, and the CSV dialect/encoding its exports use.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FunderDefinition {
+    pub funder_name: String,
+    pub required_columns: Vec<String>,
+    pub columns: ColumnMapping,
+    #[serde(default)]
+    pub dialect: DialectConfig,
+    #[serde(default)]
+    pub reversal_statuses: Vec<String>,
+    /// Date this definition went into effect. Informational only today —
+    /// reserved for a future check that a file's report date falls within
+    /// the definitions that are actually current.
+    #[serde(default, deserialize_with = "deserialize_optional_naive_date")]
+    pub effective_date: Option<NaiveDate>,
+}
+
+/// Generic [`BaseParser`] implementation driven entirely by a
+/// [`FunderDefinition`] instead of a hand-written struct: `ProcessedData` is
+/// built by looking up each mapped column name in a row and running the same
+/// `currency_to_decimal`/ledger logic every hardcoded parser uses. Meant for
+/// funders whose export is a plain gross/fee/net table with no funder-specific
+/// quirks — `EfinParser`/`BhbParser`/etc. remain the escape hatch for the
+/// funders that need one.
+pub struct ConfigParser {
+    definition: FunderDefinition,
+}
+
+impl ConfigParser {
+    /// Load a funder definition from a `.toml` file on disk.
+    pub fn from_file(path: &Path) -> ParserResult<Self> {
+        let raw = fs::read_to_string(path)?;
+        let definition: FunderDefinition = toml::from_str(&raw).map_err(|e| {
+            ParserError::ProcessingError(format!("Invalid funder definition {:?}: {}", path, e))
+        })?;
+        Self::from_definition(definition)
+    }
+
+    /// Build directly from an already-parsed [`FunderDefinition`], for
+    /// callers that load the TOML themselves (e.g. to validate it before
+    /// use). Rejects a definition whose `dialect.delimiter` can't be a CSV
+    /// delimiter byte, or whose `reversal_statuses` can never match because
+    /// `columns.status` is unset.
+    pub fn from_definition(definition: FunderDefinition) -> ParserResult<Self> {
+        if !definition.dialect.delimiter.is_ascii() {
+            return Err(ParserError::ProcessingError(format!(
+                "Funder definition '{}' has a non-ASCII dialect.delimiter {:?}; a CSV delimiter must be a single ASCII character",
+                definition.funder_name, definition.dialect.delimiter
+            )));
+        }
+
+        if !definition.reversal_statuses.is_empty() && definition.columns.status.is_none() {
+            return Err(ParserError::ProcessingError(format!(
+                "Funder definition '{}' declares reversal_statuses but columns.status is unset; reversal rows would never match",
+                definition.funder_name
+            )));
+        }
+
+        Ok(Self { definition })
+    }
+
+    /// The source column names `process_row` actually looks up via
+    /// `columns`, so `get_required_columns`/`validate_columns` catch a
+    /// `columns` mapping that points at a header missing from the TOML's own
+    /// `required_columns` list instead of silently defaulting that field to
+    /// zero for every row.
+    fn mapped_column_names(&self) -> Vec<String> {
+        let columns = &self.definition.columns;
+        let mut names = vec![
+            columns.advance_id.clone(),
+            columns.merchant_name.clone(),
+            columns.gross_payment.clone(),
+            columns.fees.clone(),
+            columns.net.clone(),
+        ];
+        if let Some(status) = &columns.status {
+            names.push(status.clone());
+        }
+        names
+    }
+}
+
+impl BaseParser for ConfigParser {
+    fn get_funder_name(&self) -> &str {
+        &self.definition.funder_name
+    }
+
+    fn get_required_columns(&self) -> Vec<String> {
+        let mut columns = self.mapped_column_names();
+        for extra in &self.definition.required_columns {
+            if !columns.contains(extra) {
+                columns.push(extra.clone());
+            }
+        }
+        columns
+    }
+
+    fn parse_file(&self, file_path: &Path) -> ParserResult<Vec<HashMap<String, String>>> {
+        self.parse_csv_with_options(file_path)
+    }
+
+    fn csv_options(&self) -> CsvOptions {
+        CsvOptions {
+            // `from_definition` already rejected a non-ASCII delimiter, so
+            // this narrowing is lossless.
+            delimiter: self.definition.dialect.delimiter as u8,
+            skip_rows: self.definition.dialect.skip_rows,
+            flexible: true,
+            encoding: self.definition.dialect.encoding,
+        }
+    }
+
+    fn encoding(&self) -> Encoding {
+        self.definition.dialect.encoding
+    }
+
+    fn number_locale(&self) -> NumberLocale {
+        self.definition.dialect.number_locale
+    }
+
+    fn reversal_statuses(&self) -> Vec<String> {
+        self.definition.reversal_statuses.clone()
+    }
+
+    fn validate_columns(&self, headers: &[String]) -> ParserResult<()> {
+        let missing: Vec<String> = self
+            .get_required_columns()
+            .into_iter()
+            .filter(|col| !headers.contains(col))
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(ParserError::MissingColumns { columns: missing });
+        }
+
+        Ok(())
+    }
+
+    fn process_row(&self, row: &HashMap<String, String>) -> ParserResult<Option<ProcessedData>> {
+        let columns = &self.definition.columns;
+
+        let advance_id = row
+            .get(&columns.advance_id)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        if advance_id.is_empty() {
+            return Ok(None);
+        }
+
+        let merchant_name = row
+            .get(&columns.merchant_name)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+
+        let gross_payment = row
+            .get(&columns.gross_payment)
+            .map(|v| self.currency_to_decimal(v))
+            .transpose()?
+            .unwrap_or(Decimal::ZERO);
+        let fees = row
+            .get(&columns.fees)
+            .map(|v| self.currency_to_decimal(v))
+            .transpose()?
+            .unwrap_or(Decimal::ZERO);
+        let net = row
+            .get(&columns.net)
+            .map(|v| self.currency_to_decimal(v))
+            .transpose()?
+            .unwrap_or(Decimal::ZERO);
+
+        let status = columns
+            .status
+            .as_ref()
+            .and_then(|col| row.get(col))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+
+        Ok(Some(ProcessedData {
+            advance_id,
+            merchant_name,
+            gross_payment,
+            fees,
+            net,
+            status,
+            ..Default::default()
+        }))
+    }
+
+    fn create_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
+        let ledger = apply_reversal_ledger(data, &self.reversal_statuses());
+
+        let mut pivot = PivotTable::new();
+        for (advance_id, entry) in sorted_by_advance_id(ledger) {
+            pivot.add_row_with_status(
+                advance_id,
+                entry.merchant_name,
+                entry.gross,
+                entry.fees,
+                entry.net,
+                entry.final_status,
+            );
+        }
+        pivot.add_totals_row();
+
+        Ok(pivot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(status_column: Option<&str>, reversal_statuses: Vec<&str>) -> FunderDefinition {
+        FunderDefinition {
+            funder_name: "Test Funder".to_string(),
+            required_columns: vec![],
+            columns: ColumnMapping {
+                advance_id: "Advance ID".to_string(),
+                merchant_name: "Merchant".to_string(),
+                gross_payment: "Gross".to_string(),
+                fees: "Fees".to_string(),
+                net: "Net".to_string(),
+                status: status_column.map(|s| s.to_string()),
+            },
+            dialect: DialectConfig::default(),
+            reversal_statuses: reversal_statuses.into_iter().map(|s| s.to_string()).collect(),
+            effective_date: None,
+        }
+    }
+
+    #[test]
+    fn from_definition_rejects_non_ascii_delimiter() {
+        let mut def = definition(None, vec![]);
+        def.dialect.delimiter = '\u{2764}';
+
+        let result = ConfigParser::from_definition(def);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn from_definition_rejects_reversal_statuses_without_status_column() {
+        let def = definition(None, vec!["Reversed"]);
+
+        let result = ConfigParser::from_definition(def);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn from_definition_accepts_valid_definition() {
+        let def = definition(Some("Status"), vec!["Reversed"]);
+
+        assert!(ConfigParser::from_definition(def).is_ok());
+    }
+
+    #[test]
+    fn process_row_reads_mapped_columns() {
+        let parser = ConfigParser::from_definition(definition(Some("Status"), vec!["Reversed"])).unwrap();
+
+        let mut row = HashMap::new();
+        row.insert("Advance ID".to_string(), "A-1".to_string());
+        row.insert("Merchant".to_string(), "Acme".to_string());
+        row.insert("Gross".to_string(), "$100.00".to_string());
+        row.insert("Fees".to_string(), "$10.00".to_string());
+        row.insert("Net".to_string(), "$90.00".to_string());
+        row.insert("Status".to_string(), "Active".to_string());
+
+        let processed = parser.process_row(&row).unwrap().unwrap();
+        assert_eq!(processed.advance_id, "A-1");
+        assert_eq!(processed.merchant_name, "Acme");
+        assert_eq!(processed.gross_payment, Decimal::new(10000, 2));
+        assert_eq!(processed.fees, Decimal::new(1000, 2));
+        assert_eq!(processed.net, Decimal::new(9000, 2));
+        assert_eq!(processed.status, "Active");
+    }
+
+    #[test]
+    fn process_row_skips_blank_advance_id() {
+        let parser = ConfigParser::from_definition(definition(None, vec![])).unwrap();
+
+        let mut row = HashMap::new();
+        row.insert("Advance ID".to_string(), "".to_string());
+
+        assert!(parser.process_row(&row).unwrap().is_none());
+    }
+
+    #[test]
+    fn create_pivot_table_sorts_rows_by_advance_id() {
+        let parser = ConfigParser::from_definition(definition(None, vec![])).unwrap();
+
+        let data = vec![
+            ProcessedData {
+                advance_id: "B-2".to_string(),
+                merchant_name: "Beta".to_string(),
+                gross_payment: Decimal::new(20000, 2),
+                fees: Decimal::ZERO,
+                net: Decimal::new(20000, 2),
+                ..Default::default()
+            },
+            ProcessedData {
+                advance_id: "A-1".to_string(),
+                merchant_name: "Alpha".to_string(),
+                gross_payment: Decimal::new(10000, 2),
+                fees: Decimal::ZERO,
+                net: Decimal::new(10000, 2),
+                ..Default::default()
+            },
+        ];
+
+        let pivot = parser.create_pivot_table(data).unwrap();
+        let advance_ids: Vec<&str> = pivot.rows.iter().map(|r| r.advance_id.as_str()).collect();
+        assert_eq!(advance_ids, vec!["A-1", "B-2", "Totals"]);
+    }
+}
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 use std::path::Path;
+use rust_decimal::Decimal;
 use crate::parsers::base_parser::{
-    BaseParser, ParserError, ParserResult, ProcessedData, PivotTable,
-    read_csv_file
+    apply_reversal_ledger, sorted_by_advance_id, BaseParser, Encoding, ParserError, ParserResult, ProcessedData,
+    PivotTable, ReconciliationWarning,
 };
 
 pub struct EfinParser;
@@ -32,7 +33,46 @@ impl BaseParser for EfinParser {
     }
 
     fn parse_file(&self, file_path: &Path) -> ParserResult<Vec<HashMap<String, String>>> {
-        read_csv_file(file_path)
+        self.parse_csv_with_options(file_path)
+    }
+
+    /// eFin exports are a bank-style statement feed that arrives in
+    /// Windows-1252, not UTF-8; "Business Name" regularly carries accented
+    /// merchant names that would otherwise come through as mojibake.
+    fn encoding(&self) -> Encoding {
+        Encoding::Windows1252
+    }
+
+    /// eFin marks a reversed or charged-back advance in "Payable Status"
+    /// (falls back to "Advance Status") rather than dropping the row, so a
+    /// naive sum would double-count the original payment and its reversal.
+    fn reversal_statuses(&self) -> Vec<String> {
+        vec![
+            "reversed".to_string(),
+            "reversal".to_string(),
+            "charged back".to_string(),
+            "chargeback".to_string(),
+        ]
+    }
+
+    /// `process_row` always takes `.abs()` of "Servicing Fee $", so a negative
+    /// `total_servicing_fee` here can only mean the reversal ledger netted an
+    /// advance's fee below zero (more reversed fee than fee originally
+    /// charged) — a sign a source file is malformed or a reversal was
+    /// double-counted, worth surfacing even though it can't crash anything.
+    fn reconciliation_checks(&self, pivot: &PivotTable) -> Vec<ReconciliationWarning> {
+        pivot
+            .rows
+            .iter()
+            .filter(|row| row.advance_id != "Totals" && row.total_servicing_fee.is_sign_negative())
+            .map(|row| ReconciliationWarning {
+                row_key: row.advance_id.clone(),
+                check: "efin_servicing_fee_not_negative".to_string(),
+                expected: row.total_servicing_fee.abs(),
+                actual: row.total_servicing_fee,
+                delta: row.total_servicing_fee.abs() * Decimal::TWO,
+            })
+            .collect()
     }
 
     fn validate_columns(&self, headers: &[String]) -> ParserResult<()> {
@@ -69,17 +109,28 @@ impl BaseParser for EfinParser {
 
         // Parse amounts
         let gross_payment = row.get("Payable Amt (Gross)")
-            .and_then(|v| self.currency_to_float(v).ok())
-            .unwrap_or(0.0);
+            .and_then(|v| self.currency_to_decimal(v).ok())
+            .unwrap_or(Decimal::ZERO);
 
         let fees = row.get("Servicing Fee $")
-            .and_then(|v| self.currency_to_float(v).ok())
-            .unwrap_or(0.0)
+            .and_then(|v| self.currency_to_decimal(v).ok())
+            .unwrap_or(Decimal::ZERO)
             .abs(); // Ensure fees are positive
 
         let net = row.get("Payable Amt (Net)")
-            .and_then(|v| self.currency_to_float(v).ok())
-            .unwrap_or(0.0);
+            .and_then(|v| self.currency_to_decimal(v).ok())
+            .unwrap_or(Decimal::ZERO);
+
+        // "Payable Status" is the per-payment transactional state (e.g. a
+        // reversal shows up here); fall back to the advance-level "Advance
+        // Status" when it's blank.
+        let payable_status = row.get("Payable Status").map(|s| s.trim()).unwrap_or("");
+        let advance_status = row.get("Advance Status").map(|s| s.trim()).unwrap_or("");
+        let status = if !payable_status.is_empty() {
+            payable_status.to_string()
+        } else {
+            advance_status.to_string()
+        };
 
         Ok(Some(ProcessedData {
             advance_id,
@@ -87,35 +138,32 @@ impl BaseParser for EfinParser {
             gross_payment,
             fees,
             net,
+            status,
+            ..Default::default()
         }))
     }
 
     fn create_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
         let mut pivot = PivotTable::new();
-        
-        // Group by Advance ID (aggregate multiple rows with same ID)
-        let mut grouped: HashMap<String, (String, f64, f64, f64)> = HashMap::new();
-        
-        for item in data {
-            let entry = grouped.entry(item.advance_id.clone()).or_insert((
-                item.merchant_name.clone(),
-                0.0,
-                0.0,
-                0.0,
-            ));
-            entry.1 += item.gross_payment;
-            entry.2 += item.fees;
-            entry.3 += item.net;
-        }
-        
-        // Add rows to pivot table
-        for (advance_id, (merchant_name, gross, fee, net)) in grouped {
-            pivot.add_row(advance_id, merchant_name, gross, fee, net);
+
+        // Group by Advance ID, netting out reversal/chargeback rows instead
+        // of summing them in as new collections (see `reversal_statuses`).
+        let ledger = apply_reversal_ledger(data, &self.reversal_statuses());
+
+        for (advance_id, entry) in sorted_by_advance_id(ledger) {
+            pivot.add_row_with_status(
+                advance_id,
+                entry.merchant_name,
+                entry.gross,
+                entry.fees,
+                entry.net,
+                entry.final_status,
+            );
         }
-        
+
         // Add totals row
         pivot.add_totals_row();
-        
+
         Ok(pivot)
     }
 }
@@ -138,15 +186,15 @@ mod tests {
                     println!("Total Net: {:.2}", pivot_table.total_net);
                     println!("Number of rows: {}", pivot_table.rows.len());
                     assert!(pivot_table.rows.len() > 0);
-                    
+
                     // Verify totals match what's expected
-                    assert!(pivot_table.total_gross > 0.0);
-                    assert!(pivot_table.total_fee > 0.0);
-                    assert!(pivot_table.total_net > 0.0);
-                    
+                    assert!(pivot_table.total_gross > Decimal::ZERO);
+                    assert!(pivot_table.total_fee > Decimal::ZERO);
+                    assert!(pivot_table.total_net > Decimal::ZERO);
+
                     // Verify the relationship: gross = net + fee (with small tolerance for rounding)
                     let calculated_gross = pivot_table.total_net + pivot_table.total_fee;
-                    assert!((pivot_table.total_gross - calculated_gross).abs() < 0.01);
+                    assert!((pivot_table.total_gross - calculated_gross).abs() < Decimal::new(1, 2));
                 },
                 Err(e) => {
                     panic!("Failed to process eFin file: {:?}", e);
@@ -160,10 +208,10 @@ mod tests {
     #[test]
     fn test_currency_parsing() {
         let parser = EfinParser::new();
-        
-        assert_eq!(parser.currency_to_float("$100.50").unwrap(), 100.50);
-        assert_eq!(parser.currency_to_float("1,234.56").unwrap(), 1234.56);
-        assert_eq!(parser.currency_to_float("(50.00)").unwrap(), -50.00);
-        assert_eq!(parser.currency_to_float("$1,234.56").unwrap(), 1234.56);
+
+        assert_eq!(parser.currency_to_decimal("$100.50").unwrap(), Decimal::new(10050, 2));
+        assert_eq!(parser.currency_to_decimal("1,234.56").unwrap(), Decimal::new(123456, 2));
+        assert_eq!(parser.currency_to_decimal("(50.00)").unwrap(), Decimal::new(-5000, 2));
+        assert_eq!(parser.currency_to_decimal("$1,234.56").unwrap(), Decimal::new(123456, 2));
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::Path;
+use rust_decimal::Decimal;
 use super::base_parser::*;
 
 pub struct InAdvParser {
@@ -57,6 +58,19 @@ impl BaseParser for InAdvParser {
         Ok(())
     }
     
+    fn skip_reason(&self, row: &HashMap<String, String>) -> String {
+        let advance_id = row.get("Advance Id").map(|s| s.as_str()).unwrap_or("");
+        if advance_id.is_empty() || advance_id.parse::<f64>().is_err() {
+            return "empty or non-numeric Advance Id".to_string();
+        }
+
+        match row.get("Status").map(|s| s.as_str()) {
+            Some("Cleared") => "filtered by process_row".to_string(),
+            Some(other) => format!("status '{}' is not 'Cleared'", other),
+            None => "missing Status".to_string(),
+        }
+    }
+
     fn process_row(&self, row: &HashMap<String, String>) -> ParserResult<Option<ProcessedData>> {
         // Get Advance ID and validate it's not empty
         let advance_id = row.get("Advance Id")
@@ -83,17 +97,17 @@ impl BaseParser for InAdvParser {
         // Get Amount (net amount after fees)
         let amount = row.get("Amount")
             .ok_or_else(|| ParserError::ProcessingError("Missing Amount".to_string()))?;
-        let net_amount = self.currency_to_float(amount)?;
-        
+        let net_amount = self.currency_to_decimal(amount)?;
+
         // Get Gross Amount
         let gross_amount = row.get("Gross Amount")
             .ok_or_else(|| ParserError::ProcessingError("Missing Gross Amount".to_string()))?;
-        let gross_amount = self.currency_to_float(gross_amount)?;
-        
+        let gross_amount = self.currency_to_decimal(gross_amount)?;
+
         // Get Mgmt Fee (the fee amount)
         let mgmt_fee = row.get("Mgmt Fee")
             .ok_or_else(|| ParserError::ProcessingError("Missing Mgmt Fee".to_string()))?;
-        let fee = self.currency_to_float(mgmt_fee)?.abs(); // Use absolute value of fee
+        let fee = self.currency_to_decimal(mgmt_fee)?.abs(); // Use absolute value of fee
         
         Ok(Some(ProcessedData {
             advance_id: advance_id.clone(),
@@ -101,36 +115,32 @@ impl BaseParser for InAdvParser {
             gross_payment: gross_amount,
             fees: fee,
             net: net_amount,
+            ..Default::default()
         }))
     }
     
     fn create_pivot_table(&self, data: Vec<ProcessedData>) -> ParserResult<PivotTable> {
         // Group by Advance ID and Merchant Name (Contact ID), summing the values
-        let mut grouped_data: HashMap<(String, String), (f64, f64, f64)> = HashMap::new();
-        
+        let mut grouped_data: HashMap<(String, String), (Decimal, Decimal, Decimal)> = HashMap::new();
+
         for row in data {
             let key = (row.advance_id, row.merchant_name);
-            let entry = grouped_data.entry(key).or_insert((0.0, 0.0, 0.0));
+            let entry = grouped_data.entry(key).or_insert((Decimal::ZERO, Decimal::ZERO, Decimal::ZERO));
             entry.0 += row.gross_payment;
             entry.1 += row.fees;
             entry.2 += row.net;
         }
-        
+
         let mut pivot = PivotTable::new();
-        
+
         // Sort by Advance ID
         let mut sorted_entries: Vec<_> = grouped_data.into_iter().collect();
         sorted_entries.sort_by(|a, b| a.0.0.cmp(&b.0.0));
-        
-        // Add data rows
+
+        // Add data rows. Values are exact sums of Decimal currency amounts;
+        // rounding only happens at display time (PivotTable::to_csv_string).
         for ((advance_id, merchant_name), (gross, fee, net)) in sorted_entries {
-            pivot.add_row(
-                advance_id,
-                merchant_name,
-                (gross * 100.0).round() / 100.0, // Round to 2 decimal places
-                (fee * 100.0).round() / 100.0,
-                (net * 100.0).round() / 100.0,
-            );
+            pivot.add_row(advance_id, merchant_name, gross, fee, net);
         }
         
         // Add totals row
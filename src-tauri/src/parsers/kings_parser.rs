@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::path::Path;
+use rust_decimal::Decimal;
 use super::base_parser::{
-    BaseParser, ParserError, ParserResult, PivotTable, ProcessedData, read_csv_file
+    BaseParser, ParserError, ParserResult, PivotTable, ProcessedData
 };
 
 pub struct KingsParser;
@@ -28,7 +29,10 @@ impl BaseParser for KingsParser {
     }
     
     fn parse_file(&self, file_path: &Path) -> ParserResult<Vec<HashMap<String, String>>> {
-        read_csv_file(file_path)
+        // Routes through BaseParser::csv_options so a Kings export with a
+        // non-default delimiter or a banner preamble can be supported by
+        // overriding csv_options() instead of a bespoke reader.
+        self.parse_csv_with_options(file_path)
     }
     
     fn validate_columns(&self, headers: &[String]) -> ParserResult<()> {
@@ -60,12 +64,12 @@ impl BaseParser for KingsParser {
         }
         
         // Parse amounts
-        let gross_payment = self.currency_to_float(gross_str)?;
-        let fees = self.currency_to_float(fees_str)?;
-        let net = self.currency_to_float(net_str)?;
-        
+        let gross_payment = self.currency_to_decimal(gross_str)?;
+        let fees = self.currency_to_decimal(fees_str)?;
+        let net = self.currency_to_decimal(net_str)?;
+
         // Skip rows with zero amounts
-        if gross_payment == 0.0 && fees == 0.0 && net == 0.0 {
+        if gross_payment.is_zero() && fees.is_zero() && net.is_zero() {
             return Ok(None);
         }
         
@@ -75,6 +79,7 @@ impl BaseParser for KingsParser {
             gross_payment,
             fees,
             net,
+            ..Default::default()
         }))
     }
     
@@ -82,14 +87,14 @@ impl BaseParser for KingsParser {
         let mut pivot = PivotTable::new();
         
         // Group by advance_id and aggregate
-        let mut grouped: HashMap<String, (String, f64, f64, f64)> = HashMap::new();
-        
+        let mut grouped: HashMap<String, (String, Decimal, Decimal, Decimal)> = HashMap::new();
+
         for item in data {
             let entry = grouped.entry(item.advance_id.clone()).or_insert((
                 item.merchant_name.clone(),
-                0.0,
-                0.0,
-                0.0,
+                Decimal::ZERO,
+                Decimal::ZERO,
+                Decimal::ZERO,
             ));
             entry.1 += item.gross_payment;
             entry.2 += item.fees;
@@ -116,6 +121,7 @@ mod tests {
     use super::*;
     use std::fs;
     use std::path::PathBuf;
+    use std::str::FromStr;
     
     #[test]
     fn test_kings_parser() {
@@ -139,10 +145,10 @@ mod tests {
         
         // Should have 3 unique advances + totals row
         assert_eq!(pivot.rows.len(), 4);
-        
+
         // Check totals
-        assert_eq!(pivot.total_gross, 153.49);
-        assert_eq!(pivot.total_fee, 4.60);
-        assert_eq!(pivot.total_net, 148.89);
+        assert_eq!(pivot.total_gross, Decimal::from_str("153.49").unwrap());
+        assert_eq!(pivot.total_fee, Decimal::from_str("4.60").unwrap());
+        assert_eq!(pivot.total_net, Decimal::from_str("148.89").unwrap());
     }
 }
\ No newline at end of file
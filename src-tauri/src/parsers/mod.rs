@@ -1,3 +1,7 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+
 pub mod base_parser;
 pub mod bhb_parser;
 pub mod big_parser;
@@ -5,10 +9,12 @@ pub mod boom_parser;
 pub mod clearview_daily_parser;
 pub mod clearview_weekly_parser;
 pub mod clearview_pivot_processor;
+pub mod config_parser;
 pub mod efin_parser;
 pub mod inadv_parser;
 pub mod kings_parser;
 pub mod portfolio_parser;
+pub mod reconciliation;
 
 #[cfg(test)]
 mod test_clearview;
@@ -22,14 +28,350 @@ mod test_combined_pivot;
 #[cfg(test)]
 mod test_inadv;
 
-pub use base_parser::{BaseParser, PivotTable};
-pub use bhb_parser::BhbParser;
+pub use base_parser::{
+    auto_detect_csv_options, normalize_currency_separators, AggFn, BaseParser, ColumnId, CsvLayout,
+    CsvOptions, Encoding, NumberLocale, ParserError, ParserResult, PivotBuilder, PivotEngine,
+    PivotEngineResult, PivotEngineRow, PivotFieldValue, PivotSpec, PivotTable, ProcessSummary,
+    ReconciliationWarning, TextEncoding, TimeBucket, TransposedPivotTable,
+};
+pub use bhb_parser::{BhbBreakdownRow, BhbParser, BhbPivotTable};
 pub use big_parser::BigParser;
 pub use boom_parser::BoomParser;
 pub use clearview_daily_parser::ClearViewDailyParser;
 pub use clearview_weekly_parser::ClearViewWeeklyParser;
 pub use clearview_pivot_processor::ClearViewPivotProcessor;
+pub use config_parser::{ColumnMapping, ConfigParser, DialectConfig, FunderDefinition};
 pub use efin_parser::EfinParser;
 pub use inadv_parser::InAdvParser;
 pub use kings_parser::KingsParser;
-pub use portfolio_parser::PortfolioParser;
+pub use portfolio_parser::{
+    parse_spreadsheet_date, DateSystem, ExtractionWarning, FunderSheetParser,
+    LearnedColumnMapping, PortfolioExtractionResult, PortfolioParser, StatementExtraction,
+    StatementParser,
+};
+pub use reconciliation::{reconcile, ReconciliationReport};
+
+/// Parsers whose signature lives in a literal header row and can be scored by
+/// `get_required_columns`/`validate_columns` — the header-sniffing tier of
+/// [`detect_parser`]'s (and [`detect_funder`]'s) detection.
+fn header_detectable_parsers() -> Vec<Box<dyn BaseParser>> {
+    vec![
+        Box::new(BhbParser::new()),
+        Box::new(EfinParser::new()),
+        Box::new(InAdvParser::new()),
+        Box::new(KingsParser::new()),
+    ]
+}
+
+/// Parsers with no header row to sniff — `BigParser`/`BoomParser` key off a
+/// fixed workbook layout instead (sheet names, fixed-position header row),
+/// so their [`BaseParser::validate_columns`] can't meaningfully reject an
+/// unrelated file the way a header-based funder's can (`BigParser`'s in
+/// particular always returns `Ok(())`). [`detect_parser`] only consults
+/// these once no [`header_detectable_parsers`] candidate has matched, via
+/// [`BaseParser::detection_score`] rather than `validate_columns`.
+fn positional_detectable_parsers() -> Vec<Box<dyn BaseParser>> {
+    vec![Box::new(BigParser::new()), Box::new(BoomParser::new())]
+}
+
+/// Inspect `file_path`'s header row and return the parser whose
+/// `get_required_columns()` are all present, without the caller needing to
+/// already know which funder produced the file.
+///
+/// When more than one [`header_detectable_parsers`] candidate matches, the
+/// one requiring the most columns wins (the more specific signature), since
+/// a shorter required-set is more likely to be a subset of an unrelated
+/// funder's file. Falls back to [`positional_detectable_parsers`] (scored
+/// via `detection_score`, since they have no header to validate) only when
+/// no header-based candidate matched at all — this is the tier that used to
+/// leave `BigParser` undetectable here even though the very same file would
+/// detect fine through [`detect_funder`].
+pub fn detect_parser(file_path: &Path) -> ParserResult<Box<dyn BaseParser>> {
+    let mut matches: Vec<Box<dyn BaseParser>> = Vec::new();
+    for parser in header_detectable_parsers() {
+        let headers = parser.parse_file_headers(file_path)?;
+        if parser.validate_columns(&headers).is_ok() {
+            matches.push(parser);
+        }
+    }
+
+    matches.sort_by_key(|p| std::cmp::Reverse(p.get_required_columns().len()));
+
+    if let Some(parser) = matches.into_iter().next() {
+        return Ok(parser);
+    }
+
+    // `>= 1.0`, not `> 0.0`: matches the same confident-match bar
+    // `BaseParser::matches_file`'s default (and therefore `detect_funder`)
+    // holds every candidate to, so a partial/graded `detection_score` (e.g.
+    // `BoomParser`'s hits/3) can't get silently routed to the wrong funder
+    // here while `detect_funder` would correctly refuse the same file.
+    if let Some(parser) = positional_detectable_parsers()
+        .into_iter()
+        .find(|parser| parser.detection_score(file_path) >= 1.0)
+    {
+        return Ok(parser);
+    }
+
+    let candidate_names: Vec<String> = header_detectable_parsers()
+        .iter()
+        .chain(positional_detectable_parsers().iter())
+        .map(|p| p.get_funder_name().to_string())
+        .collect();
+    Err(ParserError::ProcessingError(format!(
+        "Could not detect a funder for {:?}; checked candidates: {}",
+        file_path,
+        candidate_names.join(", ")
+    )))
+}
+
+/// A registry of funder parsers keyed by funder name, so a new funder is
+/// added by one [`register`](Self::register) call instead of editing every
+/// call site that currently hand-dispatches to a specific `XParser::new()`.
+///
+/// Each entry is a factory rather than a built instance since `BaseParser`
+/// impls are cheap to construct and [`detect`](Self::detect) needs a fresh
+/// one per candidate to score against the file.
+pub struct ParserRegistry {
+    factories: HashMap<String, Box<dyn Fn() -> Box<dyn BaseParser>>>,
+}
+
+impl ParserRegistry {
+    /// An empty registry with none of the built-in funders registered.
+    pub fn empty() -> Self {
+        ParserRegistry {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// A registry pre-populated with every funder parser this codebase
+    /// ships: the named-column parsers scored against their declared
+    /// `get_required_columns`, plus `BigParser`/`BoomParser`, whose
+    /// fixed-position layouts each have their own [`BaseParser::detection_score`]
+    /// override since neither has named columns to check. Coverage here
+    /// should track [`header_detectable_parsers`] + [`positional_detectable_parsers`]
+    /// (the same funders `detect_parser`/`detect_funder` know about) so this
+    /// doesn't silently drift into its own, narrower candidate set again.
+    pub fn with_builtin_parsers() -> Self {
+        let mut registry = Self::empty();
+        registry.register("BHB", Box::new(|| Box::new(BhbParser::new())));
+        registry.register("BIG", Box::new(|| Box::new(BigParser::new())));
+        registry.register("eFin", Box::new(|| Box::new(EfinParser::new())));
+        registry.register("In Advance", Box::new(|| Box::new(InAdvParser::new())));
+        registry.register("Kings", Box::new(|| Box::new(KingsParser::new())));
+        registry.register("Boom", Box::new(|| Box::new(BoomParser::new())));
+        registry
+    }
+
+    /// Register (or replace) the parser built by `factory` under `funder_name`.
+    pub fn register(&mut self, funder_name: &str, factory: Box<dyn Fn() -> Box<dyn BaseParser>>) {
+        self.factories.insert(funder_name.to_string(), factory);
+    }
+
+    /// Build the parser registered under `funder_name`, if any.
+    pub fn build(&self, funder_name: &str) -> Option<Box<dyn BaseParser>> {
+        self.factories.get(funder_name).map(|factory| factory())
+    }
+
+    /// Score every registered parser against `file_path` via
+    /// [`BaseParser::detection_score`] and return the funder name of the
+    /// highest scorer, so a caller can auto-detect a file's funder without
+    /// the user pre-selecting it. A tie between two parsers is broken
+    /// arbitrarily (registration order isn't preserved); a file that scores
+    /// `0.0` against every registered parser detects as `None`.
+    pub fn detect(&self, file_path: &Path) -> Option<String> {
+        let mut best: Option<(String, f64)> = None;
+
+        for (funder_name, factory) in &self.factories {
+            let parser = factory();
+            let score = parser.detection_score(file_path);
+            let is_better = match &best {
+                Some((_, best_score)) => score > *best_score,
+                None => true,
+            };
+            if is_better && score > 0.0 {
+                best = Some((funder_name.clone(), score));
+            }
+        }
+
+        best.map(|(funder_name, _)| funder_name)
+    }
+}
+
+/// Every parser [`detect_funder`] considers: the same
+/// [`header_detectable_parsers`] + [`positional_detectable_parsers`] split
+/// [`detect_parser`] uses, so the two functions can't independently drift
+/// out of sync on which funders they know about (the bug that used to leave
+/// `BigParser` detectable here but not through `detect_parser`).
+fn funder_detection_candidates() -> Vec<Box<dyn BaseParser>> {
+    header_detectable_parsers()
+        .into_iter()
+        .chain(positional_detectable_parsers())
+        .collect()
+}
+
+/// Identify which funder produced `file_path` by content alone (sheet
+/// names, header columns), modeled on the `is_statement`-style probing used
+/// elsewhere to classify a file before fully parsing it, so a caller never
+/// has to trust a user-supplied funder name on faith.
+///
+/// Returns an error naming every funder that matched when more than one
+/// [`BaseParser::matches_file`] succeeds, since silently picking one would
+/// risk running the wrong funder's layout over the file.
+pub fn detect_funder(file_path: &Path) -> Result<String, String> {
+    let matches: Vec<String> = funder_detection_candidates()
+        .into_iter()
+        .filter(|parser| parser.matches_file(file_path))
+        .map(|parser| parser.get_funder_name().to_string())
+        .collect();
+
+    match matches.len() {
+        0 => Err(format!("Could not detect a funder for {:?}", file_path)),
+        1 => Ok(matches.into_iter().next().unwrap()),
+        _ => Err(format!(
+            "Ambiguous file: matched more than one funder ({})",
+            matches.join(", ")
+        )),
+    }
+}
+
+/// Outcome of running a single file through [`process_batch`].
+#[derive(Debug)]
+pub struct BatchFileResult {
+    pub file_path: PathBuf,
+    pub error: Option<String>,
+}
+
+/// Result of a whole-folder batch run: the merged pivot table, a per-file
+/// success/error breakdown, and how many rows were dropped because their
+/// advance ID had already been seen in an earlier file.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub pivot: PivotTable,
+    pub file_results: Vec<BatchFileResult>,
+    pub skipped_duplicates: usize,
+}
+
+/// Parse every file in `files` in parallel (auto-detecting each one's funder
+/// via [`detect_parser`]) and merge the results into a single [`PivotTable`].
+///
+/// Mirrors the trade-registry dedup pattern used elsewhere in the codebase:
+/// an advance ID already seen in a prior file is counted once and recorded
+/// as a skipped duplicate instead of being added to `total_gross` again.
+pub fn process_batch(files: &[PathBuf]) -> BatchResult {
+    let per_file: Vec<(PathBuf, ParserResult<PivotTable>)> = files
+        .into_par_iter()
+        .map(|file_path| {
+            let outcome = detect_parser(file_path).and_then(|parser| parser.process(file_path));
+            (file_path.clone(), outcome)
+        })
+        .collect();
+
+    let mut combined = PivotTable::new();
+    let mut seen_advance_ids: HashSet<String> = HashSet::new();
+    let mut skipped_duplicates = 0usize;
+    let mut file_results = Vec::new();
+
+    for (file_path, outcome) in per_file {
+        match outcome {
+            Ok(pivot) => {
+                for row in pivot.rows {
+                    // Each per-file pivot carries its own "Totals" row; skip
+                    // it here since the merged table computes its own.
+                    if row.advance_id == "Totals" {
+                        continue;
+                    }
+
+                    if !seen_advance_ids.insert(row.advance_id.clone()) {
+                        skipped_duplicates += 1;
+                        continue;
+                    }
+
+                    combined.add_row(
+                        row.advance_id,
+                        row.merchant_name,
+                        row.sum_of_syn_gross_amount,
+                        row.total_servicing_fee,
+                        row.sum_of_syn_net_amount,
+                    );
+                }
+
+                file_results.push(BatchFileResult {
+                    file_path,
+                    error: None,
+                });
+            }
+            Err(e) => {
+                file_results.push(BatchFileResult {
+                    file_path,
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    combined.add_totals_row();
+
+    BatchResult {
+        pivot: combined,
+        file_results,
+        skipped_duplicates,
+    }
+}
+
+/// A chunk holds at least this many files once it starts splitting on byte
+/// size, so a week of tiny files doesn't degrade into one rayon task per
+/// file (the thing adaptive sizing exists to avoid).
+const MIN_FILES_PER_CHUNK: usize = 4;
+/// No chunk holds more than this many files even if the byte target hasn't
+/// been reached yet, so a long run of small files is still split across
+/// several chunks.
+const MAX_FILES_PER_CHUNK: usize = 64;
+
+/// Group `files` into work chunks sized so each chunk holds roughly
+/// `total_bytes / rayon::current_num_threads()` bytes rather than one file
+/// per chunk. A chunk closes once it reaches that byte target while holding
+/// at least [`MIN_FILES_PER_CHUNK`] files, or once it holds
+/// [`MAX_FILES_PER_CHUNK`] files; the last chunk takes whatever is left. A
+/// file whose size can't be read counts as zero bytes and is placed by the
+/// file-count limits alone.
+///
+/// Used to split a batch of same-shaped files (e.g. a week's worth of daily
+/// CSVs) across `rayon` so a pile of small files doesn't thrash the thread
+/// pool with tiny tasks. A batch of fewer than [`MIN_FILES_PER_CHUNK`]
+/// files, however large, runs as a single chunk.
+pub fn adaptive_file_chunks(files: &[PathBuf]) -> Vec<Vec<PathBuf>> {
+    if files.is_empty() {
+        return Vec::new();
+    }
+
+    let sizes: Vec<u64> = files
+        .iter()
+        .map(|f| std::fs::metadata(f).map(|m| m.len()).unwrap_or(0))
+        .collect();
+    let total_bytes: u64 = sizes.iter().sum();
+    let threads = rayon::current_num_threads().max(1) as u64;
+    let target_bytes_per_chunk = (total_bytes / threads).max(1);
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<PathBuf> = Vec::new();
+    let mut current_bytes: u64 = 0;
+
+    for (file, size) in files.iter().zip(sizes) {
+        current.push(file.clone());
+        current_bytes += size;
+
+        let hit_byte_target = current_bytes >= target_bytes_per_chunk && current.len() >= MIN_FILES_PER_CHUNK;
+        let hit_file_cap = current.len() >= MAX_FILES_PER_CHUNK;
+        if hit_byte_target || hit_file_cap {
+            chunks.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
@@ -2,285 +2,730 @@ use std::collections::HashMap;
 use std::path::Path;
 use calamine::{open_workbook, Reader, Xlsx, Data, Range, DataType};
 use chrono::{Utc, NaiveDate, Duration};
+use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use crate::database::{Database, Merchant};
 
-pub struct PortfolioParser {
-    portfolio_name: String,
-    funder_mappings: HashMap<String, String>,
+/// Which Excel date epoch a workbook's serial-number dates are relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSystem {
+    /// Serial 0 = 1899-12-31, with Excel's bogus 1900-02-29 (so serials
+    /// after 59 are off by one day and need to be shifted back). The
+    /// default for Windows-authored workbooks.
+    Excel1900,
+    /// Serial 0 = 1904-01-01, no leap-year bug. Used by older Mac-authored
+    /// workbooks.
+    Excel1904,
 }
 
-impl PortfolioParser {
-    pub fn new(portfolio_name: String) -> Self {
-        let mut funder_mappings = HashMap::new();
-        
-        // Mapping from Excel sheet names to internal funder names
-        funder_mappings.insert("BHB".to_string(), "BHB".to_string());
-        funder_mappings.insert("BIG".to_string(), "BIG".to_string());
-        funder_mappings.insert("CV".to_string(), "Clear View".to_string());
-        funder_mappings.insert("EFin".to_string(), "eFin".to_string());
-        funder_mappings.insert("InAd".to_string(), "In Advance".to_string());
-        funder_mappings.insert("Kings".to_string(), "Kings".to_string());
-        funder_mappings.insert("Boom".to_string(), "Boom".to_string());
-        
-        PortfolioParser {
-            portfolio_name,
-            funder_mappings,
-        }
-    }
-    
-    pub fn parse_portfolio_workbook(&self, file_path: &Path, db: &Database) -> Result<usize, String> {
-        let mut workbook: Xlsx<_> = open_workbook(file_path)
-            .map_err(|e| format!("Failed to open workbook: {}", e))?;
-        
-        let mut total_merchants = 0;
-        
-        // Iterate through each funder sheet
-        for (sheet_name, funder_name) in &self.funder_mappings {
-            if let Ok(range) = workbook.worksheet_range(sheet_name) {
-                match self.extract_merchants_from_sheet(&range, funder_name, db) {
-                    Ok(count) => {
-                        total_merchants += count;
-                    }
-                    Err(e) => {
-                        // Log error but continue processing other sheets
-                        eprintln!("Failed to extract merchants from {} sheet: {}", sheet_name, e);
+/// Parse a spreadsheet cell into a calendar date, trying (in order): ISO
+/// 8601, slash-separated, dash-separated, and month-name date strings, then
+/// falling back to an Excel serial-number interpretation under the given
+/// [`DateSystem`].
+///
+/// Returns `None` (rather than guessing) for ambiguous numeric strings like
+/// "03/04/2025" parsed against the wrong format, since both the month and
+/// day fields are valid as either — callers that need a specific convention
+/// should pre-normalize such strings before calling this.
+pub fn parse_spreadsheet_date(cell: &Data, date_system: DateSystem) -> Option<NaiveDate> {
+    const DATE_FORMATS: [&str; 8] = [
+        "%Y-%m-%d",  // ISO 8601: 2025-03-21
+        "%m/%d/%Y",  // slash, zero-padded: 03/21/2025
+        "%-m/%-d/%Y", // slash, unpadded: 3/21/2025
+        "%Y/%m/%d",  // ISO with slashes: 2025/03/21
+        "%m-%d-%Y",  // dash: 03-21-2025
+        "%d-%m-%Y",  // dash, day-first: 21-03-2025
+        "%d-%b-%Y",  // month-name: 21-Mar-2025
+        "%b-%d-%Y",  // month-name, month-first: Mar-21-2025
+    ];
+
+    match cell {
+        Data::String(s) => {
+            let trimmed = s.trim();
+            if trimmed.is_empty() {
+                return None;
+            }
+
+            // Two distinct leading fields that are both valid months read
+            // differently month-first and day-first; don't guess between them.
+            let fields: Vec<&str> = trimmed.split(|c: char| c == '/' || c == '-').collect();
+            if let [first, second, year] = fields.as_slice() {
+                let month = |f: &str| f.parse::<u32>().ok().filter(|n| (1..=12).contains(n));
+                if let (Some(a), Some(b)) = (month(*first), month(*second)) {
+                    if year.len() == 4 && a != b {
+                        return None;
                     }
                 }
             }
-            // Sheet not found is not an error - the workbook might not have all funders
+
+            for format in DATE_FORMATS {
+                if let Ok(date) = NaiveDate::parse_from_str(trimmed, format) {
+                    return Some(date);
+                }
+            }
+
+            None
+        }
+        Data::Float(f) => excel_serial_to_date(*f as i64, date_system),
+        Data::Int(i) => excel_serial_to_date(*i, date_system),
+        Data::DateTime(dt) => excel_serial_to_date(dt.as_f64() as i64, date_system),
+        _ => None,
+    }
+}
+
+fn excel_serial_to_date(serial: i64, date_system: DateSystem) -> Option<NaiveDate> {
+    match date_system {
+        DateSystem::Excel1900 => {
+            // Excel treats 1900 as a leap year, so serials 60+ are off by
+            // one day relative to the true calendar.
+            let adjusted = if serial > 59 { serial - 1 } else { serial };
+            NaiveDate::from_ymd_opt(1899, 12, 31)
+                .and_then(|base| base.checked_add_signed(Duration::days(adjusted)))
+        }
+        DateSystem::Excel1904 => {
+            NaiveDate::from_ymd_opt(1904, 1, 1)
+                .and_then(|base| base.checked_add_signed(Duration::days(serial)))
+        }
+    }
+}
+
+/// Detect whether `file_path` uses the 1904 date system by checking the
+/// `date1904` attribute on `<workbookPr>` in `xl/workbook.xml`. Defaults to
+/// [`DateSystem::Excel1900`] if the flag is absent or the file can't be
+/// inspected (the overwhelmingly common case).
+fn detect_date_system(file_path: &Path) -> DateSystem {
+    let file = match std::fs::File::open(file_path) {
+        Ok(f) => f,
+        Err(_) => return DateSystem::Excel1900,
+    };
+
+    let mut archive = match zip::ZipArchive::new(file) {
+        Ok(a) => a,
+        Err(_) => return DateSystem::Excel1900,
+    };
+
+    let mut workbook_xml = match archive.by_name("xl/workbook.xml") {
+        Ok(entry) => entry,
+        Err(_) => return DateSystem::Excel1900,
+    };
+
+    let mut contents = String::new();
+    if std::io::Read::read_to_string(&mut workbook_xml, &mut contents).is_err() {
+        return DateSystem::Excel1900;
+    }
+
+    if contents.contains("date1904=\"1\"") || contents.contains("date1904=\"true\"") {
+        DateSystem::Excel1904
+    } else {
+        DateSystem::Excel1900
+    }
+}
+
+/// Canonical portfolio field keys and the header spellings known to mean
+/// them. `map_column_indices` scores every sheet header against these
+/// rather than requiring an exact/substring match.
+const COLUMN_DEFINITIONS: &[(&str, &[&str])] = &[
+    ("date_funded", &["Date Funded", "Funded Date", "Fund Date"]),
+    ("merchant_name", &["Merchant Name", "Merchant", "Business Name", "DBA"]),
+    ("website", &["Website", "Web Site", "URL"]),
+    ("advance_id", &["Advance ID", "Deal ID", "Advance #", "Deal Number"]),
+    ("funder_advance_id", &["Funder Advance ID", "Funder Deal ID", "Funder ID"]),
+    ("industry", &["Industry: NAICS or SIC", "Industry", "NAICS", "SIC", "Industry Code"]),
+    ("state", &["State", "ST", "Province"]),
+    ("fico", &["FICO", "Credit Score", "Score"]),
+    ("buy_rate", &["Buy Rate", "Rate", "Factor Rate"]),
+    ("commission", &["Commission", "Comm", "Fee"]),
+    ("total_funded", &["Total Amount Funded", "Amount Funded", "Funded Amount", "Total Funded"]),
+];
+
+/// A header scores at or above this against a field's best-matching
+/// variation to be assigned to that field automatically.
+const HIGH_CONFIDENCE_THRESHOLD: f64 = 0.6;
+/// Below `HIGH_CONFIDENCE_THRESHOLD` but at or above this, a header is
+/// surfaced as "needs review" instead of being silently dropped.
+const LOW_CONFIDENCE_THRESHOLD: f64 = 0.3;
+
+/// Result of [`map_column_indices`]: the columns it was confident enough to
+/// assign, plus the ones that scored ambiguously.
+struct ColumnIndexMapping {
+    indices: HashMap<String, usize>,
+    /// `(header, best-guess field, score)` for headers in the "needs
+    /// review" band.
+    needs_review: Vec<(String, String, f64)>,
+    /// Newly-scored `(funder_name, normalized_header, field, score)`
+    /// mappings that should be remembered for next time. Headers that were
+    /// already confirmed in `column_mappings` are not repeated here.
+    newly_learned: Vec<LearnedColumnMapping>,
+}
+
+/// A `(funder, normalized header) -> field` mapping worth persisting, keyed
+/// the same way [`Database::upsert_column_mapping`] stores one.
+#[derive(Debug, Clone)]
+pub struct LearnedColumnMapping {
+    pub funder_name: String,
+    pub normalized_header: String,
+    pub field: String,
+    pub confidence: f64,
+}
+
+/// A per-sheet or per-row issue noticed while extracting merchants. These
+/// never abort extraction — they're collected alongside the rows that did
+/// parse so a caller (and eventually the UI) can show what was skipped
+/// instead of a silent count mismatch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionWarning {
+    pub sheet_name: String,
+    /// 1-based spreadsheet row the warning applies to, or `None` for a
+    /// sheet/column-level warning with no single row.
+    pub row: Option<usize>,
+    pub message: String,
+}
+
+/// Everything [`PortfolioParser::parse_portfolio_workbook_in_memory`] found
+/// in one workbook: the merchants ready to insert, any column mappings it
+/// scored for the first time that are worth remembering, and any rows or
+/// columns it couldn't confidently extract.
+#[derive(Debug, Default)]
+pub struct PortfolioExtractionResult {
+    pub merchants: Vec<Merchant>,
+    pub learned_column_mappings: Vec<LearnedColumnMapping>,
+    pub warnings: Vec<ExtractionWarning>,
+}
+
+/// Lowercase a header, strip parenthetical suffixes (e.g. "(USD)") and
+/// punctuation, and collapse whitespace, so "Amt Funded (USD)" and "amt.
+/// funded" normalize to the same token stream.
+fn normalize_header(header: &str) -> String {
+    let mut without_parens = String::with_capacity(header.len());
+    let mut depth = 0i32;
+    for c in header.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = (depth - 1).max(0),
+            _ if depth == 0 => without_parens.push(c),
+            _ => {}
         }
-        
-        Ok(total_merchants)
     }
-    
-    fn extract_merchants_from_sheet(
+
+    without_parens
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn tokenize(normalized_header: &str) -> std::collections::HashSet<String> {
+    normalized_header.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+/// Token-overlap Jaccard similarity plus a bonus when one normalized string
+/// is a substring of the other (catches abbreviations like "DBA" that share
+/// no tokens with "Business Name" but whose full variation appears verbatim
+/// in a longer header).
+fn score_header_against_variation(
+    normalized_header: &str,
+    header_tokens: &std::collections::HashSet<String>,
+    variation: &str,
+) -> f64 {
+    let normalized_variation = normalize_header(variation);
+    let variation_tokens = tokenize(&normalized_variation);
+
+    let jaccard = if header_tokens.is_empty() || variation_tokens.is_empty() {
+        0.0
+    } else {
+        let intersection = header_tokens.intersection(&variation_tokens).count() as f64;
+        let union = header_tokens.union(&variation_tokens).count() as f64;
+        intersection / union
+    };
+
+    let substring_bonus = if !normalized_variation.is_empty()
+        && (normalized_header.contains(&normalized_variation) || normalized_variation.contains(normalized_header))
+    {
+        0.3
+    } else {
+        0.0
+    };
+
+    (jaccard + substring_bonus).min(1.0)
+}
+
+/// What a [`StatementParser`] produces from one matched sheet: the
+/// merchants it extracted, any column mappings scored for the first time,
+/// and any row/column it couldn't confidently apply.
+#[derive(Debug, Default)]
+pub struct StatementExtraction {
+    pub merchants: Vec<Merchant>,
+    pub learned_column_mappings: Vec<LearnedColumnMapping>,
+    pub warnings: Vec<ExtractionWarning>,
+}
+
+/// One registered statement layout — a funder or broker's sheet within a
+/// portfolio workbook. [`PortfolioParser`] auto-selects which impl handles a
+/// given sheet the same way [`super::detect_parser`] auto-selects a
+/// [`super::BaseParser`] for a standalone statement file: first by the
+/// sheet's declared name, falling back to sniffing its header row for funder
+/// whose sheet got renamed.
+///
+/// Adding support for a funder whose export needs different extraction
+/// logic (a fixed column layout, a header row that isn't row 2, etc.) is a
+/// matter of registering one new impl in
+/// [`PortfolioParser::statement_parsers`] — no changes needed to the
+/// dispatch in `parse_portfolio_workbook_in_memory`.
+pub trait StatementParser {
+    /// Internal funder name merchants from this layout are tagged with, and
+    /// the key column mappings are persisted under.
+    fn funder_name(&self) -> &str;
+
+    /// The sheet name this layout is normally exported under.
+    fn declared_sheet_name(&self) -> &str;
+
+    /// Whether `headers` (a sheet's header row) look like this layout, used
+    /// as a fallback when a sheet's name doesn't match `declared_sheet_name`
+    /// (e.g. a renamed tab). Defaults to requiring a confident match against
+    /// some variation of `merchant_name`, the one column every layout needs.
+    fn detect_headers(&self, headers: &[String]) -> bool {
+        let merchant_name_variations = COLUMN_DEFINITIONS
+            .iter()
+            .find(|(key, _)| *key == "merchant_name")
+            .map(|(_, variations)| *variations)
+            .unwrap_or(&[]);
+
+        headers.iter().any(|header| {
+            let normalized = normalize_header(header);
+            if normalized.is_empty() {
+                return false;
+            }
+            let tokens = tokenize(&normalized);
+            merchant_name_variations
+                .iter()
+                .any(|v| score_header_against_variation(&normalized, &tokens, v) >= HIGH_CONFIDENCE_THRESHOLD)
+        })
+    }
+
+    /// Extract every merchant row out of `range`, scoring headers against
+    /// `column_mappings` so a header confirmed in an earlier run doesn't
+    /// need to be re-scored. Rows or columns that can't be confidently
+    /// extracted are recorded as an [`ExtractionWarning`] rather than
+    /// aborting the whole sheet.
+    fn extract(
         &self,
         range: &Range<Data>,
-        funder_name: &str,
-        db: &Database,
-    ) -> Result<usize, String> {
-        // Find the header row (should be at index 1, which is row 2)
-        let header_row_index = 1;
-        
-        // Get headers from row 2
-        let headers = self.get_headers_from_row(range, header_row_index)?;
-        
-        // Map column names to indices
-        let column_indices = self.map_column_indices(&headers)?;
-        
-        let mut merchant_count = 0;
-        
-        // Process data rows starting from row 3 (index 2)
-        let total_rows = range.height() as usize;
-        for row_index in (header_row_index + 1)..total_rows {
-            // Check if the row has any data
-            if self.is_row_empty(range, row_index, &column_indices) {
+        portfolio_name: &str,
+        date_system: DateSystem,
+        column_mappings: &HashMap<(String, String), String>,
+    ) -> Result<StatementExtraction, String>;
+}
+
+/// The statement layout every currently-registered funder sheet uses: fuzzy
+/// column matching against [`COLUMN_DEFINITIONS`], with the header row fixed
+/// at row 2. Distinct only by which sheet name/funder name it's registered
+/// under; a funder that needs genuinely different extraction gets its own
+/// `StatementParser` impl instead of a new instance of this one.
+pub struct FunderSheetParser {
+    funder_name: String,
+    declared_sheet_name: String,
+}
+
+impl StatementParser for FunderSheetParser {
+    fn funder_name(&self) -> &str {
+        &self.funder_name
+    }
+
+    fn declared_sheet_name(&self) -> &str {
+        &self.declared_sheet_name
+    }
+
+    fn extract(
+        &self,
+        range: &Range<Data>,
+        portfolio_name: &str,
+        date_system: DateSystem,
+        column_mappings: &HashMap<(String, String), String>,
+    ) -> Result<StatementExtraction, String> {
+        extract_generic_sheet(&self.funder_name, &self.declared_sheet_name, range, portfolio_name, date_system, column_mappings)
+    }
+}
+
+/// Shared extraction body behind [`FunderSheetParser::extract`]: find the
+/// header row, score it into column indices, then walk every data row,
+/// collecting an [`ExtractionWarning`] instead of failing outright for
+/// anything that can't be confidently extracted.
+fn extract_generic_sheet(
+    funder_name: &str,
+    sheet_name: &str,
+    range: &Range<Data>,
+    portfolio_name: &str,
+    date_system: DateSystem,
+    column_mappings: &HashMap<(String, String), String>,
+) -> Result<StatementExtraction, String> {
+    // Find the header row (should be at index 1, which is row 2)
+    let header_row_index = 1;
+    let headers = get_headers_from_row(range, header_row_index);
+
+    let mapping = map_column_indices(&headers, funder_name, column_mappings)?;
+    let column_indices = mapping.indices;
+
+    let mut warnings = Vec::new();
+    for (header, guessed_field, score) in &mapping.needs_review {
+        warnings.push(ExtractionWarning {
+            sheet_name: sheet_name.to_string(),
+            row: None,
+            message: format!(
+                "Column '{}' scored {:.2} for field '{}' — needs review, not applied",
+                header, score, guessed_field
+            ),
+        });
+    }
+
+    let mut merchants = Vec::new();
+
+    // Process data rows starting from row 3 (index 2)
+    let total_rows = range.height() as usize;
+    for row_index in (header_row_index + 1)..total_rows {
+        if is_row_empty(range, row_index, &column_indices) {
+            continue;
+        }
+
+        match extract_merchant_from_row(range, row_index, &column_indices, funder_name, portfolio_name, date_system) {
+            Ok(merchant) => merchants.push(merchant),
+            Err(e) => {
+                warnings.push(ExtractionWarning {
+                    sheet_name: sheet_name.to_string(),
+                    row: Some(row_index + 1),
+                    message: e,
+                });
+            }
+        }
+    }
+
+    Ok(StatementExtraction {
+        merchants,
+        learned_column_mappings: mapping.newly_learned,
+        warnings,
+    })
+}
+
+fn get_headers_from_row(range: &Range<Data>, row_index: usize) -> Vec<String> {
+    let mut headers = Vec::new();
+
+    let total_cols = range.width() as usize;
+    for col_index in 0..total_cols {
+        let cell_value = range.get_value((row_index as u32, col_index as u32))
+            .and_then(|cell| cell.as_string())
+            .unwrap_or_default();
+        headers.push(cell_value);
+    }
+
+    headers
+}
+
+/// Score every header against every canonical field's known variations and
+/// assign the column to its best match, resolving any collisions greedily
+/// by descending score. A header a prior run (or a user) has already
+/// confirmed for this funder is applied directly instead of being
+/// re-scored. Headers that score in the "needs review" band are surfaced
+/// rather than silently dropped.
+fn map_column_indices(
+    headers: &[String],
+    funder_name: &str,
+    column_mappings: &HashMap<(String, String), String>,
+) -> Result<ColumnIndexMapping, String> {
+    let mut indices = HashMap::new();
+    let mut needs_review = Vec::new();
+    let mut newly_learned = Vec::new();
+
+    // Candidates that scored above HIGH_CONFIDENCE_THRESHOLD, resolved
+    // by descending score so the best-matching header wins a field when
+    // more than one header matches it.
+    let mut candidates: Vec<(f64, usize, &'static str)> = Vec::new();
+
+    for (header_idx, header) in headers.iter().enumerate() {
+        let normalized_header = normalize_header(header);
+        if normalized_header.is_empty() {
+            continue;
+        }
+
+        if let Some(field) = column_mappings.get(&(funder_name.to_string(), normalized_header.clone())) {
+            if let Some((key, _)) = COLUMN_DEFINITIONS.iter().find(|(key, _)| key == field) {
+                indices.insert(key.to_string(), header_idx);
                 continue;
             }
-            
-            match self.extract_merchant_from_row(range, row_index, &column_indices, funder_name) {
-                Ok(merchant) => {
-                    // Save merchant to database
-                    if let Err(e) = db.insert_or_update_merchant(&merchant) {
-                        eprintln!("Failed to save merchant: {}", e);
-                    } else {
-                        merchant_count += 1;
-                    }
-                }
-                Err(e) => {
-                    // Skip invalid rows but log the error
-                    eprintln!("Failed to extract merchant from row {}: {}", row_index + 1, e);
+        }
+
+        let header_tokens = tokenize(&normalized_header);
+        let mut best_score = 0.0;
+        let mut best_field = None;
+
+        for (key, variations) in COLUMN_DEFINITIONS {
+            for variation in *variations {
+                let score = score_header_against_variation(&normalized_header, &header_tokens, variation);
+                if score > best_score {
+                    best_score = score;
+                    best_field = Some(*key);
                 }
             }
         }
-        
-        Ok(merchant_count)
+
+        match best_field {
+            Some(field) if best_score >= HIGH_CONFIDENCE_THRESHOLD => {
+                candidates.push((best_score, header_idx, field));
+            }
+            Some(field) if best_score >= LOW_CONFIDENCE_THRESHOLD => {
+                needs_review.push((header.clone(), field.to_string(), best_score));
+            }
+            _ => {}
+        }
+    }
+
+    candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    let mut claimed_fields = std::collections::HashSet::new();
+    for (score, header_idx, field) in candidates {
+        if claimed_fields.contains(field) {
+            continue;
+        }
+        claimed_fields.insert(field);
+        indices.insert(field.to_string(), header_idx);
+
+        let normalized_header = normalize_header(&headers[header_idx]);
+        newly_learned.push(LearnedColumnMapping {
+            funder_name: funder_name.to_string(),
+            normalized_header,
+            field: field.to_string(),
+            confidence: score,
+        });
     }
-    
-    fn get_headers_from_row(&self, range: &Range<Data>, row_index: usize) -> Result<Vec<String>, String> {
-        let mut headers = Vec::new();
-        
-        let total_cols = range.width() as usize;
-        for col_index in 0..total_cols {
-            let cell_value = range.get_value((row_index as u32, col_index as u32))
+
+    // At minimum, we need merchant_name
+    if !indices.contains_key("merchant_name") {
+        return Err("Missing required column: Merchant Name".to_string());
+    }
+
+    Ok(ColumnIndexMapping { indices, needs_review, newly_learned })
+}
+
+fn is_row_empty(range: &Range<Data>, row_index: usize, column_indices: &HashMap<String, usize>) -> bool {
+    // Check if merchant name is empty (required field)
+    if let Some(&merchant_col) = column_indices.get("merchant_name") {
+        let merchant_name = range.get_value((row_index as u32, merchant_col as u32))
+            .and_then(|cell| cell.as_string())
+            .unwrap_or_default();
+        return merchant_name.trim().is_empty();
+    }
+    true
+}
+
+fn extract_merchant_from_row(
+    range: &Range<Data>,
+    row_index: usize,
+    column_indices: &HashMap<String, usize>,
+    funder_name: &str,
+    portfolio_name: &str,
+    date_system: DateSystem,
+) -> Result<Merchant, String> {
+    let get_string_value = |key: &str| -> Option<String> {
+        column_indices.get(key).and_then(|&col_idx| {
+            range.get_value((row_index as u32, col_idx as u32))
                 .and_then(|cell| cell.as_string())
-                .unwrap_or_default();
-            headers.push(cell_value);
+                .filter(|s| !s.trim().is_empty())
+        })
+    };
+
+    let get_date_value = |key: &str| -> Option<String> {
+        column_indices.get(key).and_then(|&col_idx| {
+            range.get_value((row_index as u32, col_idx as u32))
+                .and_then(|cell| parse_spreadsheet_date(cell, date_system))
+                .map(|date| date.format("%Y-%m-%d").to_string())
+        })
+    };
+
+    let get_float_value = |key: &str| -> Option<f64> {
+        column_indices.get(key).and_then(|&col_idx| {
+            range.get_value((row_index as u32, col_idx as u32))
+                .and_then(|cell| {
+                    match cell {
+                        Data::Float(f) => Some(*f),
+                        Data::Int(i) => Some(*i as f64),
+                        Data::String(s) => {
+                            // Try to parse string as number, removing currency symbols and commas
+                            let cleaned = s.replace("$", "").replace(",", "").replace("%", "");
+                            cleaned.parse::<f64>().ok()
+                        }
+                        _ => None,
+                    }
+                })
+        })
+    };
+
+    let merchant_name = get_string_value("merchant_name")
+        .ok_or_else(|| "Missing merchant name".to_string())?;
+
+    let advance_id = get_string_value("advance_id");
+
+    // Generate unique ID
+    let id = Uuid::new_v4().to_string();
+
+    let now = Utc::now();
+
+    Ok(Merchant {
+        id,
+        portfolio_name: portfolio_name.to_string(),
+        funder_name: funder_name.to_string(),
+        date_funded: get_date_value("date_funded"),
+        merchant_name,
+        website: get_string_value("website"),
+        advance_id,
+        funder_advance_id: get_string_value("funder_advance_id"),
+        industry_naics_or_sic: get_string_value("industry"),
+        state: get_string_value("state"),
+        fico: get_string_value("fico"),
+        buy_rate: get_float_value("buy_rate"),
+        commission: get_float_value("commission"),
+        total_amount_funded: get_float_value("total_funded"),
+        created_timestamp: now,
+        updated_timestamp: now,
+    })
+}
+
+pub struct PortfolioParser {
+    portfolio_name: String,
+    parsers: Vec<Box<dyn StatementParser>>,
+}
+
+impl PortfolioParser {
+    pub fn new(portfolio_name: String) -> Self {
+        PortfolioParser {
+            portfolio_name,
+            parsers: Self::statement_parsers(),
         }
-        
-        Ok(headers)
     }
-    
-    fn map_column_indices(&self, headers: &[String]) -> Result<HashMap<String, usize>, String> {
-        let mut indices = HashMap::new();
-        
-        // Required columns and their variations
-        let column_mappings = vec![
-            ("date_funded", vec!["Date Funded", "Funded Date", "Fund Date"]),
-            ("merchant_name", vec!["Merchant Name", "Merchant", "Business Name", "DBA"]),
-            ("website", vec!["Website", "Web Site", "URL"]),
-            ("advance_id", vec!["Advance ID", "Deal ID", "Advance #", "Deal Number"]),
-            ("funder_advance_id", vec!["Funder Advance ID", "Funder Deal ID", "Funder ID"]),
-            ("industry", vec!["Industry: NAICS or SIC", "Industry", "NAICS", "SIC", "Industry Code"]),
-            ("state", vec!["State", "ST", "Province"]),
-            ("fico", vec!["FICO", "Credit Score", "Score"]),
-            ("buy_rate", vec!["Buy Rate", "Rate", "Factor Rate"]),
-            ("commission", vec!["Commission", "Comm", "Fee"]),
-            ("total_funded", vec!["Total Amount Funded", "Amount Funded", "Funded Amount", "Total Funded"]),
+
+    /// The registered statement layouts, one per known funder sheet. A new
+    /// funder is onboarded by adding an entry here (or, for a layout that
+    /// needs genuinely different extraction, a new [`StatementParser`] impl
+    /// pushed alongside these).
+    fn statement_parsers() -> Vec<Box<dyn StatementParser>> {
+        let sheet_to_funder: &[(&str, &str)] = &[
+            ("BHB", "BHB"),
+            ("BIG", "BIG"),
+            ("CV", "Clear View"),
+            ("EFin", "eFin"),
+            ("InAd", "In Advance"),
+            ("Kings", "Kings"),
+            ("Boom", "Boom"),
         ];
-        
-        for (key, variations) in column_mappings {
-            for (idx, header) in headers.iter().enumerate() {
-                let header_trimmed = header.trim();
-                let header_lower = header_trimmed.to_lowercase();
-                for variation in &variations {
-                    if header_lower == variation.to_lowercase() || header_lower.contains(&variation.to_lowercase()) {
-                        indices.insert(key.to_string(), idx);
-                        break;
-                    }
-                }
-                if indices.contains_key(key) {
-                    break;
-                }
+
+        sheet_to_funder
+            .iter()
+            .map(|(sheet_name, funder_name)| -> Box<dyn StatementParser> {
+                Box::new(FunderSheetParser {
+                    funder_name: funder_name.to_string(),
+                    declared_sheet_name: sheet_name.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Open `file_path`, extract merchants from every sheet a registered
+    /// [`StatementParser`] recognizes, and save them (plus any newly learned
+    /// column mappings) straight to `db`.
+    ///
+    /// Thin wrapper around [`Self::parse_portfolio_workbook_in_memory`] for
+    /// callers that only process one portfolio at a time and don't need to
+    /// keep the database lock off the parse itself.
+    pub fn parse_portfolio_workbook(&self, file_path: &Path, db: &Database) -> Result<usize, String> {
+        let column_mappings = db.get_all_column_mappings()
+            .map_err(|e| format!("Failed to load column mappings: {}", e))?;
+
+        let result = self.parse_portfolio_workbook_in_memory(file_path, &column_mappings)?;
+
+        let mut merchant_count = 0;
+        for merchant in &result.merchants {
+            if let Err(e) = db.insert_or_update_merchant(merchant) {
+                eprintln!("Failed to save merchant: {}", e);
+            } else {
+                merchant_count += 1;
             }
         }
-        
-        // At minimum, we need merchant_name
-        if !indices.contains_key("merchant_name") {
-            return Err("Missing required column: Merchant Name".to_string());
+        for learned in &result.learned_column_mappings {
+            let _ = db.upsert_column_mapping(
+                &learned.funder_name,
+                &learned.normalized_header,
+                &learned.field,
+                learned.confidence,
+            );
         }
-        
-        Ok(indices)
-    }
-    
-    fn is_row_empty(&self, range: &Range<Data>, row_index: usize, column_indices: &HashMap<String, usize>) -> bool {
-        // Check if merchant name is empty (required field)
-        if let Some(&merchant_col) = column_indices.get("merchant_name") {
-            let merchant_name = range.get_value((row_index as u32, merchant_col as u32))
-                .and_then(|cell| cell.as_string())
-                .unwrap_or_default();
-            return merchant_name.trim().is_empty();
+        for warning in &result.warnings {
+            eprintln!("{}: {}", warning.sheet_name, warning.message);
         }
-        true
+
+        Ok(merchant_count)
     }
-    
-    fn extract_merchant_from_row(
+
+    /// Open `file_path` and extract merchants from every sheet a registered
+    /// [`StatementParser`] recognizes — first by the sheet's declared name,
+    /// falling back to sniffing its header row for a renamed tab — scoring
+    /// headers against `column_mappings` (a snapshot of
+    /// `Database::get_all_column_mappings`) instead of querying the database
+    /// per header. Touches no database connection at all, so it's safe to
+    /// run across many portfolios in parallel and commit the combined
+    /// result under a single lock afterward.
+    pub fn parse_portfolio_workbook_in_memory(
         &self,
-        range: &Range<Data>,
-        row_index: usize,
-        column_indices: &HashMap<String, usize>,
-        funder_name: &str,
-    ) -> Result<Merchant, String> {
-        let get_string_value = |key: &str| -> Option<String> {
-            column_indices.get(key).and_then(|&col_idx| {
-                range.get_value((row_index as u32, col_idx as u32))
-                    .and_then(|cell| cell.as_string())
-                    .filter(|s| !s.trim().is_empty())
-            })
-        };
-        
-        let get_date_value = |key: &str| -> Option<String> {
-            column_indices.get(key).and_then(|&col_idx| {
-                range.get_value((row_index as u32, col_idx as u32))
-                    .and_then(|cell| {
-                        match cell {
-                            Data::String(s) if !s.trim().is_empty() => {
-                                // Parse date format like "3/21/2025" to "2025-03-21"
-                                let parts: Vec<&str> = s.trim().split('/').collect();
-                                if parts.len() == 3 {
-                                    // Convert M/D/YYYY to YYYY-MM-DD
-                                    if let (Some(month), Some(day), Some(year)) = 
-                                        (parts[0].parse::<u32>().ok(), 
-                                         parts[1].parse::<u32>().ok(), 
-                                         parts[2].parse::<i32>().ok()) {
-                                        Some(format!("{:04}-{:02}-{:02}", year, month, day))
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    Some(s.clone())
-                                }
-                            }
-                            Data::Float(f) => {
-                                // Excel stores dates as days since 1900-01-01
-                                // But there's a bug: Excel thinks 1900 is a leap year
-                                let days = *f as i64;
-                                let adjusted_days = if days > 59 { days - 1 } else { days };
-                                
-                                NaiveDate::from_ymd_opt(1899, 12, 31)
-                                    .and_then(|base| base.checked_add_signed(Duration::days(adjusted_days)))
-                                    .map(|date| date.format("%Y-%m-%d").to_string())
-                            }
-                            Data::Int(i) => {
-                                // Excel stores dates as days since 1900-01-01
-                                let adjusted_days = if *i > 59 { i - 1 } else { *i };
-                                
-                                NaiveDate::from_ymd_opt(1899, 12, 31)
-                                    .and_then(|base| base.checked_add_signed(Duration::days(adjusted_days)))
-                                    .map(|date| date.format("%Y-%m-%d").to_string())
-                            }
-                            Data::DateTime(dt) => {
-                                // Excel DateTime: days since 1900-01-01, but Excel incorrectly treats 1900 as leap year
-                                let days = dt.as_f64() as i64;
-                                // Adjust for Excel's leap year bug (1900-02-29 doesn't exist)
-                                let adjusted_days = if days > 59 { days - 1 } else { days };
-                                
-                                NaiveDate::from_ymd_opt(1899, 12, 31)
-                                    .and_then(|base| base.checked_add_signed(Duration::days(adjusted_days)))
-                                    .map(|date| date.format("%Y-%m-%d").to_string())
-                            }
-                            _ => None
-                        }
-                    })
-            })
-        };
-        
-        let get_float_value = |key: &str| -> Option<f64> {
-            column_indices.get(key).and_then(|&col_idx| {
-                range.get_value((row_index as u32, col_idx as u32))
-                    .and_then(|cell| {
-                        match cell {
-                            Data::Float(f) => Some(*f),
-                            Data::Int(i) => Some(*i as f64),
-                            Data::String(s) => {
-                                // Try to parse string as number, removing currency symbols and commas
-                                let cleaned = s.replace("$", "").replace(",", "").replace("%", "");
-                                cleaned.parse::<f64>().ok()
-                            }
-                            _ => None,
-                        }
-                    })
-            })
-        };
-        
-        let merchant_name = get_string_value("merchant_name")
-            .ok_or_else(|| "Missing merchant name".to_string())?;
-        
-        let advance_id = get_string_value("advance_id");
-        
-        // Generate unique ID
-        let id = Uuid::new_v4().to_string();
-        
-        let now = Utc::now();
-        
-        Ok(Merchant {
-            id,
-            portfolio_name: self.portfolio_name.clone(),
-            funder_name: funder_name.to_string(),
-            date_funded: get_date_value("date_funded"),
-            merchant_name,
-            website: get_string_value("website"),
-            advance_id,
-            funder_advance_id: get_string_value("funder_advance_id"),
-            industry_naics_or_sic: get_string_value("industry"),
-            state: get_string_value("state"),
-            fico: get_string_value("fico"),
-            buy_rate: get_float_value("buy_rate"),
-            commission: get_float_value("commission"),
-            total_amount_funded: get_float_value("total_funded"),
-            created_timestamp: now,
-            updated_timestamp: now,
-        })
+        file_path: &Path,
+        column_mappings: &HashMap<(String, String), String>,
+    ) -> Result<PortfolioExtractionResult, String> {
+        let mut workbook: Xlsx<_> = crate::retry::retry_with_backoff(
+            || open_workbook(file_path),
+            crate::retry::RetryPolicy::default(),
+        )
+        .map_err(|e| format!("Failed to open workbook: {}", e))?;
+
+        // The 1900-vs-1904 date epoch lives in workbook.xml, so it only
+        // needs to be detected once per workbook rather than per cell.
+        let date_system = detect_date_system(file_path);
+
+        let mut result = PortfolioExtractionResult::default();
+
+        for sheet_name in workbook.sheet_names().to_vec() {
+            let range = match workbook.worksheet_range(&sheet_name) {
+                Ok(range) => range,
+                Err(_) => continue,
+            };
+
+            let parser = self
+                .parsers
+                .iter()
+                .find(|p| p.declared_sheet_name().eq_ignore_ascii_case(&sheet_name))
+                .or_else(|| {
+                    let headers = get_headers_from_row(&range, 1);
+                    self.parsers.iter().find(|p| p.detect_headers(&headers))
+                });
+
+            // A sheet no registered layout recognizes (e.g. a summary tab)
+            // isn't an error - the workbook might carry sheets this parser
+            // was never meant to read.
+            let Some(parser) = parser else {
+                continue;
+            };
+
+            match parser.extract(&range, &self.portfolio_name, date_system, column_mappings) {
+                Ok(extraction) => {
+                    result.merchants.extend(extraction.merchants);
+                    result.learned_column_mappings.extend(extraction.learned_column_mappings);
+                    result.warnings.extend(extraction.warnings);
+                }
+                Err(e) => {
+                    result.warnings.push(ExtractionWarning {
+                        sheet_name: sheet_name.clone(),
+                        row: None,
+                        message: format!("Failed to extract merchants from {} sheet: {}", sheet_name, e),
+                    });
+                }
+            }
+        }
+
+        Ok(result)
     }
 }
\ No newline at end of file
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use crate::parsers::base_parser::{BaseParser, PivotTable, ReconciliationWarning};
+
+/// Every [`ReconciliationWarning`] [`reconcile`] turned up: [`PivotTable::reconcile`]'s
+/// built-in checks, the duplicate-`advance_id` check, and whatever the
+/// parser's own [`BaseParser::reconciliation_checks`] contributed. A bare
+/// `Vec` would work just as well, but callers (the desktop app's
+/// discrepancy display) want a named type to hang a `is_clean` convenience on.
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationReport {
+    pub warnings: Vec<ReconciliationWarning>,
+}
+
+impl ReconciliationReport {
+    /// No check failed.
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Two or more rows (other than "Totals") sharing the same `advance_id` after
+/// grouping, which should be impossible for a correctly-grouped pivot but
+/// would otherwise silently understate that advance's totals if it ever
+/// happened (e.g. a merge of two already-grouped pivots that didn't dedup).
+fn duplicate_advance_id_warnings(pivot: &PivotTable) -> Vec<ReconciliationWarning> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for row in &pivot.rows {
+        if row.advance_id == "Totals" {
+            continue;
+        }
+        *counts.entry(row.advance_id.as_str()).or_insert(0) += 1;
+    }
+
+    let mut duplicates: Vec<(&str, usize)> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .collect();
+    // `counts` is a HashMap, so its iteration order is randomized; sort by
+    // advance_id so the returned warnings (and anything downstream that
+    // displays them in order) are stable across runs.
+    duplicates.sort_by(|a, b| a.0.cmp(b.0));
+
+    duplicates
+        .into_iter()
+        .map(|(advance_id, count)| ReconciliationWarning {
+            row_key: advance_id.to_string(),
+            check: "duplicate_advance_id".to_string(),
+            expected: Decimal::ONE,
+            actual: Decimal::from(count),
+            delta: Decimal::from(count - 1),
+        })
+        .collect()
+}
+
+/// Run every reconciliation check against `pivot`: [`PivotTable::reconcile`]'s
+/// built-in gross/fee/net and totals-match checks, the duplicate-`advance_id`
+/// check, and `parser`'s own [`BaseParser::reconciliation_checks`]. Never
+/// panics — a failing check is just another entry in the returned report.
+pub fn reconcile(parser: &dyn BaseParser, pivot: &PivotTable) -> ReconciliationReport {
+    let mut warnings = pivot.reconcile();
+    warnings.extend(duplicate_advance_id_warnings(pivot));
+    warnings.extend(parser.reconciliation_checks(pivot));
+
+    ReconciliationReport { warnings }
+}
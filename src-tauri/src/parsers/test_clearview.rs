@@ -2,6 +2,7 @@
 mod tests {
     use super::super::clearview_daily_parser::ClearViewDailyParser;
     use super::super::clearview_weekly_parser::ClearViewWeeklyParser;
+    use std::fs;
     use std::path::Path;
 
     #[test]
@@ -79,4 +80,60 @@ mod tests {
             println!("Test files not found, skipping test");
         }
     }
+
+    /// Each daily file's AdvanceIDs sum to the same totals regardless of
+    /// which file they land in, so `process_parallel`'s per-file rayon fan-out
+    /// can't be distinguished from `process`'s byte-chunked one by totals
+    /// alone — only row order proves [`ClearViewDailyParser::process_parallel`]
+    /// sorts before returning rather than leaving it to `HashMap` iteration.
+    #[test]
+    fn test_process_parallel_orders_rows_by_advance_id() {
+        let temp_dir = std::env::temp_dir();
+        let file_a = temp_dir.join("test_clearview_parallel_a.csv");
+        let file_b = temp_dir.join("test_clearview_parallel_b.csv");
+
+        fs::write(&file_a, "AdvanceID,Syn Gross Amount,Syn Net Amount,Advance Status\nZZZ-9,\"$100.00\",\"$90.00\",Active\n")
+            .expect("Failed to write test file");
+        fs::write(&file_b, "AdvanceID,Syn Gross Amount,Syn Net Amount,Advance Status\nAAA-1,\"$200.00\",\"$180.00\",Active\n")
+            .expect("Failed to write test file");
+
+        let parser = ClearViewDailyParser::new(vec![file_a.clone(), file_b.clone()]);
+        let pivot = parser.process_parallel().expect("process_parallel failed");
+
+        let advance_ids: Vec<&str> = pivot.rows.iter().map(|r| r.advance_id.as_str()).collect();
+        assert_eq!(advance_ids, vec!["AAA-1", "ZZZ-9", "Totals"]);
+
+        fs::remove_file(&file_a).ok();
+        fs::remove_file(&file_b).ok();
+    }
+
+    /// A malformed file shouldn't abort the whole batch silently — its error
+    /// should be collected and surfaced alongside every other failing file's,
+    /// rather than `process_parallel` stopping at the first one.
+    #[test]
+    fn test_process_parallel_aggregates_errors_across_files() {
+        let temp_dir = std::env::temp_dir();
+        let good_file = temp_dir.join("test_clearview_parallel_good.csv");
+        let missing_file_1 = temp_dir.join("test_clearview_parallel_missing_1.csv");
+        let missing_file_2 = temp_dir.join("test_clearview_parallel_missing_2.csv");
+
+        fs::write(&good_file, "AdvanceID,Syn Gross Amount,Syn Net Amount,Advance Status\nAAA-1,\"$200.00\",\"$180.00\",Active\n")
+            .expect("Failed to write test file");
+        fs::remove_file(&missing_file_1).ok();
+        fs::remove_file(&missing_file_2).ok();
+
+        let parser = ClearViewDailyParser::new(vec![
+            good_file.clone(),
+            missing_file_1.clone(),
+            missing_file_2.clone(),
+        ]);
+        let err = parser.process_parallel().expect_err("expected missing files to fail");
+        let message = err.to_string();
+
+        assert!(message.contains(&missing_file_1.display().to_string()));
+        assert!(message.contains(&missing_file_2.display().to_string()));
+        assert!(message.contains("2 of 3 files failed"));
+
+        fs::remove_file(&good_file).ok();
+    }
 }
\ No newline at end of file
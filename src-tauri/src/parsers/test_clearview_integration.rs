@@ -3,6 +3,7 @@ mod tests {
     use super::super::clearview_weekly_parser::ClearViewWeeklyParser;
     use super::super::clearview_pivot_processor::ClearViewPivotProcessor;
     use crate::file_handler::{get_excelerate_dir, ensure_directories};
+    use rust_decimal::Decimal;
     use std::path::Path;
     use std::fs;
     
@@ -29,9 +30,9 @@ DEAL001,"$500.00","$50.00","$450.00"
         
         // Verify the pivot table has correct data
         assert_eq!(pivot.rows.len(), 3); // 2 deals + 1 totals row
-        assert_eq!(pivot.total_gross, 4000.0);
-        assert_eq!(pivot.total_fee, 400.0);
-        assert_eq!(pivot.total_net, 3600.0);
+        assert_eq!(pivot.total_gross, Decimal::new(400000, 2));
+        assert_eq!(pivot.total_fee, Decimal::new(40000, 2));
+        assert_eq!(pivot.total_net, Decimal::new(360000, 2));
         
         // Test the pivot processor saves to file system
         let processor = ClearViewPivotProcessor::new(
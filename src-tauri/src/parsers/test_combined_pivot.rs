@@ -3,6 +3,7 @@ mod tests {
     use super::super::clearview_pivot_processor::ClearViewPivotProcessor;
     use super::super::base_parser::PivotTable;
     use crate::file_handler::{get_excelerate_dir, ensure_directories};
+    use rust_decimal::Decimal;
     use std::fs;
     use std::path::PathBuf;
     
@@ -96,9 +97,9 @@ Totals,,4500.00,450.00,4050.00"#;
         // Daily: 3000 + Weekly: 4500 = 7500 total gross
         // But DEAL002 appears in both, so it should be:
         // DEAL001: 1000, DEAL002: 2000 + 1500 = 3500, DEAL003: 3000 = Total: 7500
-        assert_eq!(combined_pivot.total_gross, 7500.0, "Combined gross total incorrect");
-        assert_eq!(combined_pivot.total_fee, 750.0, "Combined fee total incorrect");
-        assert_eq!(combined_pivot.total_net, 6750.0, "Combined net total incorrect");
+        assert_eq!(combined_pivot.total_gross, Decimal::new(750000, 2), "Combined gross total incorrect");
+        assert_eq!(combined_pivot.total_fee, Decimal::new(75000, 2), "Combined fee total incorrect");
+        assert_eq!(combined_pivot.total_net, Decimal::new(675000, 2), "Combined net total incorrect");
         
         // Read the saved combined file and verify content
         let saved_content = fs::read_to_string(&expected_combined_path)
@@ -2,6 +2,7 @@
 mod tests {
     use super::super::inadv_parser::InAdvParser;
     use super::super::base_parser::BaseParser;
+    use rust_decimal::Decimal;
     use std::path::PathBuf;
 
     #[test]
@@ -18,14 +19,14 @@ mod tests {
             Ok(pivot) => {
                 // Verify we got the expected results
                 assert!(pivot.rows.len() > 0, "Should have at least one row");
-                assert!(pivot.total_gross > 0.0, "Total gross should be positive");
-                assert!(pivot.total_fee > 0.0, "Total fees should be positive");
-                assert!(pivot.total_net > 0.0, "Total net should be positive");
-                
+                assert!(pivot.total_gross > Decimal::ZERO, "Total gross should be positive");
+                assert!(pivot.total_fee > Decimal::ZERO, "Total fees should be positive");
+                assert!(pivot.total_net > Decimal::ZERO, "Total net should be positive");
+
                 // Check that totals roughly match (gross - fees = net)
                 let calculated_net = pivot.total_gross - pivot.total_fee;
                 let diff = (calculated_net - pivot.total_net).abs();
-                assert!(diff < 0.01, "Net should equal gross minus fees");
+                assert!(diff < Decimal::new(1, 2), "Net should equal gross minus fees");
                 
                 println!("✅ InAdvance parser test passed!");
                 println!("Total Gross: ${:.2}", pivot.total_gross);
@@ -0,0 +1,103 @@
+//! Retry-with-backoff for fallible file operations that fail transiently —
+//! most commonly a workbook still held open by Excel or a sync client
+//! (`open_workbook` returning a sharing violation on Windows) — rather than
+//! permanently, like a genuinely malformed file. Retrying a permanent
+//! failure just wastes time, so [`retry_with_backoff`] only retries errors
+//! [`is_transient_file_error`] recognizes.
+
+use std::thread;
+use std::time::Duration;
+
+/// How a retried operation backs off between attempts: delay doubles each
+/// attempt starting from `base_delay_ms`, capped at `max_delay_ms`, with a
+/// random jitter added so several callers retrying at once don't all wake
+/// up and collide on the same file at the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    /// 5 attempts, 100ms/200ms/400ms/800ms delays capped at 2s — long enough
+    /// for a sync client to release a lock, short enough not to stall an
+    /// upload that's genuinely failing.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay_ms: 100,
+            max_delay_ms: 2000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exponential = self.base_delay_ms.saturating_mul(1u64 << attempt.min(32));
+        let capped = exponential.min(self.max_delay_ms);
+        let jitter = jitter_ms(capped / 4);
+        Duration::from_millis(capped.saturating_add(jitter))
+    }
+}
+
+/// A small pseudo-random delay in `0..=max_jitter_ms`, seeded from the
+/// process's randomized `HashMap` seed plus the current time. Good enough to
+/// spread out retries; not meant to be cryptographically random.
+fn jitter_ms(max_jitter_ms: u64) -> u64 {
+    if max_jitter_ms == 0 {
+        return 0;
+    }
+
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let now_nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(now_nanos);
+    hasher.finish() % (max_jitter_ms + 1)
+}
+
+/// Whether `error`'s message looks like a transient "file is locked/busy"
+/// failure (a sharing violation, the file being open elsewhere, a
+/// permission error from a sync client briefly holding it) rather than a
+/// genuine format/content problem. Errors from this codebase's file
+/// operations don't carry a structured "locked" variant, so this matches on
+/// the wording the underlying OS/library errors use.
+pub fn is_transient_file_error<E: std::fmt::Display>(error: &E) -> bool {
+    let message = error.to_string().to_lowercase();
+    message.contains("sharing violation")
+        || message.contains("being used by another process")
+        || message.contains("permission denied")
+        || message.contains("access is denied")
+        || message.contains("resource busy")
+        || message.contains("locked")
+}
+
+/// Run `op`, retrying per `policy` as long as the error [`is_transient_file_error`]
+/// and attempts remain. A non-transient error, or a transient one on the
+/// final attempt, is returned immediately.
+pub fn retry_with_backoff<F, T, E>(mut op: F, policy: RetryPolicy) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+    E: std::fmt::Display,
+{
+    let mut attempt = 0u32;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                let attempts_remaining = attempt + 1 < policy.max_attempts;
+                if !attempts_remaining || !is_transient_file_error(&error) {
+                    return Err(error);
+                }
+                thread::sleep(policy.delay_for_attempt(attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
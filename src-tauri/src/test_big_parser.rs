@@ -1,8 +1,187 @@
 #[cfg(test)]
 mod tests {
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
+    use rust_xlsxwriter::Workbook;
     use crate::parsers::{BigParser, BaseParser};
 
+    /// One BIG-style data row, addressed by field rather than column index so
+    /// the fixtures below read the same way `process_sheet_data_deduped` does.
+    struct BigRow {
+        advance_id: &'static str,
+        merchant_name: &'static str,
+        total: f64,
+        fee: f64,
+    }
+
+    /// Build a synthetic BIG workbook at a temp path: each `sheets` entry is
+    /// `(sheet_name, rows)`. The sheet name must contain "R&H" or "White
+    /// Rabbit" for `detect_portfolio_sheets` to pick it up, and the header
+    /// row carries the same "funding"/"business"/"total"/"servicing fee"
+    /// aliases `BigParser::column_specs` looks for, at columns far enough
+    /// apart that they can't be mistaken for the AJ:AP daily-payment columns
+    /// `process_sheet_data_deduped` falls back to summing.
+    fn write_big_workbook(path: &Path, sheets: &[(&str, Vec<BigRow>)]) {
+        let mut workbook = Workbook::new();
+
+        for (sheet_name, rows) in sheets {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(*sheet_name).expect("valid sheet name");
+
+            worksheet.write(0, 0, "Funding ID").unwrap();
+            worksheet.write(0, 2, "Business Name").unwrap();
+            worksheet.write(0, 34, "Total").unwrap();
+            worksheet.write(0, 45, "Servicing Fee").unwrap();
+
+            for (row_index, row) in rows.iter().enumerate() {
+                let excel_row = (row_index + 1) as u32;
+                worksheet.write(excel_row, 0, row.advance_id).unwrap();
+                worksheet.write(excel_row, 2, row.merchant_name).unwrap();
+                worksheet.write(excel_row, 34, row.total).unwrap();
+                worksheet.write(excel_row, 45, row.fee).unwrap();
+            }
+        }
+
+        workbook.save(path).expect("failed to write fixture workbook");
+    }
+
+    fn temp_workbook_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}.xlsx", name))
+    }
+
+    #[test]
+    fn test_duplicate_advance_id_collapsed_as_confirmed_duplicate() {
+        let path = temp_workbook_path("test_big_parser_confirmed_duplicate");
+        write_big_workbook(&path, &[(
+            "R&H Daily",
+            vec![
+                BigRow { advance_id: "100", merchant_name: "Acme Corp", total: 100.0, fee: 10.0 },
+                // Same advance_id with identical gross/fee/net: a confirmed
+                // duplicate, e.g. the same advance repeated across tabs.
+                BigRow { advance_id: "100", merchant_name: "Acme Corp", total: 100.0, fee: 10.0 },
+            ],
+        )]);
+
+        let parser = BigParser::new();
+        let result = parser.validate_file_structure(&path);
+
+        assert!(result.is_valid);
+        assert!(result.warnings.iter().any(|w| w.contains("Collapsed 1 duplicate")));
+
+        let pivot = parser.process(&path).expect("process should succeed");
+        let advance_rows: Vec<_> = pivot.rows.iter().filter(|r| r.advance_id == "100").collect();
+        assert_eq!(advance_rows.len(), 1, "duplicate row should be collapsed, not double-counted");
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_conflicting_reupload_kept_with_warning() {
+        let path = temp_workbook_path("test_big_parser_conflicting");
+        write_big_workbook(&path, &[(
+            "R&H Daily",
+            vec![
+                BigRow { advance_id: "200", merchant_name: "Beta LLC", total: 50.0, fee: 5.0 },
+                // Same advance_id, different amounts: a likely corrected
+                // re-upload, so both rows are kept (summed) rather than the
+                // second silently dropped as a duplicate.
+                BigRow { advance_id: "200", merchant_name: "Beta LLC", total: 60.0, fee: 5.0 },
+            ],
+        )]);
+
+        let parser = BigParser::new();
+        let result = parser.validate_file_structure(&path);
+
+        assert!(result.is_valid);
+        assert!(result.warnings.iter().any(|w| {
+            w.contains("Advance 200") && w.contains("more than once with different amounts")
+        }));
+
+        let pivot = parser.process(&path).expect("process should succeed");
+        let row = pivot.rows.iter().find(|r| r.advance_id == "200").expect("advance 200 present");
+        // Both conflicting rows were kept and summed: gross 50 + 60 = 110.
+        assert_eq!(row.sum_of_syn_gross_amount, rust_decimal::Decimal::new(11000, 2));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_zero_net_nonzero_gross_reversal_row_survives_skip_guard() {
+        let path = temp_workbook_path("test_big_parser_zero_net_skip_guard");
+        write_big_workbook(&path, &[(
+            "R&H Daily",
+            vec![
+                // Gross and fee fully offset to a zero net, and the merchant
+                // name is blank — the skip guard must only drop a row when
+                // gross *and* fee *and* merchant name are all empty, so this
+                // row (nonzero gross) has to survive it.
+                BigRow { advance_id: "300", merchant_name: "", total: 100.0, fee: 100.0 },
+            ],
+        )]);
+
+        let parser = BigParser::new();
+        let pivot = parser.process(&path).expect("process should succeed");
+
+        let row = pivot.rows.iter().find(|r| r.advance_id == "300").expect("advance 300 should survive the skip guard");
+        assert_eq!(row.sum_of_syn_net_amount, rust_decimal::Decimal::ZERO);
+        assert_eq!(row.sum_of_syn_gross_amount, rust_decimal::Decimal::new(10000, 2));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_process_all_splits_rows_by_portfolio_sheet() {
+        let path = temp_workbook_path("test_big_parser_process_all");
+        write_big_workbook(&path, &[
+            ("R&H Daily", vec![
+                BigRow { advance_id: "400", merchant_name: "Alder Advance", total: 100.0, fee: 10.0 },
+            ]),
+            ("White Rabbit Daily", vec![
+                BigRow { advance_id: "500", merchant_name: "White Rabbit Advance", total: 200.0, fee: 20.0 },
+            ]),
+        ]);
+
+        let parser = BigParser::new();
+        let pivots = parser.process_all(&path).expect("process_all should succeed");
+
+        assert_eq!(pivots.keys().cloned().collect::<std::collections::HashSet<_>>(),
+            ["Alder".to_string(), "White Rabbit".to_string()].into_iter().collect());
+
+        let alder = &pivots["Alder"];
+        assert!(alder.rows.iter().any(|r| r.advance_id == "400"));
+        assert!(!alder.rows.iter().any(|r| r.advance_id == "500"));
+
+        let white_rabbit = &pivots["White Rabbit"];
+        assert!(white_rabbit.rows.iter().any(|r| r.advance_id == "500"));
+        assert!(!white_rabbit.rows.iter().any(|r| r.advance_id == "400"));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    /// Regression test for the `detect_parser`/`ParserRegistry` bug fixed
+    /// alongside `BigParser::detection_score`: a BIG workbook used to detect
+    /// fine through `detect_funder` (which uses `matches_file`) but never
+    /// through `detect_parser` or `ParserRegistry::detect` (which previously
+    /// excluded `BigParser` entirely), so a folder dropped into
+    /// `process_batch` could never auto-detect a BIG file.
+    #[test]
+    fn test_big_parser_detectable_through_detect_parser_and_registry() {
+        let path = temp_workbook_path("test_big_parser_detection");
+        write_big_workbook(&path, &[(
+            "R&H Daily",
+            vec![BigRow { advance_id: "600", merchant_name: "Gamma Inc", total: 100.0, fee: 10.0 }],
+        )]);
+
+        assert!(BigParser::new().detection_score(&path) >= 1.0);
+
+        let detected = crate::parsers::detect_parser(&path).expect("detect_parser should find BigParser");
+        assert_eq!(detected.get_funder_name(), "BIG");
+
+        let registry = crate::parsers::ParserRegistry::with_builtin_parsers();
+        assert_eq!(registry.detect(&path).as_deref(), Some("BIG"));
+
+        std::fs::remove_file(&path).ok();
+    }
+
     #[test]
     fn test_big_parser() {
         let file_path = Path::new("../examples/BIG-AL 09-05-25.xlsx");
@@ -0,0 +1,185 @@
+//! Chunked-upload staging so a large funder workbook doesn't have to cross
+//! the webview-to-Rust IPC boundary (and sit in memory) as one giant
+//! `Vec<u8>`. A session is opened with [`begin_upload`], fed sequential byte
+//! ranges with [`push_upload_chunk`] (which also keeps a running content
+//! hash, so [`finish_upload`] never has to re-hash the whole file), and
+//! either finalized with [`finish_upload`] or discarded with
+//! [`abort_upload`]. A session that's neither finished nor aborted is
+//! garbage-collected by the first function here to run after it has been
+//! idle longer than [`SESSION_TTL`].
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::file_handler::get_excelerate_dir;
+
+/// A session whose last chunk arrived more than this long ago is dropped
+/// (and its temp file deleted) the next time any function here runs, so an
+/// abandoned upload doesn't leak disk space forever.
+const SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// A single upload can't exceed this many bytes; guards against a runaway
+/// or misbehaving client filling disk via endless `push_upload_chunk` calls.
+const MAX_UPLOAD_BYTES: u64 = 2 * 1024 * 1024 * 1024; // 2 GiB
+
+/// The same fields [`crate::file_handler::save_funder_upload`] takes, minus
+/// the bytes themselves — those arrive separately via `push_upload_chunk`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadMetadata {
+    pub portfolio_name: String,
+    pub funder_name: String,
+    pub file_name: String,
+    pub report_date: String,
+    pub upload_type: String,
+}
+
+/// What [`finish_upload`] hands back to the caller once a session's chunks
+/// are fully assembled: the metadata it was opened with, the temp file
+/// holding the bytes, and the hash accumulated across every chunk.
+pub struct AssembledUpload {
+    pub metadata: UploadMetadata,
+    pub temp_path: PathBuf,
+    pub file_size: i64,
+    pub content_sha256: String,
+    pub content_md5: String,
+}
+
+struct UploadSession {
+    metadata: UploadMetadata,
+    temp_path: PathBuf,
+    bytes_written: u64,
+    sha256: Sha256,
+    md5: md5::Context,
+    last_activity: Instant,
+}
+
+lazy_static::lazy_static! {
+    static ref SESSIONS: Mutex<HashMap<String, UploadSession>> = Mutex::new(HashMap::new());
+}
+
+/// Drop every session whose last chunk arrived more than [`SESSION_TTL`]
+/// ago, deleting its temp file. Called at the top of every public function
+/// here so an idle session never needs its own background sweeper.
+fn gc_expired(sessions: &mut HashMap<String, UploadSession>) {
+    let expired: Vec<String> = sessions
+        .iter()
+        .filter(|(_, session)| session.last_activity.elapsed() > SESSION_TTL)
+        .map(|(token, _)| token.clone())
+        .collect();
+
+    for token in expired {
+        if let Some(session) = sessions.remove(&token) {
+            let _ = fs::remove_file(&session.temp_path);
+        }
+    }
+}
+
+fn temp_dir() -> Result<PathBuf, String> {
+    let dir = get_excelerate_dir()?.join(".upload_tmp");
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create upload temp directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Open a new chunked-upload session and return its token. No bytes are
+/// written until the first [`push_upload_chunk`] call.
+pub fn begin_upload(metadata: UploadMetadata) -> Result<String, String> {
+    let mut sessions = SESSIONS.lock().unwrap();
+    gc_expired(&mut sessions);
+
+    let token = Uuid::new_v4().to_string();
+    let temp_path = temp_dir()?.join(&token);
+    File::create(&temp_path).map_err(|e| format!("Failed to create upload temp file: {}", e))?;
+
+    sessions.insert(
+        token.clone(),
+        UploadSession {
+            metadata,
+            temp_path,
+            bytes_written: 0,
+            sha256: Sha256::new(),
+            md5: md5::Context::new(),
+            last_activity: Instant::now(),
+        },
+    );
+
+    Ok(token)
+}
+
+/// Append `bytes` to `token`'s temp file and fold them into its running
+/// hash. `offset` must equal the number of bytes already written, so a
+/// retried or out-of-order chunk is rejected rather than silently
+/// corrupting the assembled file.
+pub fn push_upload_chunk(token: &str, offset: u64, bytes: &[u8]) -> Result<(), String> {
+    let mut sessions = SESSIONS.lock().unwrap();
+    gc_expired(&mut sessions);
+
+    let session = sessions
+        .get_mut(token)
+        .ok_or_else(|| "Unknown or expired upload token".to_string())?;
+
+    if offset != session.bytes_written {
+        return Err(format!(
+            "Out-of-order chunk: expected offset {}, got {}",
+            session.bytes_written, offset
+        ));
+    }
+
+    if session.bytes_written + bytes.len() as u64 > MAX_UPLOAD_BYTES {
+        return Err(format!("Upload exceeds the {}-byte limit", MAX_UPLOAD_BYTES));
+    }
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(&session.temp_path)
+        .map_err(|e| format!("Failed to open upload temp file: {}", e))?;
+    file.write_all(bytes)
+        .map_err(|e| format!("Failed to write upload chunk: {}", e))?;
+
+    session.sha256.update(bytes);
+    session.md5.consume(bytes);
+    session.bytes_written += bytes.len() as u64;
+    session.last_activity = Instant::now();
+
+    Ok(())
+}
+
+/// Remove `token`'s session and delete its temp file without processing it.
+pub fn abort_upload(token: &str) -> Result<(), String> {
+    let mut sessions = SESSIONS.lock().unwrap();
+    gc_expired(&mut sessions);
+
+    if let Some(session) = sessions.remove(token) {
+        let _ = fs::remove_file(&session.temp_path);
+    }
+
+    Ok(())
+}
+
+/// Finalize `token`'s session: remove it from the registry and return its
+/// metadata, temp file path, size, and the hash accumulated across every
+/// `push_upload_chunk` call. The caller is responsible for moving the temp
+/// file into the blob store and deleting it once done.
+pub fn finish_upload(token: &str) -> Result<AssembledUpload, String> {
+    let mut sessions = SESSIONS.lock().unwrap();
+    gc_expired(&mut sessions);
+
+    let session = sessions
+        .remove(token)
+        .ok_or_else(|| "Unknown or expired upload token".to_string())?;
+
+    Ok(AssembledUpload {
+        file_size: session.bytes_written as i64,
+        content_sha256: format!("{:x}", session.sha256.finalize()),
+        content_md5: format!("{:x}", session.md5.compute()),
+        metadata: session.metadata,
+        temp_path: session.temp_path,
+    })
+}
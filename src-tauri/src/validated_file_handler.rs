@@ -1,14 +1,14 @@
 use std::fs;
 use std::path::Path;
 use serde::{Serialize, Deserialize};
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use crate::file_handler::{
     save_funder_upload as original_save_funder_upload,
     save_portfolio_workbook_with_version as original_save_portfolio_workbook,
     UploadResponse
 };
 use crate::parsers::{BaseParser, BhbParser, BigParser, BoomParser, EfinParser, InAdvParser, KingsParser};
-use crate::notification::{NotificationManager, ValidationResult};
+use crate::notification::{FileValidator, NotificationManager, ValidationResult};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidatedUploadResponse {
@@ -54,12 +54,28 @@ pub async fn save_funder_upload_validated(
         .map_err(|e| format!("Failed to write temporary file: {}", e))?;
     
     // Validate based on funder type (skip Clear View for now as it has special handling)
-    let validation_result = if funder_name == "Clear View" || funder_name == "ClearView" {
+    let mut validation_result = if funder_name == "Clear View" || funder_name == "ClearView" {
         ValidationResult::valid()
     } else {
-        validate_funder_file(&funder_name, &temp_path)?
+        validate_against_funder(&funder_name, &temp_path)?
     };
-    
+
+    // Cross-check the user-supplied funder against what the file's content
+    // actually looks like, so a misfiled document gets caught before it's
+    // parsed as the wrong funder's layout rather than just silently
+    // producing wrong totals.
+    if funder_name != "Clear View" && funder_name != "ClearView" {
+        match crate::parsers::detect_funder(&temp_path) {
+            Ok(detected_funder) if detected_funder != funder_name => {
+                validation_result.add_warning(format!(
+                    "Selected funder is '{}' but this file looks like a '{}' statement",
+                    funder_name, detected_funder
+                ));
+            }
+            _ => {}
+        }
+    }
+
     // Clean up temp file
     let _ = fs::remove_file(&temp_path);
     
@@ -184,38 +200,73 @@ pub async fn save_portfolio_workbook_validated(
     }
 }
 
-/// Validate a funder file based on the funder type
-fn validate_funder_file(funder_name: &str, file_path: &Path) -> Result<ValidationResult, String> {
-    let validation_result = match funder_name {
-        "BHB" => {
-            let parser = BhbParser::new();
-            parser.validate_file_structure(file_path)
-        }
-        "BIG" => {
-            let parser = BigParser::new();
-            parser.validate_file_structure(file_path)
-        }
-        "eFin" => {
-            let parser = EfinParser::new();
-            parser.validate_file_structure(file_path)
-        }
-        "InAdvance" => {
-            let parser = InAdvParser::new();
-            parser.validate_file_structure(file_path)
-        }
-        "Kings" => {
-            let parser = KingsParser::new();
-            parser.validate_file_structure(file_path)
-        }
-        "Boom" => {
-            let parser = BoomParser::new();
-            parser.validate_file_structure(file_path)
-        }
-        _ => {
-            // Unknown funder, skip validation
-            ValidationResult::valid()
+/// A [`FileValidator`] per funder, each a thin wrapper around that funder's
+/// `BaseParser::validate_file_structure` so validation can run (and be
+/// swapped out per funder) without pulling in the rest of `BaseParser` or
+/// computing a pivot table.
+macro_rules! parser_file_validator {
+    ($name:ident, $parser:ty) => {
+        struct $name;
+
+        impl FileValidator for $name {
+            fn validate(&self, file_path: &Path) -> Result<ValidationResult, String> {
+                Ok(<$parser>::new().validate_file_structure(file_path))
+            }
         }
     };
-    
-    Ok(validation_result)
+}
+
+parser_file_validator!(BhbFileValidator, BhbParser);
+parser_file_validator!(BigFileValidator, BigParser);
+parser_file_validator!(EfinFileValidator, EfinParser);
+parser_file_validator!(InAdvFileValidator, InAdvParser);
+parser_file_validator!(KingsFileValidator, KingsParser);
+parser_file_validator!(BoomFileValidator, BoomParser);
+
+/// The [`FileValidator`] registered for `funder_name`, or `None` for a
+/// funder (e.g. Clear View) that has no structural pre-check.
+fn file_validator_for_funder(funder_name: &str) -> Option<Box<dyn FileValidator>> {
+    match funder_name {
+        "BHB" => Some(Box::new(BhbFileValidator)),
+        "BIG" => Some(Box::new(BigFileValidator)),
+        "eFin" => Some(Box::new(EfinFileValidator)),
+        "InAdvance" => Some(Box::new(InAdvFileValidator)),
+        "Kings" => Some(Box::new(KingsFileValidator)),
+        "Boom" => Some(Box::new(BoomFileValidator)),
+        _ => None,
+    }
+}
+
+/// Validate a funder file based on the funder type
+fn validate_against_funder(funder_name: &str, file_path: &Path) -> Result<ValidationResult, String> {
+    match file_validator_for_funder(funder_name) {
+        Some(validator) => validator.validate(file_path),
+        // Unknown funder (or one with no structural pre-check, e.g. Clear View): skip validation
+        None => Ok(ValidationResult::valid()),
+    }
+}
+
+/// Run pre-flight validation for `file_path` against `funder_name`'s
+/// [`FileValidator`] without computing a pivot table, pushing the result
+/// through [`NotificationManager`] so the frontend's existing toast
+/// handling (manual dismissal for errors, timed duration for warnings) is
+/// reused instead of the caller building its own notification.
+#[tauri::command]
+pub fn validate_funder_file(
+    app_handle: AppHandle,
+    funder_name: String,
+    file_path: String,
+) -> Result<ValidationResult, String> {
+    let path = Path::new(&file_path);
+    if !path.exists() {
+        return Err("File not found".to_string());
+    }
+
+    let result = validate_against_funder(&funder_name, path)?;
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or(&file_path);
+    let notification = result.to_notification(file_name);
+    let _ = NotificationManager::send(&app_handle, notification);
+
+    Ok(result)
 }
\ No newline at end of file